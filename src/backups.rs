@@ -0,0 +1,144 @@
+use crate::{
+    helpers::{config::get_rimrs_dir, paths::push_backups_path},
+    serialization::ParseXMLError,
+    traits::PushChained,
+    ModsConfig,
+};
+use std::{
+    cmp::Reverse,
+    env::VarError,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How many old versions of `ModsConfig.xml` are kept by default.
+pub const DEFAULT_BACKUP_COUNT: usize = 10;
+
+const BACKUP_PREFIX: &str = "ModsConfig-";
+const BACKUP_EXTENSION: &str = ".xml";
+
+/// The folder of old versions of `ModsConfig.xml`, which the writer thread and the command line
+/// add to before each save.
+/// Backups are named after when they were made, and only the newest few are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backups {
+    dir: PathBuf,
+    keep: usize,
+}
+
+impl Backups {
+    #[must_use]
+    pub fn new(dir: PathBuf, keep: usize) -> Self {
+        Self { dir, keep }
+    }
+
+    /// The `backups` folder in [`get_rimrs_dir`], keeping [`DEFAULT_BACKUP_COUNT`] backups.
+    ///
+    /// # Errors
+    /// If the rimrs folder can't be found, see [`get_rimrs_dir`].
+    pub fn in_rimrs_dir() -> Result<Self, VarError> {
+        get_rimrs_dir().map(|dir| Self::new(push_backups_path(dir), DEFAULT_BACKUP_COUNT))
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Copies the file at `path` into the backups, then deletes the oldest backups past the limit.
+    /// Does nothing if there's no file there yet, or it's the same as the newest backup,
+    /// so saving without changes doesn't push the older versions out.
+    ///
+    /// # Errors
+    /// If it can't read the file, create the backups folder, write the backup, or delete old backups.
+    pub fn back_up<P: AsRef<Path>>(&self, path: P) -> io::Result<Option<Backup>> {
+        if self.keep == 0 || !path.as_ref().is_file() {
+            return Ok(None);
+        }
+        let contents = fs::read(path)?;
+        let newest = self.list()?.into_iter().next();
+        if let Some(newest) = newest.as_ref() {
+            if fs::read(&newest.path).is_ok_and(|backed_up| backed_up == contents) {
+                return Ok(None);
+            }
+        }
+        fs::create_dir_all(&self.dir)?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+        // always after the newest, even if saved twice in the same millisecond
+        let timestamp = match newest {
+            Some(newest) => now.max(newest.timestamp + 1),
+            None => now,
+        };
+        let backup = self.backup_at(timestamp);
+
+        fs::write(&backup.path, contents)?;
+        self.prune()?;
+        Ok(Some(backup))
+    }
+
+    /// Lists the backups, newest first.
+    /// There aren't any if the folder doesn't exist yet.
+    ///
+    /// # Errors
+    /// If it can't read the backups folder.
+    pub fn list(&self) -> io::Result<Vec<Backup>> {
+        if !self.dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let name = entry?.file_name();
+            let timestamp = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(BACKUP_PREFIX))
+                .and_then(|name| name.strip_suffix(BACKUP_EXTENSION))
+                .and_then(|timestamp| timestamp.parse().ok());
+            if let Some(timestamp) = timestamp {
+                backups.push(self.backup_at(timestamp));
+            }
+        }
+
+        backups.sort_by_key(|backup| Reverse(backup.timestamp));
+        Ok(backups)
+    }
+
+    fn prune(&self) -> io::Result<()> {
+        for old in self.list()?.iter().skip(self.keep) {
+            fs::remove_file(&old.path)?;
+        }
+        Ok(())
+    }
+
+    fn backup_at(&self, timestamp: u64) -> Backup {
+        Backup {
+            path: self
+                .dir
+                .clone()
+                .push_chained(format!("{BACKUP_PREFIX}{timestamp}{BACKUP_EXTENSION}")),
+            timestamp,
+        }
+    }
+}
+
+/// A single old version of `ModsConfig.xml`, see [`Backups`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// When it was backed up, in milliseconds since the unix epoch.
+    pub timestamp: u64,
+}
+
+impl Backup {
+    /// Reads the backed up `ModsConfig.xml`.
+    ///
+    /// # Errors
+    /// See [`ModsConfig::try_from`].
+    pub fn read(&self) -> Result<ModsConfig, ParseXMLError> {
+        ModsConfig::try_from(self.path.as_path())
+    }
+}
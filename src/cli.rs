@@ -0,0 +1,432 @@
+use crate::{
+    backups::Backups,
+    helpers::{config::SystemEnv, folders::Folder, paths::push_mods_config_path},
+    mods::Diagnostic,
+    serialization::{
+        import::{import_file, ImportError, ImportResult, Imported},
+        settings::SettingsError,
+        ParseXMLError,
+    },
+    sort_lenient,
+    traits::LockIgnorePoisoned,
+    validate_for_version,
+    writer_thread::write_mods_config_file,
+    ModList, ModsConfigFile, Settings, SortError, SortMode,
+};
+use serde_json::{json, Value};
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+pub const USAGE: &str = "\
+Usage: rimrs [options] [command]
+
+Launches the GUI if no command is given.
+
+Commands:
+    list                List active and inactive mods
+    sort [--stable]     Sort the active mods, keeping their current order where possible with --stable
+    validate            Check the active mods for problems (exit code 1 if any errors)
+    activate <pid>      Add a mod to the end of the active mods
+    deactivate <pid>    Remove a mod from the active mods
+    export <file>       Write the active mods to a mod list file
+    import <file>       Replace the active mods with those in a mod list file
+                        (ModsConfig.xml, a RimPy or Mod Manager list, or a save)
+    help                Show this message
+
+Options:
+    --settings <file>   Use the given settings.ini instead of the default one
+    --dry-run           Don't write any changes to ModsConfig.xml
+    --keep-missing      Keep imported mods that aren't installed (exit code 1 if any are left out)
+
+Output is JSON on stdout; errors are JSON on stderr.";
+
+/// Exit code when the command succeeded.
+pub const EXIT_OK: i32 = 0;
+/// Exit code when `validate` found errors in the mod list,
+/// or `import` left out mods that aren't installed.
+pub const EXIT_INVALID: i32 = 1;
+/// Exit code when the command couldn't be run.
+pub const EXIT_ERROR: i32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    List,
+    Sort(SortMode),
+    Validate,
+    Activate(String),
+    Deactivate(String),
+    Export(PathBuf),
+    Import {
+        path: PathBuf,
+        /// Whether to keep mods that aren't installed in the list.
+        keep_missing: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub settings: Option<PathBuf>,
+    pub dry_run: bool,
+    pub command: Command,
+}
+
+impl Args {
+    /// Parses the command line arguments, excluding the program name.
+    ///
+    /// # Errors
+    /// [`CliError::Usage`] if the arguments aren't valid.
+    pub fn parse<I, S>(args: I) -> Result<Self, CliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut args = args.into_iter().map(Into::into);
+        let mut settings = None;
+        let mut dry_run = false;
+        let mut stable = false;
+        let mut keep_missing = false;
+        let mut command = None;
+
+        while let Some(arg) = args.next() {
+            let mut operand = |name: &str| {
+                args.next()
+                    .ok_or_else(|| CliError::Usage(format!("`{name}` expects an argument")))
+            };
+
+            let cmd = match arg.as_str() {
+                "--settings" => {
+                    settings = Some(PathBuf::from(operand("--settings")?));
+                    continue;
+                }
+                "--dry-run" => {
+                    dry_run = true;
+                    continue;
+                }
+                "--stable" => {
+                    stable = true;
+                    continue;
+                }
+                "--keep-missing" => {
+                    keep_missing = true;
+                    continue;
+                }
+                "help" | "--help" | "-h" => Command::Help,
+                "list" => Command::List,
+                "sort" => Command::Sort(SortMode::Alphabetical),
+                "validate" => Command::Validate,
+                "activate" => Command::Activate(operand("activate")?.to_lowercase()),
+                "deactivate" => Command::Deactivate(operand("deactivate")?.to_lowercase()),
+                "export" => Command::Export(PathBuf::from(operand("export")?)),
+                "import" => Command::Import {
+                    path: PathBuf::from(operand("import")?),
+                    keep_missing: false,
+                },
+                _ => return Err(CliError::Usage(format!("unknown argument `{arg}`"))),
+            };
+
+            if command.replace(cmd).is_some() {
+                return Err(CliError::Usage(String::from("expected a single command")));
+            }
+        }
+
+        let command = match command {
+            Some(Command::Sort(_)) if stable => Command::Sort(SortMode::Stable),
+            Some(_) if stable => {
+                return Err(CliError::Usage(String::from(
+                    "`--stable` only applies to `sort`",
+                )))
+            }
+            Some(command) => command,
+            None => return Err(CliError::Usage(String::from("no command given"))),
+        };
+        let command = match command {
+            Command::Import { path, .. } if keep_missing => Command::Import {
+                path,
+                keep_missing: true,
+            },
+            _ if keep_missing => {
+                return Err(CliError::Usage(String::from(
+                    "`--keep-missing` only applies to `import`",
+                )))
+            }
+            command => command,
+        };
+
+        Ok(Self {
+            settings,
+            dry_run,
+            command,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("couldn't read settings: {0}")]
+    Settings(#[from] SettingsError),
+    #[error("{0} not set, run rimrs without a command to set it up")]
+    MissingFolder(Folder),
+    #[error("couldn't read mod folders: {0}")]
+    ModList(io::Error),
+    #[error("couldn't read mod list: {0}")]
+    ModsConfig(#[from] ParseXMLError),
+    #[error("couldn't import: {0}")]
+    Import(#[from] ImportError),
+    #[error("{0} has load order rules, not a mod list")]
+    NotAModList(PathBuf),
+    #[error("couldn't sort: {0}")]
+    Sort(#[from] SortError),
+    #[error("mod `{0}` isn't installed")]
+    UnknownMod(String),
+    #[error("couldn't write {0}: {1}")]
+    Write(PathBuf, io::Error),
+    #[error("couldn't back up ModsConfig.xml: {0}")]
+    Backup(io::Error),
+}
+
+/// Runs the command line interface, writing JSON output to `out` and errors to `err`.
+/// `ModsConfig.xml` is backed up to the same place as the GUI backs it up to before it's changed.
+/// Returns the process exit code.
+pub fn run<I, S>(args: I, out: &mut impl Write, err: &mut impl Write) -> i32
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    run_with_backups(args, Backups::in_rimrs_dir().ok(), out, err)
+}
+
+/// Like [`run`], but backs up `ModsConfig.xml` to `backups` (if any) before it's changed.
+pub fn run_with_backups<I, S>(
+    args: I,
+    backups: Option<Backups>,
+    out: &mut impl Write,
+    err: &mut impl Write,
+) -> i32
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let res = Args::parse(args).and_then(|args| {
+        if args.command == Command::Help {
+            writeln!(out, "{USAGE}").ok();
+            Ok(EXIT_OK)
+        } else {
+            let ctx = Context::load(args.settings.as_deref(), backups)?;
+            let (code, output) = ctx.run(&args.command, args.dry_run)?;
+            serde_json::to_writer_pretty(&mut *out, &output).ok();
+            writeln!(out).ok();
+            Ok(code)
+        }
+    });
+
+    match res {
+        Ok(code) => code,
+        Err(e) => {
+            serde_json::to_writer(&mut *err, &json!({ "error": e.to_string() })).ok();
+            writeln!(err).ok();
+            if matches!(e, CliError::Usage(_)) {
+                writeln!(err, "{USAGE}").ok();
+            }
+            EXIT_ERROR
+        }
+    }
+}
+
+fn messages(diagnostics: Option<&Vec<Diagnostic>>) -> Vec<String> {
+    diagnostics
+        .into_iter()
+        .flatten()
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn diagnostic_json(diagnostic: &Diagnostic) -> Value {
+    json!({
+        "kind": diagnostic.kind(),
+        "severity": diagnostic.severity().to_string(),
+        "packageIds": diagnostic.package_ids(),
+        "message": diagnostic.to_string(),
+        "fix": diagnostic.suggested_fix().map(|fix| fix.to_string()),
+    })
+}
+
+/// Everything a command needs, loaded the same way the GUI does.
+struct Context {
+    mod_list: ModList,
+    config_file: ModsConfigFile,
+    mods_config_path: PathBuf,
+    backups: Option<Backups>,
+}
+
+impl Context {
+    fn load(settings_path: Option<&Path>, backups: Option<Backups>) -> Result<Self, CliError> {
+        let settings = match settings_path {
+            Some(path) => Settings::from_path(path).map_err(SettingsError::from)?,
+            None => Settings::load()?.with_discovered_folders(&SystemEnv),
+        };
+
+        let mods_config_path = push_mods_config_path(
+            settings
+                .folders
+                .config_folder
+                .clone()
+                .ok_or(CliError::MissingFolder(Folder::Config))?,
+        );
+        let config_file = ModsConfigFile::try_from(mods_config_path.as_path())?;
+        let mod_list = ModList::try_from(&settings).map_err(CliError::ModList)?;
+
+        Ok(Self {
+            mod_list,
+            config_file,
+            mods_config_path,
+            backups,
+        })
+    }
+
+    fn run(self, command: &Command, dry_run: bool) -> Result<(i32, Value), CliError> {
+        match command {
+            Command::Help => Ok((EXIT_OK, Value::Null)),
+            Command::List => Ok((EXIT_OK, self.list())),
+            Command::Validate => Ok(self.validate()),
+            Command::Sort(mode) => {
+                let report = sort_lenient(
+                    &self.config_file.mods_config.activeMods,
+                    &self.mod_list.mods,
+                    self.config_file.mods_config.version.as_deref(),
+                    *mode,
+                )?;
+                let ignored: Vec<_> = report.ignored.iter().map(ToString::to_string).collect();
+                let moved: Vec<_> = report.moved.iter().map(ToString::to_string).collect();
+                let (code, mut output) = self.set_active_mods(report.sorted, dry_run)?;
+                output["ignored"] = json!(ignored);
+                if *mode == SortMode::Stable {
+                    output["moved"] = json!(moved);
+                }
+                Ok((code, output))
+            }
+            Command::Activate(pid) => {
+                if !self.is_installed(pid) {
+                    return Err(CliError::UnknownMod(pid.clone()));
+                }
+                let mut active = self.config_file.mods_config.activeMods.clone();
+                if !active.contains(pid) {
+                    active.push(pid.clone());
+                }
+                self.set_active_mods(active, dry_run)
+            }
+            Command::Deactivate(pid) => {
+                let mut active = self.config_file.mods_config.activeMods.clone();
+                active.retain(|m| m != pid);
+                self.set_active_mods(active, dry_run)
+            }
+            Command::Import { path, keep_missing } => {
+                let Imported::ModList(mods) = import_file(path)? else {
+                    return Err(CliError::NotAModList(path.clone()));
+                };
+                let result = ImportResult::new(mods, &self.mod_list.mods.lock_ignore_poisoned());
+                let missing: Vec<_> = result
+                    .missing
+                    .iter()
+                    .map(|m| json!({ "packageId": m.package_id, "name": m.name }))
+                    .collect();
+                let code = if missing.is_empty() || *keep_missing {
+                    EXIT_OK
+                } else {
+                    EXIT_INVALID
+                };
+
+                let (_, mut output) =
+                    self.set_active_mods(result.into_mod_list(*keep_missing), dry_run)?;
+                output["missing"] = json!(missing);
+                Ok((code, output))
+            }
+            Command::Export(path) => {
+                write_mods_config_file(path, &self.config_file)
+                    .map_err(|e| CliError::Write(path.clone(), e))?;
+                Ok((
+                    EXIT_OK,
+                    json!({
+                        "exported": path,
+                        "active": self.config_file.mods_config.activeMods,
+                    }),
+                ))
+            }
+        }
+    }
+
+    fn list(&self) -> Value {
+        let mut inactive: Vec<_> = self
+            .mod_list
+            .package_ids()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|pid| !self.config_file.mods_config.activeMods.contains(pid))
+            .collect();
+        inactive.sort();
+
+        let mods = self.mod_list.mods.lock_ignore_poisoned();
+        let entry = |pid: &String| {
+            let name = mods.get(pid).and_then(|mmd| mmd.name.clone());
+            json!({ "packageId": pid, "name": name })
+        };
+
+        json!({
+            "version": self.config_file.mods_config.version,
+            "active": self.config_file.mods_config.activeMods.iter().map(entry).collect::<Vec<_>>(),
+            "inactive": inactive.iter().map(entry).collect::<Vec<_>>(),
+        })
+    }
+
+    fn validate(&self) -> (i32, Value) {
+        let res = validate_for_version(
+            &self.mod_list.mods,
+            &self.config_file.mods_config.activeMods,
+            self.config_file.mods_config.version.as_deref(),
+        );
+        let code = if res.is_err() { EXIT_INVALID } else { EXIT_OK };
+        (
+            code,
+            json!({
+                "valid": !res.is_err(),
+                "warnings": messages(res.warnings()),
+                "errors": messages(res.errors()),
+                "diagnostics": res.diagnostics().map(diagnostic_json).collect::<Vec<_>>(),
+            }),
+        )
+    }
+
+    fn is_installed(&self, pid: &str) -> bool {
+        self.mod_list.mods.lock_ignore_poisoned().contains_key(pid)
+    }
+
+    fn set_active_mods(
+        mut self,
+        active: Vec<String>,
+        dry_run: bool,
+    ) -> Result<(i32, Value), CliError> {
+        self.config_file.mods_config.activeMods = active;
+        if !dry_run {
+            if let Some(backups) = &self.backups {
+                backups
+                    .back_up(&self.mods_config_path)
+                    .map_err(CliError::Backup)?;
+            }
+            write_mods_config_file(&self.mods_config_path, &self.config_file)
+                .map_err(|e| CliError::Write(self.mods_config_path.clone(), e))?;
+        }
+
+        Ok((
+            EXIT_OK,
+            json!({
+                "active": self.config_file.mods_config.activeMods,
+                "written": !dry_run,
+            }),
+        ))
+    }
+}
@@ -1,33 +1,272 @@
-use crate::helpers::paths::push_mod_lists_path;
+use crate::{
+    helpers::paths::{push_mod_lists_path, push_saves_path, push_settings_path},
+    traits::PushChained,
+};
 use std::{
+    collections::HashMap,
     env::{self, VarError},
+    hash::BuildHasher,
     path::PathBuf,
 };
 
+/// `RimWorld`'s steam app id, used for its workshop content folder.
+pub const RIMWORLD_APP_ID: &str = "294100";
+
+/// A source of environment variables.
+///
+/// Path discovery goes through this rather than [`env::var`] directly,
+/// so it can be pointed at a fake home directory.
+pub trait Env {
+    /// See [`env::var`].
+    ///
+    /// # Errors
+    /// If the variable isn't set (or isn't valid unicode).
+    fn var(&self, key: &str) -> Result<String, VarError>;
+
+    /// Gets the variable as a path, treating empty values as unset.
+    ///
+    /// # Errors
+    /// See [`Env::var`]
+    fn path(&self, key: &str) -> Result<PathBuf, VarError> {
+        match self.var(key) {
+            Ok(val) if val.is_empty() => Err(VarError::NotPresent),
+            res => res.map(PathBuf::from),
+        }
+    }
+}
+
+/// The actual process environment.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemEnv;
+
+impl Env for SystemEnv {
+    fn var(&self, key: &str) -> Result<String, VarError> {
+        env::var(key)
+    }
+}
+
+impl<S: BuildHasher> Env for HashMap<String, String, S> {
+    fn var(&self, key: &str) -> Result<String, VarError> {
+        self.get(key).cloned().ok_or(VarError::NotPresent)
+    }
+}
+
 /// Gets the `RimPy` config directory.
 /// Its main config file is in `config.ini`, and mod lists are in `ModLists/`
 ///
 /// # Errors
 /// See [`env::var`]
 pub fn get_config_dir() -> Result<PathBuf, VarError> {
-    get_config_dir_from_env()
+    get_config_dir_from_env(&SystemEnv)
 }
 
+/// Gets the `RimPy` config directory using the given [`Env`].
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
 #[cfg(target_os = "windows")]
-fn get_config_dir_from_env() -> Result<PathBuf, VarError> {
-    env::var("APPDATA").map(|path_raw| {
-        let mut path = PathBuf::from(path_raw);
-        path.pop();
-        path.push("LocalLow");
-        path.push("RimPy Mod Manager");
-        path
+pub fn get_config_dir_from_env(env: &impl Env) -> Result<PathBuf, VarError> {
+    local_low_dir(env).map(|path| path.push_chained("RimPy Mod Manager"))
+}
+
+/// Gets the `RimPy` config directory using the given [`Env`].
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
+#[cfg(target_os = "macos")]
+pub fn get_config_dir_from_env(env: &impl Env) -> Result<PathBuf, VarError> {
+    application_support_dir(env).map(|path| path.push_chained("RimPy Mod Manager"))
+}
+
+/// Gets the `RimPy` config directory using the given [`Env`].
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn get_config_dir_from_env(env: &impl Env) -> Result<PathBuf, VarError> {
+    unity_config_dir(env).map(|path| path.push_chained("RimPy Mod Manager"))
+}
+
+/// Gets the directory rimrs stores its own settings and data in.
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
+pub fn get_rimrs_dir() -> Result<PathBuf, VarError> {
+    get_rimrs_dir_from_env(&SystemEnv)
+}
+
+/// Gets the directory rimrs stores its own settings and data in using the given [`Env`].
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
+#[cfg(target_os = "windows")]
+pub fn get_rimrs_dir_from_env(env: &impl Env) -> Result<PathBuf, VarError> {
+    env.path("APPDATA").map(|path| path.push_chained("rimrs"))
+}
+
+/// Gets the directory rimrs stores its own settings and data in using the given [`Env`].
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
+#[cfg(target_os = "macos")]
+pub fn get_rimrs_dir_from_env(env: &impl Env) -> Result<PathBuf, VarError> {
+    application_support_dir(env).map(|path| path.push_chained("rimrs"))
+}
+
+/// Gets the directory rimrs stores its own settings and data in using the given [`Env`].
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn get_rimrs_dir_from_env(env: &impl Env) -> Result<PathBuf, VarError> {
+    env.path("XDG_CONFIG_HOME")
+        .or_else(|_| env.path("HOME").map(|home| home.push_chained(".config")))
+        .map(|path| path.push_chained("rimrs"))
+}
+
+/// Gets `RimWorld`'s own config directory, which contains `ModsConfig.xml`.
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
+pub fn get_game_config_dir() -> Result<PathBuf, VarError> {
+    get_game_config_dir_from_env(&SystemEnv)
+}
+
+/// Gets `RimWorld`'s own config directory using the given [`Env`].
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
+#[cfg(target_os = "windows")]
+pub fn get_game_config_dir_from_env(env: &impl Env) -> Result<PathBuf, VarError> {
+    local_low_dir(env).map(|path| {
+        path.push_chained("Ludeon Studios")
+            .push_chained("RimWorld by Ludeon Studios")
+            .push_chained("Config")
+    })
+}
+
+/// Gets `RimWorld`'s own config directory using the given [`Env`].
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
+#[cfg(target_os = "macos")]
+pub fn get_game_config_dir_from_env(env: &impl Env) -> Result<PathBuf, VarError> {
+    application_support_dir(env).map(|path| path.push_chained("RimWorld").push_chained("Config"))
+}
+
+/// Gets `RimWorld`'s own config directory using the given [`Env`].
+///
+/// # Errors
+/// If the variables needed to find the user's config directory aren't set.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn get_game_config_dir_from_env(env: &impl Env) -> Result<PathBuf, VarError> {
+    unity_config_dir(env).map(|path| {
+        path.push_chained("Ludeon Studios")
+            .push_chained("RimWorld by Ludeon Studios")
+            .push_chained("Config")
     })
 }
 
-#[cfg(not(target_os = "windows"))]
-fn get_config_dir_from_env() -> Result<PathBuf, VarError> {
-    log::error!("Unimplemented for operating systems other than Windows.");
-    Err(VarError::NotPresent)
+/// Gets the places Steam is usually installed to, most likely first.
+/// Doesn't check whether they exist.
+#[must_use]
+#[cfg(target_os = "windows")]
+pub fn steam_dir_candidates(env: &impl Env) -> Vec<PathBuf> {
+    ["ProgramFiles(x86)", "ProgramFiles"]
+        .into_iter()
+        .filter_map(|key| env.path(key).ok())
+        .map(|path| path.push_chained("Steam"))
+        .collect()
+}
+
+/// Gets the places Steam is usually installed to, most likely first.
+/// Doesn't check whether they exist.
+#[must_use]
+#[cfg(target_os = "macos")]
+pub fn steam_dir_candidates(env: &impl Env) -> Vec<PathBuf> {
+    application_support_dir(env)
+        .map(|path| vec![path.push_chained("Steam")])
+        .unwrap_or_default()
+}
+
+/// Gets the places Steam is usually installed to, most likely first.
+/// Doesn't check whether they exist.
+#[must_use]
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn steam_dir_candidates(env: &impl Env) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+
+    let data_home = env.path("XDG_DATA_HOME").or_else(|_| {
+        env.path("HOME")
+            .map(|home| home.push_chained(".local/share"))
+    });
+    if let Ok(data_home) = data_home {
+        candidates.push(data_home.push_chained("Steam"));
+    }
+
+    if let Ok(home) = env.path("HOME") {
+        candidates.push(home.clone().push_chained(".steam/steam"));
+        candidates.push(home.clone().push_chained(".steam/debian-installation"));
+        // flatpak
+        candidates.push(home.push_chained(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+    }
+
+    candidates
+}
+
+/// Finds `RimWorld`'s workshop content folder, where subscribed steam mods are downloaded to.
+#[must_use]
+pub fn find_workshop_dir(env: &impl Env) -> Option<PathBuf> {
+    steam_dir_candidates(env)
+        .into_iter()
+        .map(|steam| {
+            steam
+                .push_chained("steamapps/workshop/content")
+                .push_chained(RIMWORLD_APP_ID)
+        })
+        .find(|path| path.is_dir())
+}
+
+/// Finds the game folder of a steam install of `RimWorld`.
+///
+/// On macOS this is the `RimWorldMac.app` bundle, since that's where `Data/` and `Mods/` live.
+#[must_use]
+pub fn find_game_dir(env: &impl Env) -> Option<PathBuf> {
+    steam_dir_candidates(env)
+        .into_iter()
+        .map(|steam| {
+            let path = steam.push_chained("steamapps/common/RimWorld");
+            if cfg!(target_os = "macos") {
+                path.push_chained("RimWorldMac.app")
+            } else {
+                path
+            }
+        })
+        .find(|path| path.is_dir())
+}
+
+/// Windows' `AppData/LocalLow`, which unity games use to store their configs.
+#[cfg(target_os = "windows")]
+fn local_low_dir(env: &impl Env) -> Result<PathBuf, VarError> {
+    let mut path = env.path("APPDATA")?;
+    path.pop();
+    path.push("LocalLow");
+    Ok(path)
+}
+
+/// `~/Library/Application Support`
+#[cfg(target_os = "macos")]
+fn application_support_dir(env: &impl Env) -> Result<PathBuf, VarError> {
+    env.path("HOME")
+        .map(|home| home.push_chained("Library/Application Support"))
+}
+
+/// Linux equivalent of `LocalLow` for unity games, `$XDG_CONFIG_HOME/unity3d`.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn unity_config_dir(env: &impl Env) -> Result<PathBuf, VarError> {
+    env.path("XDG_CONFIG_HOME")
+        .or_else(|_| env.path("HOME").map(|home| home.push_chained(".config")))
+        .map(|path| path.push_chained("unity3d"))
 }
 
 /// Gets the `config.ini` path for rimpy.
@@ -40,6 +279,14 @@ pub fn get_config_ini_path() -> Result<PathBuf, VarError> {
     Ok(p)
 }
 
+/// Gets the path of rimrs's `settings.ini`.
+///
+/// # Errors
+/// See [`get_rimrs_dir`]
+pub fn get_settings_path() -> Result<PathBuf, VarError> {
+    get_rimrs_dir().map(push_settings_path)
+}
+
 /// Gets the `ModList` path
 ///
 /// # Errors
@@ -47,3 +294,14 @@ pub fn get_config_ini_path() -> Result<PathBuf, VarError> {
 pub fn get_mod_list_path() -> Result<PathBuf, VarError> {
     get_config_dir().map(push_mod_lists_path)
 }
+
+/// Gets the folder `RimWorld` keeps its saves in, next to its config directory.
+///
+/// # Errors
+/// See [`get_game_config_dir`]
+pub fn get_saves_path() -> Result<PathBuf, VarError> {
+    get_game_config_dir().map(|mut path| {
+        path.pop();
+        push_saves_path(path)
+    })
+}
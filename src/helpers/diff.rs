@@ -0,0 +1,240 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+/// A single entry in the diff between two lists, see [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change<T> {
+    Kept(T),
+    Added(T),
+    Removed(T),
+}
+
+impl<T> Change<T> {
+    #[must_use]
+    pub fn item(&self) -> &T {
+        match self {
+            Self::Kept(item) | Self::Added(item) | Self::Removed(item) => item,
+        }
+    }
+
+    /// Checks if this is an [`Change::Added`] or [`Change::Removed`].
+    #[must_use]
+    pub fn is_change(&self) -> bool {
+        !matches!(self, Self::Kept(_))
+    }
+}
+
+/// Diffs two lists using their longest common subsequence,
+/// so an item that moved shows up as removed from its old place and added at its new one.
+///
+/// # Examples
+/// ```
+/// use rimrs::helpers::diff::{diff, Change};
+///
+/// let changes = diff(&["a", "b", "c"], &["a", "c", "d"]);
+/// assert_eq!(
+///     changes,
+///     vec![
+///         Change::Kept("a"),
+///         Change::Removed("b"),
+///         Change::Kept("c"),
+///         Change::Added("d"),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn diff<T: Eq + Hash + Clone>(before: &[T], after: &[T]) -> Vec<Change<T>> {
+    if let Some(changes) = diff_unique(before, after) {
+        return changes;
+    }
+
+    let mut changes = Vec::with_capacity(before.len().max(after.len()));
+    diff_into(before, after, &mut changes);
+    changes
+}
+
+/// Mod lists don't have duplicates, and then the longest common subsequence is the longest increasing subsequence
+/// of where each item of `after` is in `before`. That takes O(n log n) however much the lists differ,
+/// where [`diff_into`] gets slow for lists that are shuffled.
+/// Returns `None` if either list has duplicates.
+fn diff_unique<T: Eq + Hash + Clone>(before: &[T], after: &[T]) -> Option<Vec<Change<T>>> {
+    let mut positions = HashMap::with_capacity(before.len());
+    for (i, item) in before.iter().enumerate() {
+        if positions.insert(item, i).is_some() {
+            return None;
+        }
+    }
+    let mut seen = HashSet::with_capacity(after.len());
+    if !after.iter().all(|item| seen.insert(item)) {
+        return None;
+    }
+
+    // (index in `after`, index in `before`) of the items in both
+    let common: Vec<(usize, usize)> = after
+        .iter()
+        .enumerate()
+        .filter_map(|(j, item)| positions.get(item).map(|&i| (j, i)))
+        .collect();
+
+    // tails[k] is the index into `common` of the smallest last item of an increasing subsequence k + 1 long
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; common.len()];
+    for (c, &(_, i)) in common.iter().enumerate() {
+        let k = tails.partition_point(|&t| common[t].1 < i);
+        if k > 0 {
+            previous[c] = Some(tails[k - 1]);
+        }
+        if k == tails.len() {
+            tails.push(c);
+        } else {
+            tails[k] = c;
+        }
+    }
+    let mut kept = Vec::with_capacity(tails.len());
+    let mut next = tails.last().copied();
+    while let Some(c) = next {
+        kept.push(common[c]);
+        next = previous[c];
+    }
+
+    let mut changes = Vec::with_capacity(before.len().max(after.len()));
+    let (mut i, mut j) = (0, 0);
+    for (kept_j, kept_i) in kept.into_iter().rev() {
+        changes.extend(before[i..kept_i].iter().cloned().map(Change::Removed));
+        changes.extend(after[j..kept_j].iter().cloned().map(Change::Added));
+        changes.push(Change::Kept(before[kept_i].clone()));
+        (i, j) = (kept_i + 1, kept_j + 1);
+    }
+    changes.extend(before[i..].iter().cloned().map(Change::Removed));
+    changes.extend(after[j..].iter().cloned().map(Change::Added));
+    Some(changes)
+}
+
+/// Myers' diff algorithm, in its linear space form: the lists are split where the shortest edit script
+/// passes through its middle (see [`split_point`]), and each side is diffed on its own.
+/// Takes O((n + m) * d) time for d changes, and O(n + m) space.
+fn diff_into<T: PartialEq + Clone>(before: &[T], after: &[T], changes: &mut Vec<Change<T>>) {
+    let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    changes.extend(before[..prefix].iter().cloned().map(Change::Kept));
+
+    let (middle_before, middle_after) = (
+        &before[prefix..before.len() - suffix],
+        &after[prefix..after.len() - suffix],
+    );
+    if let Some((x, y)) = split_point(middle_before, middle_after) {
+        diff_into(&middle_before[..x], &middle_after[..y], changes);
+        diff_into(&middle_before[x..], &middle_after[y..], changes);
+    } else {
+        changes.extend(middle_before.iter().cloned().map(Change::Removed));
+        changes.extend(middle_after.iter().cloned().map(Change::Added));
+    }
+
+    changes.extend(
+        before[before.len() - suffix..]
+            .iter()
+            .cloned()
+            .map(Change::Kept),
+    );
+}
+
+/// Searches for the shortest edit script from both ends at once,
+/// and returns where the two searches meet as the indices to split `before` and `after` at.
+/// `None` if they have nothing in common (or either is empty), so there's nowhere to split them.
+///
+/// Only the furthest point reached on each diagonal (`x - y`) is kept, for each direction.
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+fn split_point<T: PartialEq>(before: &[T], after: &[T]) -> Option<(usize, usize)> {
+    if before.is_empty() || after.is_empty() {
+        return None;
+    }
+
+    let (n, m) = (before.len() as isize, after.len() as isize);
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d;
+    let len = 2 * max_d + 1;
+    let mut forward = vec![-1_isize; len as usize];
+    let mut backward = vec![-1_isize; len as usize];
+    forward[offset as usize + 1] = 0;
+    backward[offset as usize + 1] = 0;
+
+    let delta = n - m;
+    // the searches can only overlap going forwards if `delta` is odd, and going backwards if it's even
+    let front = delta % 2 != 0;
+    // diagonals that have gone past the end of either list don't need searching any further
+    let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0, 0, 0, 0);
+
+    for d in 0..max_d {
+        for k1 in (-d + k1_start..=d - k1_end).step_by(2) {
+            let k1_offset = (offset + k1) as usize;
+            let mut x1 = if k1 == -d || (k1 != d && forward[k1_offset - 1] < forward[k1_offset + 1])
+            {
+                forward[k1_offset + 1]
+            } else {
+                forward[k1_offset - 1] + 1
+            };
+            let mut y1 = x1 - k1;
+            while x1 < n && y1 < m && before[x1 as usize] == after[y1 as usize] {
+                x1 += 1;
+                y1 += 1;
+            }
+            forward[k1_offset] = x1;
+
+            if x1 > n {
+                k1_end += 2;
+            } else if y1 > m {
+                k1_start += 2;
+            } else if front {
+                let k2_offset = offset + delta - k1;
+                if (0..len).contains(&k2_offset)
+                    && backward[k2_offset as usize] != -1
+                    && x1 >= n - backward[k2_offset as usize]
+                {
+                    return Some((x1 as usize, y1 as usize));
+                }
+            }
+        }
+
+        for k2 in (-d + k2_start..=d - k2_end).step_by(2) {
+            let k2_offset = (offset + k2) as usize;
+            let mut x2 =
+                if k2 == -d || (k2 != d && backward[k2_offset - 1] < backward[k2_offset + 1]) {
+                    backward[k2_offset + 1]
+                } else {
+                    backward[k2_offset - 1] + 1
+                };
+            let mut y2 = x2 - k2;
+            while x2 < n && y2 < m && before[(n - x2 - 1) as usize] == after[(m - y2 - 1) as usize]
+            {
+                x2 += 1;
+                y2 += 1;
+            }
+            backward[k2_offset] = x2;
+
+            if x2 > n {
+                k2_end += 2;
+            } else if y2 > m {
+                k2_start += 2;
+            } else if !front {
+                let k1_offset = offset + delta - k2;
+                if (0..len).contains(&k1_offset) && forward[k1_offset as usize] != -1 {
+                    let x1 = forward[k1_offset as usize];
+                    let y1 = offset + x1 - k1_offset;
+                    if x1 >= n - x2 {
+                        return Some((x1 as usize, y1 as usize));
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,150 @@
+use crate::{
+    helpers::paths::{push_game_exe_path, push_mods_config_path},
+    serialization::rimpy_config::RimPyConfigFolders,
+};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// The folders rimrs needs to know about, as shown in the setup screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Folder {
+    Game,
+    Config,
+    LocalMods,
+    Expansions,
+    SteamMods,
+}
+
+impl Folder {
+    pub const ALL: [Folder; 5] = [
+        Folder::Game,
+        Folder::Config,
+        Folder::LocalMods,
+        Folder::Expansions,
+        Folder::SteamMods,
+    ];
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Game => "Game folder",
+            Self::Config => "Config folder",
+            Self::LocalMods => "Local mods",
+            Self::Expansions => "Expansions",
+            Self::SteamMods => "Steam mods",
+        }
+    }
+
+    /// Describes what the folder should contain, to help the user pick it.
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Game => "The folder RimWorld is installed in",
+            Self::Config => "RimWorld's config folder, containing ModsConfig.xml",
+            Self::LocalMods => "The Mods folder in the game folder",
+            Self::Expansions => "The Data folder in the game folder, containing Core and the DLCs",
+            Self::SteamMods => {
+                "Steam workshop content folder (.../steamapps/workshop/content/294100)"
+            }
+        }
+    }
+
+    /// Non-steam installs won't have a workshop folder, but the game can't run without the others.
+    #[must_use]
+    pub fn is_required(self) -> bool {
+        !matches!(self, Self::SteamMods)
+    }
+
+    #[must_use]
+    pub fn get(self, folders: &RimPyConfigFolders) -> Option<&PathBuf> {
+        match self {
+            Self::Game => folders.game_folder.as_ref(),
+            Self::Config => folders.config_folder.as_ref(),
+            Self::LocalMods => folders.local_mods.as_ref(),
+            Self::Expansions => folders.expansions.as_ref(),
+            Self::SteamMods => folders.steam_mods.as_ref(),
+        }
+    }
+
+    pub fn set(self, folders: &mut RimPyConfigFolders, path: Option<PathBuf>) {
+        match self {
+            Self::Game => folders.game_folder = path,
+            Self::Config => folders.config_folder = path,
+            Self::LocalMods => folders.local_mods = path,
+            Self::Expansions => folders.expansions = path,
+            Self::SteamMods => folders.steam_mods = path,
+        }
+    }
+
+    /// Checks that the folder is set in `folders` and looks like what it claims to be.
+    ///
+    /// # Errors
+    /// See [`Folder::check_path`]. [`FolderError::NotSet`] if it isn't set.
+    pub fn check(self, folders: &RimPyConfigFolders) -> Result<(), FolderError> {
+        self.get(folders)
+            .ok_or(FolderError::NotSet)
+            .and_then(|path| self.check_path(path))
+    }
+
+    /// Checks that the given path looks like this kind of folder.
+    ///
+    /// # Errors
+    /// * [`FolderError::NotADirectory`] if it doesn't exist
+    /// * [`FolderError::NoExecutable`] if a game folder doesn't have the game in it
+    /// * [`FolderError::NoModsConfig`] if a config folder doesn't have `ModsConfig.xml`
+    /// * [`FolderError::NoMods`] if an expansions folder has no `*/About/About.xml`
+    /// * [`FolderError::Unreadable`] if a steam mods folder can't be read
+    pub fn check_path(self, path: &Path) -> Result<(), FolderError> {
+        if !path.is_dir() {
+            return Err(FolderError::NotADirectory);
+        }
+
+        match self {
+            Self::Game if !push_game_exe_path(path.to_path_buf()).exists() => {
+                Err(FolderError::NoExecutable)
+            }
+            Self::Config if !push_mods_config_path(path.to_path_buf()).is_file() => {
+                Err(FolderError::NoModsConfig)
+            }
+            Self::Expansions if !contains_mods(path) => Err(FolderError::NoMods),
+            // the workshop folder is empty until a mod is subscribed to
+            Self::SteamMods if fs::read_dir(path).is_err() => Err(FolderError::Unreadable),
+            // the Mods folder is empty on a fresh install, so there's nothing else to check
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Folder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum FolderError {
+    #[error("not set")]
+    NotSet,
+    #[error("not a folder")]
+    NotADirectory,
+    #[error("game executable not found")]
+    NoExecutable,
+    #[error("ModsConfig.xml not found")]
+    NoModsConfig,
+    #[error("no mods found (expected */About/About.xml)")]
+    NoMods,
+    #[error("couldn't read folder")]
+    Unreadable,
+}
+
+/// Checks if any subfolder of `path` has an `About/About.xml` file.
+fn contains_mods(path: &Path) -> bool {
+    fs::read_dir(path).is_ok_and(|entries| {
+        entries
+            .filter_map(Result::ok)
+            .any(|entry| entry.path().join("About").join("About.xml").is_file())
+    })
+}
@@ -0,0 +1,102 @@
+use std::collections::VecDeque;
+
+/// How many changes to the active mods can be undone by default.
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// Which way to move through a [`History`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryStep {
+    Undo,
+    Redo,
+}
+
+/// Bounded undo/redo stacks of past states, e.g. of the active mods.
+///
+/// States are recorded from before each change with [`History::record`].
+/// Undoing and redoing take the current state, so it can be returned to.
+///
+/// # Examples
+/// ```
+/// use rimrs::helpers::history::History;
+///
+/// let mut history = History::new(10);
+/// history.record(vec!["a"]);
+///
+/// assert_eq!(history.undo(vec!["a", "b"]), Some(vec!["a"]));
+/// assert_eq!(history.redo(vec!["a"]), Some(vec!["a", "b"]));
+/// assert_eq!(history.redo(vec!["a", "b"]), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    limit: usize,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl<T> History<T> {
+    /// Makes an empty history, keeping at most `limit` states to undo to.
+    #[must_use]
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Records the state from before a change, forgetting anything that could be redone.
+    /// The oldest state is dropped once there are more than the limit.
+    pub fn record(&mut self, before: T) {
+        self.redo.clear();
+        if self.limit == 0 {
+            return;
+        }
+        if self.undo.len() == self.limit {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(before);
+    }
+
+    /// Returns the state from before the last change, if there is one.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// Returns the state from before the last undo, if there is one.
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+        Some(next)
+    }
+
+    /// Either [`History::undo`] or [`History::redo`].
+    pub fn step(&mut self, step: HistoryStep, current: T) -> Option<T> {
+        match step {
+            HistoryStep::Undo => self.undo(current),
+            HistoryStep::Redo => self.redo(current),
+        }
+    }
+
+    #[must_use]
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    #[must_use]
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
@@ -1,8 +1,10 @@
-use once_cell::sync::Lazy;
-use std::{io::Read, sync::atomic::AtomicUsize};
+use std::{fmt::Write, io::Read, sync::atomic::AtomicUsize};
 use thiserror::Error;
 
 pub mod config;
+pub mod diff;
+pub mod folders;
+pub mod history;
 pub mod paths;
 pub mod traits;
 pub mod ui;
@@ -22,18 +24,10 @@ pub enum Side {
 }
 
 /// To get rid of BOM <https://en.wikipedia.org/wiki/Byte_order_mark>, which `xml-rs` doesn't allow.
-///
-/// # Panics
-/// If `bytes.len() < 3`, the length of BOM. Just checks if the first 3 bytes match the BOM sequence.
-/// All valid XML should be more than 3 characters long, so this should  be fine.
 #[must_use]
 pub fn strip_bom(bytes: &[u8]) -> &[u8] {
     const BOM: [u8; 3] = [239, 187, 191];
-    if bytes[0..3] == BOM {
-        &bytes[3..]
-    } else {
-        bytes
-    }
+    bytes.strip_prefix(&BOM).unwrap_or(bytes)
 }
 
 /// Takes a [`Vec`] of items (which don't contain the `li` tags).
@@ -53,12 +47,13 @@ pub fn fold_lis<S: AsRef<str>>(items: &[S], indenting: usize) -> String {
     let indent = single_indent.repeat(indenting);
 
     items.iter().fold(String::new(), |mut acc, item| {
-        acc.push_str(&format!("{indent}<li>{}</li>\n", item.as_ref()));
+        let _ = writeln!(acc, "{indent}<li>{}</li>", item.as_ref());
         acc
     })
 }
 
 /// Reads a line from a reader.
+/// Returns `None` once the reader is exhausted; blank lines are returned as empty strings.
 ///
 /// # Errors
 /// If `reader.read(buf)` returns `Err`
@@ -70,17 +65,19 @@ pub fn read_line(
 
     loop {
         let n = reader.read(&mut buf[..])?;
-        if n == 0 || buf[0] == b'\n' {
+        if n == 0 {
+            if line.is_empty() {
+                return Ok(None);
+            }
+            break;
+        }
+        if buf[0] == b'\n' {
             break;
         }
         line.push(buf[0]);
     }
 
-    if line.is_empty() {
-        Ok(None)
-    } else {
-        Ok(Some(String::from_utf8(line)?))
-    }
+    Ok(Some(String::from_utf8(line)?))
 }
 
 #[derive(Debug, Error)]
@@ -91,7 +88,77 @@ pub enum ReadLineError {
     FromUtf8Error(#[from] std::string::FromUtf8Error),
 }
 
-static ID_COUNTER: Lazy<AtomicUsize> = Lazy::new(|| AtomicUsize::new(0));
+/// Gets the steam workshop page for the given workshop id.
+///
+/// # Examples
+/// ```
+/// use rimrs::helpers::steam_workshop_url;
+///
+/// assert_eq!(
+///     steam_workshop_url("2009463077"),
+///     "https://steamcommunity.com/sharedfiles/filedetails/?id=2009463077"
+/// );
+/// ```
+#[must_use]
+pub fn steam_workshop_url(workshop_id: &str) -> String {
+    format!("https://steamcommunity.com/sharedfiles/filedetails/?id={workshop_id}")
+}
+
+/// Gets the workshop id from a steam workshop link,
+/// either a web page (`...?id=2009463077`) or a `steam://` link (`.../CommunityFilePage/2009463077`).
+///
+/// # Examples
+/// ```
+/// use rimrs::helpers::workshop_id_from_url;
+///
+/// assert_eq!(
+///     workshop_id_from_url("https://steamcommunity.com/workshop/filedetails/?id=2009463077"),
+///     Some("2009463077")
+/// );
+/// assert_eq!(
+///     workshop_id_from_url("steam://url/CommunityFilePage/2009463077"),
+///     Some("2009463077")
+/// );
+/// assert_eq!(workshop_id_from_url("https://github.com/pardeike/Harmony"), None);
+/// ```
+#[must_use]
+pub fn workshop_id_from_url(url: &str) -> Option<&str> {
+    let id = match url.find("id=") {
+        Some(i) => url[i + 3..].split('&').next()?,
+        None => url.trim_end_matches('/').rsplit('/').next()?,
+    };
+    Some(id).filter(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Formats a unix timestamp (in seconds) as a UTC date and time.
+///
+/// # Examples
+/// ```
+/// use rimrs::helpers::format_timestamp;
+///
+/// assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+/// assert_eq!(format_timestamp(1_709_210_096), "2024-02-29 12:34:56 UTC");
+/// ```
+#[must_use]
+pub fn format_timestamp(secs: u64) -> String {
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+    let (hour, minute, second) = (secs / 3600, secs % 3600 / 60, secs % 60);
+
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02} UTC")
+}
+
+static ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 #[must_use]
 pub fn fetch_inc_id() -> usize {
@@ -103,7 +170,7 @@ pub fn fetch_inc_id() -> usize {
 /// Very scuffed, because the font isn't monospace.
 /// Would be better to instead go `char`-by-`char` and calculate the actual width.
 #[must_use]
-pub fn truncate(s: &String, width: f32) -> String {
+pub fn truncate(s: &str, width: f32) -> String {
     /// String to add to truncated strings.
     const APPEND: &str = "...";
 
@@ -117,6 +184,6 @@ pub fn truncate(s: &String, width: f32) -> String {
         st.push_str(APPEND);
         st
     } else {
-        s.clone()
+        String::from(s)
     }
 }
@@ -2,20 +2,78 @@ use crate::traits::PushChained;
 use std::path::PathBuf;
 
 const MODS_CONFIG_FILE_NAME: &str = "ModsConfig.xml";
+const BACKUPS_DIR_NAME: &str = "backups";
+const PROFILES_DIR_NAME: &str = "profiles";
 const MOD_LIST_DIR_NAME: &str = "ModLists";
+const SAVES_DIR_NAME: &str = "Saves";
+const SETTINGS_FILE_NAME: &str = "settings.ini";
+const COMMUNITY_RULES_FILE_NAME: &str = "communityRules.json";
+const USER_RULES_FILE_NAME: &str = "userRules.json";
 
 #[must_use]
 pub fn push_mods_config_path(path: PathBuf) -> PathBuf {
     path.push_chained(MODS_CONFIG_FILE_NAME)
 }
 
+#[must_use]
+pub fn push_backups_path(path: PathBuf) -> PathBuf {
+    path.push_chained(BACKUPS_DIR_NAME)
+}
+
+#[must_use]
+pub fn push_profiles_path(path: PathBuf) -> PathBuf {
+    path.push_chained(PROFILES_DIR_NAME)
+}
+
 #[must_use]
 pub fn push_mod_lists_path(path: PathBuf) -> PathBuf {
     path.push_chained(MOD_LIST_DIR_NAME)
 }
 
+#[must_use]
+pub fn push_saves_path(path: PathBuf) -> PathBuf {
+    path.push_chained(SAVES_DIR_NAME)
+}
+
+#[must_use]
+pub fn push_settings_path(path: PathBuf) -> PathBuf {
+    path.push_chained(SETTINGS_FILE_NAME)
+}
+
+#[must_use]
+pub fn push_community_rules_path(path: PathBuf) -> PathBuf {
+    path.push_chained(COMMUNITY_RULES_FILE_NAME)
+}
+
+#[must_use]
+pub fn push_user_rules_path(path: PathBuf) -> PathBuf {
+    path.push_chained(USER_RULES_FILE_NAME)
+}
+
 #[allow(clippy::ptr_arg)]
 #[must_use]
 pub fn path_to_str(path: &PathBuf) -> Option<&str> {
     path.as_os_str().to_str()
 }
+
+/// Pushes the name of the game's executable onto the game folder.
+#[must_use]
+#[cfg(target_os = "windows")]
+pub fn push_game_exe_path(game_folder: PathBuf) -> PathBuf {
+    game_folder.push_chained("RimWorldWin64.exe") // TODO: allow for 32 bit
+}
+
+/// Pushes the name of the game's executable onto the game folder.
+/// The game folder on macOS is expected to be the `RimWorldMac.app` bundle.
+#[must_use]
+#[cfg(target_os = "macos")]
+pub fn push_game_exe_path(game_folder: PathBuf) -> PathBuf {
+    game_folder.push_chained("Contents/MacOS/RimWorld by Ludeon Studios")
+}
+
+/// Pushes the name of the game's executable onto the game folder.
+#[must_use]
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn push_game_exe_path(game_folder: PathBuf) -> PathBuf {
+    game_folder.push_chained("RimWorldLinux")
+}
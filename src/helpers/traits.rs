@@ -32,7 +32,7 @@ impl ToStringOrEmpty for Option<PathBuf> {
         self.clone()
             .map(PathBuf::into_os_string)
             .map_or(Ok(String::new()), OsString::into_string)
-            .unwrap_or(String::new())
+            .unwrap_or_default()
     }
 }
 
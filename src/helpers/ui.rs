@@ -1,7 +1,40 @@
-use eframe::egui::Ui;
+use crate::helpers::diff::{diff, Change};
+use eframe::egui::{Color32, ScrollArea, Ui};
 
 pub fn force_width(ui: &mut Ui, w: f32) {
     ui.set_width(w);
     ui.set_min_width(w);
     ui.set_max_width(w);
 }
+
+/// Lists the mods added (in green) and removed (in red) going from `before` to `after`,
+/// using `display_name` to show them.
+pub fn changes_list(
+    ui: &mut Ui,
+    id_source: &str,
+    before: &[String],
+    after: &[String],
+    display_name: impl Fn(&str) -> String,
+) {
+    let changes: Vec<_> = diff(before, after)
+        .into_iter()
+        .filter(Change::is_change)
+        .collect();
+
+    ScrollArea::vertical()
+        .id_source(id_source)
+        .max_height(300.0)
+        .show(ui, |ui| {
+            if changes.is_empty() {
+                ui.label("Same as the current active mods");
+            }
+            for change in &changes {
+                let name = display_name(change.item());
+                match change {
+                    Change::Added(_) => ui.colored_label(Color32::GREEN, format!("+ {name}")),
+                    Change::Removed(_) => ui.colored_label(Color32::RED, format!("- {name}")),
+                    Change::Kept(_) => unreachable!(),
+                };
+            }
+        });
+}
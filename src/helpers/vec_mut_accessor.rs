@@ -12,13 +12,13 @@ impl<'v, T> From<&'v mut Vec<T>> for VecMutAccessor<'v, T> {
     }
 }
 
-impl<'v, T> From<Arc<Mutex<Vec<T>>>> for VecMutAccessor<'v, T> {
+impl<T> From<Arc<Mutex<Vec<T>>>> for VecMutAccessor<'_, T> {
     fn from(vec: Arc<Mutex<Vec<T>>>) -> Self {
         Self::ArcMutex(vec)
     }
 }
 
-impl<'v, T> VecMutAccessor<'v, T> {
+impl<T> VecMutAccessor<'_, T> {
     #[must_use]
     pub fn len(&self) -> usize {
         match self {
@@ -74,7 +74,7 @@ impl<'v, T> VecMutAccessor<'v, T> {
     }
 }
 
-impl<'v, T> Mover for &mut VecMutAccessor<'v, T> {
+impl<T> Mover for &mut VecMutAccessor<'_, T> {
     type Error = VecMoveError;
 
     fn move_up(self, i: usize) -> Result<(), Self::Error> {
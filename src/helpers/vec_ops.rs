@@ -65,7 +65,7 @@ impl<T: std::fmt::Debug> std::fmt::Debug for VecOp<'_, T> {
     }
 }
 
-impl<'a, T> VecOp<'a, T> {
+impl<T> VecOp<'_, T> {
     /// Runs the operation.
     ///
     /// # Errors
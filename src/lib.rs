@@ -1,11 +1,16 @@
 #![warn(clippy::pedantic)]
 
 // pub mods
+pub mod backups;
+pub mod cli;
 pub mod helpers;
 pub mod panels;
+pub mod profiles;
 pub use helpers::traits;
 pub mod glyphs;
 pub mod serialization;
+pub mod steamcmd;
+pub mod watcher;
 pub mod widgets;
 pub mod writer_thread;
 
@@ -14,44 +19,80 @@ mod mods;
 pub use mods::*;
 
 // standalone reexports
-pub use serialization::{mods_config::ModsConfig, rimpy_config::RimPyConfig};
+pub use serialization::{
+    mods_config::{ModsConfig, ModsConfigFile},
+    rimpy_config::RimPyConfig,
+    settings::Settings,
+};
 
 // local imports
+use backups::Backups;
 use eframe::{
-    egui::{self, CentralPanel, TopBottomPanel},
+    egui::{self, CentralPanel, SidePanel, TopBottomPanel},
     App, CreationContext,
 };
-use helpers::AtomicFlag;
-use once_cell::sync::Lazy;
+use helpers::{
+    config::SystemEnv,
+    paths::{push_game_exe_path, push_mods_config_path},
+    AtomicFlag,
+};
 use panels::panel_using_widget;
+use profiles::Profiles;
 use std::{
     rc::Rc,
     sync::{
         mpsc::{sync_channel, SyncSender},
-        Arc,
+        Arc, LazyLock,
     },
 };
+use traits::LogIfErr;
 
-pub static CHANGED_ACTIVE_MODS: Lazy<AtomicFlag> = Lazy::new(AtomicFlag::new);
+pub static CHANGED_ACTIVE_MODS: LazyLock<AtomicFlag> = LazyLock::new(AtomicFlag::new);
 
 #[non_exhaustive]
 #[derive(Debug)]
 pub struct RimRs<'a> {
-    pub rimpy_config: Rc<RimPyConfig>,
-    pub mods_config: Arc<ModsConfig>,
-    paths_panel: panels::PathsPanel,
     hint_panel: panels::HintPanel,
+    hint_tx: SyncSender<String>,
+    writer_thread_tx: SyncSender<writer_thread::Message>,
+    screen: Screen<'a>,
+}
+
+/// What's shown in the central panel.
+#[derive(Debug)]
+enum Screen<'a> {
+    Setup(Box<panels::SetupPanel>),
+    Main(Box<MainScreen<'a>>),
+}
+
+/// The normal UI, once all the folders are known.
+#[derive(Debug)]
+#[allow(clippy::struct_field_names)]
+struct MainScreen<'a> {
+    paths_panel: panels::PathsPanel,
+    profiles_panel: Option<panels::ProfilesPanel<'a>>,
     mods_panel: panels::ModsPanel<'a>,
 }
 
-impl<'a> RimRs<'a> {
+#[derive(Debug, thiserror::Error)]
+pub enum StartupError {
+    #[error("{0} not set")]
+    MissingFolder(helpers::folders::Folder),
+    #[error("couldn't read mod folders: {0}")]
+    ModList(#[from] std::io::Error),
+    #[error("couldn't read ModsConfig.xml: {0}")]
+    ModsConfig(#[from] serialization::ParseXMLError),
+    #[error("couldn't setup writer thread")]
+    WriterThread,
+}
+
+impl RimRs<'_> {
     /// Creates a new [`RimRs`] app instance.
     ///
-    /// # Panics
-    /// * If it fails to read [`RimPyConfig`]
-    /// * If it can't read the initial mod folders
+    /// Reads [`Settings`] (importing them from `RimPy` on first run),
+    /// and looks for any folders still missing in their default locations.
+    /// If any are still missing, starts on the setup screen.
     #[must_use]
-    #[allow(unused_variables, clippy::needless_pass_by_value)]
     pub fn new(
         cc: &CreationContext<'_>,
         writer_thread_tx: SyncSender<writer_thread::Message>,
@@ -59,80 +100,165 @@ impl<'a> RimRs<'a> {
         let (hint_tx, hint_rx) = sync_channel(3);
         let hint_panel = panels::HintPanel::new(hint_rx);
 
-        let rimpy_config = RimPyConfig::from_file().unwrap();
-        let mod_list = ModList::try_from(&rimpy_config).unwrap();
-        let rimpy_config = Rc::new(rimpy_config);
+        let settings = Settings::load()
+            .log_if_err()
+            .unwrap_or_default()
+            .with_discovered_folders(&SystemEnv);
 
-        let mut exe_path = rimpy_config.folders.game_folder.clone().unwrap();
-        exe_path.push("RimWorldWin64.exe"); // TODO: allow for 32 bit, and also non-windows
+        cc.egui_ctx.set_visuals(if settings.ui.dark_mode {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        });
 
-        let cmd_args = rimpy_config.startup_params.clone();
+        let screen = if panels::SetupPanel::is_needed(&settings) {
+            Screen::Setup(Box::new(panels::SetupPanel::new(settings, None)))
+        } else {
+            Screen::main_or_setup(settings, &hint_tx, &writer_thread_tx)
+        };
 
-        let mut mods_config_path = rimpy_config
-            .folders
-            .config_folder
-            .clone()
-            .expect("Game config folder not found in RimPy `config.ini`");
-        mods_config_path.push("ModsConfig.xml");
-        writer_thread_tx
-            .send(writer_thread::Message::SetDestination(
-                mods_config_path.clone(),
-            ))
-            .expect("Couldn't setup writer thread");
+        Self {
+            hint_panel,
+            hint_tx,
+            writer_thread_tx,
+            screen,
+        }
+    }
+
+    /// Reads all the mods again, see [`panels::ModsPanel::reload_mods`].
+    /// Changes to single mods are picked up by the panel's [`watcher::Watcher`].
+    pub fn update_modlist(&mut self) {
+        if let Screen::Main(main) = &mut self.screen {
+            main.mods_panel.reload_mods();
+        }
+    }
+}
+
+impl Screen<'_> {
+    /// Tries to start the main UI, going back to setup with the error if it fails.
+    fn main_or_setup(
+        settings: Settings,
+        hint_tx: &SyncSender<String>,
+        writer_thread_tx: &SyncSender<writer_thread::Message>,
+    ) -> Self {
+        match MainScreen::new(settings.clone(), hint_tx, writer_thread_tx) {
+            Ok(main) => Self::Main(Box::new(main)),
+            Err(err) => {
+                log::error!("{err}");
+                Self::Setup(Box::new(panels::SetupPanel::new(
+                    settings,
+                    Some(err.to_string()),
+                )))
+            }
+        }
+    }
+}
+
+impl MainScreen<'_> {
+    fn new(
+        settings: Settings,
+        hint_tx: &SyncSender<String>,
+        writer_thread_tx: &SyncSender<writer_thread::Message>,
+    ) -> Result<Self, StartupError> {
+        use helpers::folders::Folder;
+
+        let mod_list = ModList::try_from(&settings)?;
+        let settings = Rc::new(settings);
+
+        let exe_path = push_game_exe_path(
+            settings
+                .folders
+                .game_folder
+                .clone()
+                .ok_or(StartupError::MissingFolder(Folder::Game))?,
+        );
+
+        let cmd_args = settings.startup_params.clone();
+
+        let mods_config_path = push_mods_config_path(
+            settings
+                .folders
+                .config_folder
+                .clone()
+                .ok_or(StartupError::MissingFolder(Folder::Config))?,
+        );
+        let mods_config_file = ModsConfigFile::try_from(mods_config_path.as_path())?;
+        let mods_config = Arc::new(mods_config_file.mods_config.clone());
 
-        let mods_config = Arc::from(ModsConfig::try_from(mods_config_path.as_path()).unwrap());
         writer_thread_tx
-            .send(writer_thread::Message::SetModsConfig(mods_config.clone()))
-            .expect("Couldn't setup writer thread");
+            .send(writer_thread::Message::SetDestination(mods_config_path))
+            .and_then(|()| {
+                writer_thread_tx.send(writer_thread::Message::SetModsConfig(Arc::new(
+                    mods_config_file,
+                )))
+            })
+            .map_err(|_| StartupError::WriterThread)?;
+
+        let backups = Backups::in_rimrs_dir().log_if_err();
+        if let Some(backups) = &backups {
+            writer_thread_tx
+                .send(writer_thread::Message::SetBackups(backups.clone()))
+                .map_err(|_| StartupError::WriterThread)?;
+        }
 
         let version = mods_config.version.clone().unwrap_or(String::from("???"));
 
-        let paths_panel = panels::PathsPanel::new(rimpy_config.clone(), version, hint_tx.clone());
+        let paths_panel = panels::PathsPanel::new(settings.clone(), version, hint_tx.clone());
         let mods_panel = panels::ModsPanel::new(
-            rimpy_config.clone(),
+            settings,
             mods_config.clone(),
             mod_list,
-            &hint_tx,
-            writer_thread_tx,
+            hint_tx,
+            writer_thread_tx.clone(),
+            backups,
             exe_path,
             cmd_args,
         );
 
-        Self {
-            rimpy_config,
-            mods_config,
+        let profiles_panel = Profiles::in_rimrs_dir().log_if_err().map(|profiles| {
+            panels::ProfilesPanel::new(
+                profiles,
+                mods_panel.active_mod_listing_ref(),
+                mods_panel.change_mod_list_tx(),
+                mods_config.version.clone(),
+                hint_tx.clone(),
+            )
+        });
+
+        Ok(Self {
             paths_panel,
-            hint_panel,
+            profiles_panel,
             mods_panel,
-        }
-    }
-
-    pub fn update_modlist(&mut self) {
-        let mut paths = Vec::new();
-        if let Some(p) = &self.rimpy_config.folders.expansions {
-            paths.push(p.clone());
-        }
-        if let Some(p) = &self.rimpy_config.folders.steam_mods {
-            paths.push(p.clone());
-        }
-        if let Some(p) = &self.rimpy_config.folders.local_mods {
-            paths.push(p.clone());
-        }
-
-        match ModList::from_dirs(paths) {
-            Ok(mod_list) => self.mods_panel.mods = mod_list,
-            Err(e) => log::error!("{e}"),
-        }
+        })
     }
 }
 
-impl<'a> App for RimRs<'a> {
+impl App for RimRs<'_> {
     #[allow(unused_variables)]
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        TopBottomPanel::top("paths_panel")
-            .show(ctx, |ui| panel_using_widget(ui, &mut self.paths_panel));
         TopBottomPanel::bottom("hint_panel")
             .show(ctx, |ui| panel_using_widget(ui, &mut self.hint_panel));
-        CentralPanel::default().show(ctx, |ui| panel_using_widget(ui, &mut self.mods_panel));
+
+        match &mut self.screen {
+            Screen::Setup(setup) => {
+                CentralPanel::default().show(ctx, |ui| panel_using_widget(ui, &mut **setup));
+                if let Some(settings) = setup.take_finished() {
+                    self.screen =
+                        Screen::main_or_setup(settings, &self.hint_tx, &self.writer_thread_tx);
+                }
+            }
+            Screen::Main(main) => {
+                TopBottomPanel::top("paths_panel").show(ctx, |ui| {
+                    if let Some(profiles_panel) = &mut main.profiles_panel {
+                        SidePanel::right("profiles_panel")
+                            .resizable(false)
+                            .show_inside(ui, |ui| panel_using_widget(ui, profiles_panel));
+                    }
+                    panel_using_widget(ui, &mut main.paths_panel);
+                });
+                CentralPanel::default()
+                    .show(ctx, |ui| panel_using_widget(ui, &mut main.mods_panel));
+            }
+        }
     }
 }
@@ -1,6 +1,6 @@
 use eframe::{run_native, NativeOptions};
 use rimrs::*;
-use std::sync::mpsc::sync_channel;
+use std::{env, io, process, sync::mpsc::sync_channel};
 
 fn main() {
     #[cfg(feature = "dotenv")]
@@ -9,6 +9,11 @@ fn main() {
     }
     env_logger::init();
 
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        process::exit(cli::run(args, &mut io::stdout(), &mut io::stderr()));
+    }
+
     let (writer_tx, writer_rx) = sync_channel(3);
     let writer_handle = writer_thread::spawn(writer_rx);
 
@@ -0,0 +1,133 @@
+use crate::{
+    helpers::diff::{diff, Change},
+    mods::{Diagnostic, SuggestedFix},
+    traits::LockIgnorePoisoned,
+    validate_for_version, ModMetaData,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+/// Fixing one problem can reveal or cause another, so fixes are applied in passes,
+/// revalidating in between. This caps how many passes are made.
+const MAX_PASSES: usize = 16;
+
+/// The changes proposed by [`auto_fix`].
+#[derive(Debug, Clone, Default)]
+pub struct AutoFix {
+    /// The active mods before the fixes, with their package ids lowercased like `after`'s.
+    pub before: Vec<String>,
+    /// The active mods after the fixes.
+    pub after: Vec<String>,
+    /// The fixes that were applied, in order, with the problems they were for.
+    pub applied: Vec<(Diagnostic, SuggestedFix)>,
+    /// The problems that are left in `after`.
+    pub remaining: Vec<Diagnostic>,
+}
+
+impl AutoFix {
+    /// Checks if there was nothing that could be fixed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.before == self.after
+    }
+
+    /// Diffs the active mods before and after the fixes.
+    #[must_use]
+    pub fn diff(&self) -> Vec<Change<String>> {
+        diff(&self.before, &self.after)
+    }
+}
+
+/// Works out a set of changes to `mods` that fix as many of the problems [`crate::validate`] finds as it can,
+/// using each [`Diagnostic::suggested_fix`].
+///
+/// Missing dependencies are only activated if they're in `mod_metadata`, i.e. installed.
+/// Doesn't change anything itself, see [`AutoFix::after`].
+#[allow(clippy::implicit_hasher)]
+#[must_use]
+pub fn auto_fix(
+    mod_metadata: &Arc<Mutex<HashMap<String, ModMetaData>>>,
+    mods: &[String],
+    version: Option<&str>,
+) -> AutoFix {
+    let before: Vec<String> = mods.iter().map(|pid| pid.to_lowercase()).collect();
+    let mut after = before.clone();
+    let mut applied = Vec::new();
+    let mut seen_states = HashSet::from([after.clone()]);
+
+    for _ in 0..MAX_PASSES {
+        let res = validate_for_version(mod_metadata, &after, version);
+        let mut changed = false;
+
+        for diagnostic in res.diagnostics() {
+            let Some(fix) = diagnostic.suggested_fix() else {
+                continue;
+            };
+            if still_applies(diagnostic, &after)
+                && is_installable(&fix, mod_metadata)
+                && fix.apply(&mut after)
+            {
+                applied.push((diagnostic.clone(), fix));
+                changed = true;
+            }
+        }
+
+        // stop if we're going round in circles, e.g. with conflicting load order rules
+        if !changed || !seen_states.insert(after.clone()) {
+            break;
+        }
+    }
+
+    let remaining = validate_for_version(mod_metadata, &after, version)
+        .diagnostics()
+        .cloned()
+        .collect();
+
+    AutoFix {
+        before,
+        after,
+        applied,
+        remaining,
+    }
+}
+
+/// Checks a diagnostic from earlier in the pass hasn't been dealt with by another fix since.
+fn still_applies(diagnostic: &Diagnostic, mods: &[String]) -> bool {
+    let is_active = |pid: &String| mods.contains(pid);
+
+    match diagnostic {
+        Diagnostic::MissingDependency {
+            package_id,
+            dependency,
+            is_active: false,
+        } => is_active(package_id) && !is_active(dependency),
+        Diagnostic::MissingDependency {
+            package_id,
+            dependency: other,
+            is_active: true,
+        }
+        | Diagnostic::LoadOrderViolation {
+            package_id, other, ..
+        }
+        | Diagnostic::Incompatible { package_id, other } => {
+            is_active(package_id) && is_active(other)
+        }
+        Diagnostic::MissingMetadata { package_id }
+        | Diagnostic::UnsupportedVersion { package_id, .. }
+        | Diagnostic::Duplicate { package_id } => is_active(package_id),
+    }
+}
+
+fn is_installable(
+    fix: &SuggestedFix,
+    mod_metadata: &Arc<Mutex<HashMap<String, ModMetaData>>>,
+) -> bool {
+    match fix {
+        SuggestedFix::Activate { package_id, .. } => {
+            mod_metadata.lock_ignore_poisoned().contains_key(package_id)
+        }
+        _ => true,
+    }
+}
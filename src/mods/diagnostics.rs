@@ -0,0 +1,266 @@
+use std::fmt;
+
+/// How bad a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The game will load, but probably not how the mod authors intended.
+    Warning,
+    /// The game will refuse to load a mod, or is likely to break.
+    Error,
+}
+
+/// Which rule a [`Diagnostic::LoadOrderViolation`] broke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoadOrderRule {
+    /// From `loadBefore`.
+    LoadBefore,
+    /// From `loadAfter`.
+    LoadAfter,
+}
+
+/// A single problem found with the active mod list by [`crate::validate`].
+///
+/// The [`fmt::Display`] impl gives a human readable message, e.g. `b requires a`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Diagnostic {
+    /// `package_id` has `dependency` in its `modDependencies`, but it isn't loaded before it.
+    MissingDependency {
+        package_id: String,
+        dependency: String,
+        /// Whether the dependency is active at all, just loaded too late.
+        is_active: bool,
+    },
+    /// `package_id` has a `loadBefore`/`loadAfter` rule for `other` that isn't being followed.
+    LoadOrderViolation {
+        package_id: String,
+        other: String,
+        rule: LoadOrderRule,
+    },
+    /// `package_id` has `other` in its `incompatibleWith`, and both are active.
+    Incompatible { package_id: String, other: String },
+    /// `package_id` is active, but isn't installed (or its `About.xml` couldn't be read).
+    MissingMetadata { package_id: String },
+    /// `package_id` doesn't list `version` in its `supportedVersions`.
+    UnsupportedVersion { package_id: String, version: String },
+    /// `package_id` is in the active mod list more than once.
+    Duplicate { package_id: String },
+}
+
+/// A change to the active mod list that should resolve a [`Diagnostic`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SuggestedFix {
+    /// Activate `package_id`, placing it right before `before`.
+    Activate { package_id: String, before: String },
+    /// Remove `package_id` from the active mods.
+    Deactivate(String),
+    /// Move `package_id` to right before `before`.
+    MoveBefore { package_id: String, before: String },
+    /// Move `package_id` to right after `after`.
+    MoveAfter { package_id: String, after: String },
+    /// Keep only the first occurrence of `package_id`.
+    RemoveDuplicates(String),
+}
+
+impl Diagnostic {
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::MissingDependency { .. } | Self::Incompatible { .. } => Severity::Error,
+            Self::LoadOrderViolation { .. }
+            | Self::MissingMetadata { .. }
+            | Self::UnsupportedVersion { .. }
+            | Self::Duplicate { .. } => Severity::Warning,
+        }
+    }
+
+    /// A short, stable name for the kind of diagnostic, e.g. for the CLI's JSON output.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::MissingDependency { .. } => "missingDependency",
+            Self::LoadOrderViolation { .. } => "loadOrderViolation",
+            Self::Incompatible { .. } => "incompatible",
+            Self::MissingMetadata { .. } => "missingMetadata",
+            Self::UnsupportedVersion { .. } => "unsupportedVersion",
+            Self::Duplicate { .. } => "duplicate",
+        }
+    }
+
+    /// The mods involved, starting with the one the diagnostic is about.
+    #[must_use]
+    pub fn package_ids(&self) -> Vec<&str> {
+        match self {
+            Self::MissingDependency {
+                package_id,
+                dependency: other,
+                ..
+            }
+            | Self::LoadOrderViolation {
+                package_id, other, ..
+            }
+            | Self::Incompatible { package_id, other } => vec![package_id, other],
+            Self::MissingMetadata { package_id }
+            | Self::UnsupportedVersion { package_id, .. }
+            | Self::Duplicate { package_id } => vec![package_id],
+        }
+    }
+
+    /// Gets a change to the mod list that would resolve this, if there's an obvious one.
+    ///
+    /// The fix isn't checked against the rest of the mod list,
+    /// e.g. [`SuggestedFix::Activate`] may suggest a mod that isn't installed.
+    #[must_use]
+    pub fn suggested_fix(&self) -> Option<SuggestedFix> {
+        match self {
+            Self::MissingDependency {
+                package_id,
+                dependency,
+                is_active: true,
+            } => Some(SuggestedFix::MoveBefore {
+                package_id: dependency.clone(),
+                before: package_id.clone(),
+            }),
+            Self::MissingDependency {
+                package_id,
+                dependency,
+                is_active: false,
+            } => Some(SuggestedFix::Activate {
+                package_id: dependency.clone(),
+                before: package_id.clone(),
+            }),
+            Self::LoadOrderViolation {
+                package_id,
+                other,
+                rule: LoadOrderRule::LoadBefore,
+            } => Some(SuggestedFix::MoveBefore {
+                package_id: package_id.clone(),
+                before: other.clone(),
+            }),
+            Self::LoadOrderViolation {
+                package_id,
+                other,
+                rule: LoadOrderRule::LoadAfter,
+            } => Some(SuggestedFix::MoveAfter {
+                package_id: package_id.clone(),
+                after: other.clone(),
+            }),
+            Self::Incompatible { package_id, .. } => {
+                Some(SuggestedFix::Deactivate(package_id.clone()))
+            }
+            Self::Duplicate { package_id } => {
+                Some(SuggestedFix::RemoveDuplicates(package_id.clone()))
+            }
+            Self::MissingMetadata { .. } | Self::UnsupportedVersion { .. } => None,
+        }
+    }
+}
+
+impl SuggestedFix {
+    /// Applies the fix to a list of active mods.
+    /// Returns `false` (leaving `mods` unchanged) if it can't be applied,
+    /// e.g. if a mod it refers to isn't in the list.
+    pub fn apply(&self, mods: &mut Vec<String>) -> bool {
+        let position = |mods: &[String], pid: &str| mods.iter().position(|m| m == pid);
+
+        match self {
+            Self::Activate { package_id, before } => {
+                if mods.contains(package_id) {
+                    return false;
+                }
+                let i = position(mods, before).unwrap_or(mods.len());
+                mods.insert(i, package_id.clone());
+            }
+            Self::Deactivate(package_id) => {
+                let len = mods.len();
+                mods.retain(|m| m != package_id);
+                return mods.len() != len;
+            }
+            Self::MoveBefore { package_id, before } => {
+                let (Some(from), Some(_)) = (position(mods, package_id), position(mods, before))
+                else {
+                    return false;
+                };
+                let pid = mods.remove(from);
+                let to = position(mods, before).unwrap_or_default();
+                mods.insert(to, pid);
+            }
+            Self::MoveAfter { package_id, after } => {
+                let (Some(from), Some(_)) = (position(mods, package_id), position(mods, after))
+                else {
+                    return false;
+                };
+                let pid = mods.remove(from);
+                let to = position(mods, after).map_or(mods.len(), |i| i + 1);
+                mods.insert(to, pid);
+            }
+            Self::RemoveDuplicates(package_id) => {
+                let len = mods.len();
+                let mut seen = false;
+                mods.retain(|m| m != package_id || !std::mem::replace(&mut seen, true));
+                return mods.len() != len;
+            }
+        }
+
+        true
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingDependency {
+                package_id,
+                dependency,
+                ..
+            } => write!(f, "{package_id} requires {dependency}"),
+            Self::LoadOrderViolation {
+                package_id,
+                other,
+                rule: LoadOrderRule::LoadBefore,
+            } => write!(f, "{package_id} should be loaded before {other}"),
+            Self::LoadOrderViolation {
+                package_id,
+                other,
+                rule: LoadOrderRule::LoadAfter,
+            } => write!(f, "{package_id} should be loaded after {other}"),
+            Self::Incompatible { package_id, other } => {
+                write!(f, "{package_id} is incompatible with {other}")
+            }
+            Self::MissingMetadata { package_id } => {
+                write!(f, "Couldn't find metadata for {package_id}")
+            }
+            Self::UnsupportedVersion {
+                package_id,
+                version,
+            } => write!(f, "{package_id} doesn't support version {version}"),
+            Self::Duplicate { package_id } => write!(f, "{package_id} is active more than once"),
+        }
+    }
+}
+
+impl fmt::Display for SuggestedFix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Activate { package_id, before } => {
+                write!(f, "activate {package_id} before {before}")
+            }
+            Self::Deactivate(package_id) => write!(f, "deactivate {package_id}"),
+            Self::MoveBefore { package_id, before } => {
+                write!(f, "move {package_id} before {before}")
+            }
+            Self::MoveAfter { package_id, after } => write!(f, "move {package_id} after {after}"),
+            Self::RemoveDuplicates(package_id) => {
+                write!(f, "remove duplicates of {package_id}")
+            }
+        }
+    }
+}
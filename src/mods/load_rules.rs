@@ -0,0 +1,46 @@
+use std::{collections::HashSet, fmt};
+
+/// Where a load order rule came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuleSource {
+    /// The mod's own `About.xml`.
+    About,
+    /// `RimPy`'s community rules database, see [`crate::serialization::community_rules`].
+    Community,
+    /// The user's own rules file, see [`crate::serialization::user_rules`].
+    User,
+}
+
+impl fmt::Display for RuleSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::About => "About.xml",
+            Self::Community => "community rules",
+            Self::User => "your rules",
+        })
+    }
+}
+
+/// Load order rules for a mod that come from somewhere other than its `About.xml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadRules {
+    pub load_after: HashSet<String>,
+    pub load_before: HashSet<String>,
+    pub incompatible_with: HashSet<String>,
+    /// Load before everything else, unless another rule says otherwise.
+    pub load_top: bool,
+    /// Load after everything else, unless another rule says otherwise.
+    pub load_bottom: bool,
+}
+
+impl LoadRules {
+    /// Checks if there aren't any rules.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.load_after.is_empty()
+            && self.load_before.is_empty()
+            && self.incompatible_with.is_empty()
+            && !self.load_top
+            && !self.load_bottom
+    }
+}
@@ -1,15 +1,27 @@
 mod mod_meta_data;
-pub use mod_meta_data::ModMetaData;
+pub use mod_meta_data::{major_minor_version, ModMetaData};
+
+mod load_rules;
+pub use load_rules::*;
 
 mod mod_list;
 pub use mod_list::*;
 
+mod diagnostics;
+pub use diagnostics::*;
+
+mod auto_fix;
+pub use auto_fix::*;
+
 mod validation;
 pub use validation::*;
 
 mod sorting;
 pub use sorting::*;
 
+mod mod_graph;
+pub use mod_graph::*;
+
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 #[allow(non_snake_case)]
 pub struct Dependency {
@@ -18,3 +30,13 @@ pub struct Dependency {
     pub downloadUrl: Option<String>,
     pub steamWorkshopUrl: Option<String>,
 }
+
+impl Dependency {
+    /// Gets the dependency's workshop id from its `steamWorkshopUrl`.
+    #[must_use]
+    pub fn steam_workshop_id(&self) -> Option<&str> {
+        self.steamWorkshopUrl
+            .as_deref()
+            .and_then(crate::helpers::workshop_id_from_url)
+    }
+}
@@ -1,9 +1,13 @@
-use crate::{ModMetaData, RimPyConfig};
+use crate::{
+    serialization::{community_rules::CommunityRules, user_rules::UserRules},
+    traits::{LockIgnorePoisoned, LogIfErr},
+    ModMetaData, RimPyConfig, Settings,
+};
 use std::{
     collections::HashMap,
     fs::{self, DirEntry},
     io,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
@@ -25,7 +29,7 @@ impl ModList {
                 if let Ok(p) = mod_dir.as_ref().map(DirEntry::path) {
                     if mod_dir
                         .and_then(|md| md.file_type())
-                        .map_or(false, |ft| ft.is_dir())
+                        .is_ok_and(|ft| ft.is_dir())
                     {
                         paths.push(p);
                     }
@@ -45,6 +49,58 @@ impl ModList {
         Ok(ModList::from(mods))
     }
 
+    /// Reads the mod in `mod_dir` again, after it was added, updated or deleted,
+    /// and returns the package ids (lowercase) of the mods that changed.
+    ///
+    /// Mods that were already in the list keep their community and user rules,
+    /// and `apply_rules` is used to give new ones theirs.
+    /// If its `About.xml` can't be parsed (e.g. it's still being downloaded), nothing changes.
+    pub fn rescan_mod(
+        &self,
+        mod_dir: &Path,
+        apply_rules: impl Fn(&mut ModMetaData),
+    ) -> Vec<String> {
+        let about = mod_dir.join("About").join("About.xml");
+        let found = if about.is_file() {
+            match ModMetaData::read(&about).log_if_err() {
+                Some(mmd) => mmd.packageId.is_some().then_some(mmd),
+                None => return Vec::new(),
+            }
+        } else {
+            None
+        };
+
+        let mut mods = self.mods.lock_ignore_poisoned();
+        let old_pids: Vec<_> = mods
+            .iter()
+            .filter(|(_, mmd)| {
+                mmd.path
+                    .as_ref()
+                    .is_some_and(|path| path.starts_with(mod_dir))
+            })
+            .map(|(pid, _)| pid.clone())
+            .collect();
+        let old: Vec<_> = old_pids
+            .iter()
+            .filter_map(|pid| mods.remove(pid).map(|mmd| (pid.clone(), mmd)))
+            .collect();
+        let mut changed = old_pids;
+
+        if let Some(mut mmd) = found {
+            let pid = mmd.packageId.as_deref().unwrap_or_default().to_lowercase();
+            if let Some((_, old)) = old.into_iter().find(|(old_pid, _)| *old_pid == pid) {
+                mmd.community_rules = old.community_rules;
+                mmd.user_rules = old.user_rules;
+            } else {
+                apply_rules(&mut mmd);
+                changed.push(pid.clone());
+            }
+            mods.insert(pid, mmd);
+        }
+
+        changed
+    }
+
     /// Gets the package ids (lowercase) of all mods in the mod list.
     /// Returns `None` if there is a [`std::sync::PoisonError`].
     #[must_use]
@@ -73,18 +129,21 @@ impl TryFrom<&RimPyConfig> for ModList {
     type Error = io::Error;
 
     fn try_from(rimpy_config: &RimPyConfig) -> Result<Self, Self::Error> {
-        let mut paths = Vec::new();
+        ModList::from_dirs(rimpy_config.folders.mod_dirs())
+    }
+}
 
-        if let Some(p) = &rimpy_config.folders.expansions {
-            paths.push(p.clone());
-        }
-        if let Some(p) = &rimpy_config.folders.steam_mods {
-            paths.push(p.clone());
-        }
-        if let Some(p) = &rimpy_config.folders.local_mods {
-            paths.push(p.clone());
-        }
+impl TryFrom<&Settings> for ModList {
+    type Error = io::Error;
 
-        ModList::from_dirs(paths)
+    /// Reads the mods in the folders in `settings`, along with their community and user rules if there are any.
+    /// See [`CommunityRules::load`] and [`UserRules::load`].
+    fn try_from(settings: &Settings) -> Result<Self, Self::Error> {
+        let mod_list = ModList::from_dirs(settings.folders.mod_dirs())?;
+        if let Some(rules) = CommunityRules::load(settings) {
+            rules.apply(&mod_list);
+        }
+        UserRules::load(settings).apply(&mod_list);
+        Ok(mod_list)
     }
 }
@@ -1,5 +1,6 @@
 use crate::{
-    mods::Dependency,
+    helpers::steam_workshop_url,
+    mods::{Dependency, LoadRules, RuleSource},
     serialization::{about::parse_about, ParseXMLError},
 };
 use std::{
@@ -45,6 +46,11 @@ pub struct ModMetaData {
     // incompat
     pub incompatibleWith: Option<HashSet<String>>,
     pub incompatibleWithByVersion: Option<HashMap<String, HashSet<String>>>,
+
+    /// Rules for this mod from the community rules database, see [`ModMetaData::load_after_rules`].
+    pub community_rules: Option<LoadRules>,
+    /// Rules for this mod from the user's own rules file, see [`ModMetaData::load_after_rules`].
+    pub user_rules: Option<LoadRules>,
 }
 
 impl ModMetaData {
@@ -55,7 +61,7 @@ impl ModMetaData {
     /// * [`xml::reader::Error`]: if it tries to parse invalid XML
     #[allow(clippy::missing_panics_doc)]
     pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, ParseXMLError> {
-        log::debug!("Parsing {:?}", path.as_ref());
+        log::debug!("Parsing {}", path.as_ref().display());
         let file = fs::read(path.as_ref())?;
 
         // parse
@@ -77,9 +83,11 @@ impl ModMetaData {
         }
         let load_after = mmd.loadAfter.as_mut().unwrap();
         #[allow(clippy::collapsible_if)]
-        if mmd.packageId.as_ref().map_or(true, |pid| {
-            !pid.to_lowercase().starts_with("ludeon.rimworld")
-        }) {
+        if mmd
+            .packageId
+            .as_ref()
+            .is_none_or(|pid| !pid.to_lowercase().starts_with("ludeon.rimworld"))
+        {
             if !load_before.contains(CORE) {
                 load_after.insert(String::from(CORE));
 
@@ -99,4 +107,181 @@ impl ModMetaData {
 
         Ok(mmd)
     }
+
+    /// Gets the metadata as it applies to the given game version,
+    /// with the `*ByVersion` rules for that version merged into the generic ones.
+    ///
+    /// `version` can be the full version from `ModsConfig.xml` (e.g. `1.4.3613 rev641`), as only the major
+    /// and minor parts are used to find the matching `<v1.4>` entries.
+    /// If there's a version-specific description it replaces the generic one.
+    #[must_use]
+    pub fn effective_for(&self, version: &str) -> Self {
+        fn merge<T: Clone + Eq + std::hash::Hash>(
+            generic: Option<&HashSet<T>>,
+            by_version: Option<&HashSet<T>>,
+        ) -> Option<HashSet<T>> {
+            match (generic, by_version) {
+                (None, None) => None,
+                (generic, by_version) => Some(
+                    generic
+                        .into_iter()
+                        .chain(by_version)
+                        .flatten()
+                        .cloned()
+                        .collect(),
+                ),
+            }
+        }
+
+        let version = major_minor_version(version);
+        let mut mmd = self.clone();
+
+        if let Some(desc) = self
+            .descriptionsByVersion
+            .as_ref()
+            .and_then(|d| d.get(version))
+        {
+            mmd.description = Some(desc.clone());
+        }
+        mmd.modDependencies = merge(
+            self.modDependencies.as_ref(),
+            self.modDependenciesByVersion
+                .as_ref()
+                .and_then(|d| d.get(version)),
+        );
+        mmd.loadAfter = merge(
+            self.loadAfter.as_ref(),
+            self.loadAfterByVersion
+                .as_ref()
+                .and_then(|d| d.get(version)),
+        );
+        mmd.loadBefore = merge(
+            self.loadBefore.as_ref(),
+            self.loadBeforeByVersion
+                .as_ref()
+                .and_then(|d| d.get(version)),
+        );
+        mmd.incompatibleWith = merge(
+            self.incompatibleWith.as_ref(),
+            self.incompatibleWithByVersion
+                .as_ref()
+                .and_then(|d| d.get(version)),
+        );
+
+        mmd
+    }
+
+    /// Every `loadAfter` rule for this mod, with where it came from.
+    /// Doesn't include `forceLoadAfter`.
+    pub fn load_after_rules(&self) -> impl Iterator<Item = (&String, RuleSource)> {
+        self.rules(self.loadAfter.as_ref(), |r| &r.load_after)
+    }
+
+    /// Every `loadBefore` rule for this mod, with where it came from.
+    /// Doesn't include `forceLoadBefore`.
+    pub fn load_before_rules(&self) -> impl Iterator<Item = (&String, RuleSource)> {
+        self.rules(self.loadBefore.as_ref(), |r| &r.load_before)
+    }
+
+    /// Every `incompatibleWith` rule for this mod, with where it came from.
+    pub fn incompatible_rules(&self) -> impl Iterator<Item = (&String, RuleSource)> {
+        self.rules(self.incompatibleWith.as_ref(), |r| &r.incompatible_with)
+    }
+
+    /// Chains the rules from `About.xml` with the matching ones from [`ModMetaData::community_rules`]
+    /// and [`ModMetaData::user_rules`].
+    fn rules<'a>(
+        &'a self,
+        about: Option<&'a HashSet<String>>,
+        extra: fn(&LoadRules) -> &HashSet<String>,
+    ) -> impl Iterator<Item = (&'a String, RuleSource)> {
+        let about = about
+            .into_iter()
+            .flatten()
+            .map(|pid| (pid, RuleSource::About));
+        let community = self.community_rules.iter().flat_map(extra);
+        let user = self.user_rules.iter().flat_map(extra);
+        about
+            .chain(community.map(|pid| (pid, RuleSource::Community)))
+            .chain(user.map(|pid| (pid, RuleSource::User)))
+    }
+
+    /// Checks if the user wants the mod loaded before everything else that allows it.
+    #[must_use]
+    pub fn load_top(&self) -> bool {
+        self.user_rules.as_ref().is_some_and(|r| r.load_top)
+    }
+
+    /// Checks if the mod should be loaded after everything else that allows it.
+    /// The user's rules win over the community rules.
+    #[must_use]
+    pub fn load_bottom(&self) -> bool {
+        match self.user_rules.as_ref() {
+            Some(rules) if rules.load_top || rules.load_bottom => rules.load_bottom,
+            _ => self.community_rules.as_ref().is_some_and(|r| r.load_bottom),
+        }
+    }
+
+    /// Checks if the mod lists the given game version in its `supportedVersions`.
+    /// Only the major and minor parts of the versions are compared, see [`major_minor_version`].
+    ///
+    /// Returns [`None`] if the mod doesn't list any supported versions.
+    #[must_use]
+    pub fn supports_version(&self, version: &str) -> Option<bool> {
+        let version = major_minor_version(version);
+        self.supportedVersions
+            .as_ref()
+            .map(|supported| supported.iter().any(|v| major_minor_version(v) == version))
+    }
+
+    /// Gets the mod's steam workshop id.
+    /// Mods downloaded from the workshop are kept in a folder named after it, so it's taken from there.
+    #[must_use]
+    pub fn steam_workshop_id(&self) -> Option<&str> {
+        // path is `.../{folder}/About/About.xml`
+        let folder = self.path.as_ref()?.parent()?.parent()?;
+        folder
+            .file_name()?
+            .to_str()
+            .filter(|name| !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit()))
+    }
+
+    /// Gets the mod's steam workshop page, see [`ModMetaData::steam_workshop_id`].
+    #[must_use]
+    pub fn steam_workshop_url(&self) -> Option<String> {
+        self.steam_workshop_id().map(steam_workshop_url)
+    }
+}
+
+/// Gets the `major.minor` part of a game version, as used in `supportedVersions` and the `*ByVersion` tags.
+///
+/// # Examples
+/// ```
+/// use rimrs::major_minor_version;
+///
+/// assert_eq!(major_minor_version("1.4.3613 rev641"), "1.4");
+/// assert_eq!(major_minor_version("1.4"), "1.4");
+/// ```
+#[must_use]
+pub fn major_minor_version(version: &str) -> &str {
+    let version = version.split_whitespace().next().unwrap_or_default();
+    match version.match_indices('.').nth(1) {
+        Some((i, _)) => &version[..i],
+        None => version,
+    }
+}
+
+/// Applies [`ModMetaData::effective_for`] to each of the given mods that has metadata.
+pub(crate) fn effective_metadata<S: std::hash::BuildHasher>(
+    mod_metadata: &HashMap<String, ModMetaData, S>,
+    mods: &[String],
+    version: &str,
+) -> HashMap<String, ModMetaData> {
+    mods.iter()
+        .filter_map(|pid| {
+            mod_metadata
+                .get(pid)
+                .map(|mmd| (pid.clone(), mmd.effective_for(version)))
+        })
+        .collect()
 }
@@ -1,156 +1,396 @@
-use crate::{traits::LockIgnorePoisoned, ModMetaData};
-use itertools::Itertools;
+use crate::{
+    helpers::diff::{diff, Change},
+    mods::{mod_meta_data::effective_metadata, ModEdge, ModGraph},
+    traits::LockIgnorePoisoned,
+    ModMetaData,
+};
 use std::{
-    collections::{HashMap, HashSet},
-    sync::{Arc, Mutex, MutexGuard},
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::{Arc, Mutex},
 };
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum SortError {
-    #[error("cyclic dependency")]
-    CyclicError,
+    #[error("cyclic dependency: {0}")]
+    CyclicError(Cycle),
     #[error("missing mod metadata")]
     MissingData,
 }
 
+/// The About.xml rule that says one mod has to be loaded after another.
+/// Ordered from strictest to most lenient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SortRule {
+    ModDependency,
+    ForceLoadAfter,
+    ForceLoadBefore,
+    LoadAfter,
+    LoadBefore,
+}
+
+/// `package_id` has to be loaded after `after`, because of `rule`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SortEdge {
+    pub package_id: String,
+    pub after: String,
+    pub rule: SortRule,
+}
+
+/// A chain of [`SortEdge`]s where each edge's `after` is the next one's `package_id`,
+/// and the last one's `after` is the first one's `package_id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle(pub Vec<SortEdge>);
+
+impl SortRule {
+    /// `loadBefore` and `loadAfter` are only advice, the game will still load mods that break them.
+    #[must_use]
+    pub fn is_soft(self) -> bool {
+        matches!(self, Self::LoadAfter | Self::LoadBefore)
+    }
+}
+
+impl Cycle {
+    /// The mods in the cycle, in the order of the chain.
+    #[must_use]
+    pub fn package_ids(&self) -> Vec<&str> {
+        self.0.iter().map(|edge| edge.package_id.as_str()).collect()
+    }
+}
+
+impl fmt::Display for SortRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ModDependency => "modDependencies",
+            Self::ForceLoadAfter => "forceLoadAfter",
+            Self::ForceLoadBefore => "forceLoadBefore",
+            Self::LoadAfter => "loadAfter",
+            Self::LoadBefore => "loadBefore",
+        })
+    }
+}
+
+impl fmt::Display for SortEdge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            package_id,
+            after,
+            rule,
+        } = self;
+        match rule {
+            SortRule::ModDependency => write!(f, "{package_id} depends on {after}"),
+            SortRule::ForceLoadAfter | SortRule::LoadAfter => {
+                write!(
+                    f,
+                    "{package_id} loads after {after} ({rule} in {package_id})"
+                )
+            }
+            SortRule::ForceLoadBefore | SortRule::LoadBefore => {
+                write!(f, "{package_id} loads after {after} ({rule} in {after})")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let edges: Vec<_> = self.0.iter().map(ToString::to_string).collect();
+        f.write_str(&edges.join(", "))
+    }
+}
+
 /// Performs a [topological sort](https://en.wikipedia.org/wiki/Topological_sorting) on the given list of mods.
 /// Uses the depth-first search algorithm.
 ///
 /// # Errors
-/// * [`SortError::CyclicError`] if there is a cyclic dependency
+/// * [`SortError::CyclicError`] if there is a cyclic dependency, with the [`Cycle`] it found
 /// * [`SortError::MissingData`] if there is missing data in `mod_metadata` (e.g. missing `package_id`)
 #[allow(clippy::implicit_hasher, clippy::missing_panics_doc)]
 pub fn sort(
     mods: &[String],
     mod_metadata: &Arc<Mutex<HashMap<String, ModMetaData>>>,
 ) -> Result<Vec<String>, SortError> {
-    let mut sorted = Vec::from(mods);
-    sorted.sort();
+    sort_for_version(mods, mod_metadata, None)
+}
 
+/// Like [`sort`], but also follows the `*ByVersion` rules for the given game version, if any.
+/// See [`ModMetaData::effective_for`].
+///
+/// # Errors
+/// See [`sort`]
+#[allow(clippy::implicit_hasher)]
+pub fn sort_for_version(
+    mods: &[String],
+    mod_metadata: &Arc<Mutex<HashMap<String, ModMetaData>>>,
+    version: Option<&str>,
+) -> Result<Vec<String>, SortError> {
     let mmd = mod_metadata.lock_ignore_poisoned();
-    let deps = build_deps(&sorted, &mmd)?;
-
-    let mut output: Vec<String> = Vec::with_capacity(mods.len());
-    let mut unmarked: Vec<&String> = sorted.iter().collect();
-    let mut temp_marks: HashSet<&String> = HashSet::new();
-
-    // I would do a `while let` but `visit` would break the iterator
-    while !unmarked.is_empty() {
-        visit(
-            unmarked.first().unwrap(),
-            &deps,
-            &mut output,
-            &mut unmarked,
-            &mut temp_marks,
-        )?;
+    match version {
+        Some(version) => sort_with(mods, &effective_metadata(&mmd, mods, version)),
+        None => sort_with(mods, &mmd),
     }
-
-    Ok(output)
 }
 
-fn visit<'a>(
-    node: &'a String,
-    deps: &HashMap<&'a String, Vec<&'a String>>,
-    output: &mut Vec<String>,
-    unmarked: &mut Vec<&String>,
-    temp_marks: &mut HashSet<&'a String>,
-) -> Result<(), SortError> {
-    if !unmarked.iter().any(|&x| x == node) {
-        // Already done
-        return Ok(());
-    }
+/// How [`sort_lenient`] breaks ties between mods that no rule orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortMode {
+    /// Alphabetically by package id, like [`sort`].
+    #[default]
+    Alphabetical,
+    /// By their current position, so mods only move when a rule requires it.
+    Stable,
+}
 
-    let newly_marked = temp_marks.insert(node);
-    if !newly_marked {
-        // Must contain at least one cycle
-        return Err(SortError::CyclicError);
-    }
+/// A mod that was moved by [`sort_lenient`] in [`SortMode::Stable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub package_id: String,
+    /// Position before sorting.
+    pub from: usize,
+    /// Position after sorting.
+    pub to: usize,
+    /// The rules the old order broke that involve this mod.
+    pub because: Vec<SortEdge>,
+}
 
-    if let Some(d) = deps.get(node) {
-        for d in d {
-            visit(d, deps, output, unmarked, temp_marks)?;
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} moved from {} to {}",
+            self.package_id,
+            self.from + 1,
+            self.to + 1
+        )?;
+        if !self.because.is_empty() {
+            let because: Vec<_> = self.because.iter().map(ToString::to_string).collect();
+            write!(f, " ({})", because.join(", "))?;
         }
+        Ok(())
     }
+}
 
-    temp_marks.remove(node);
-    unmarked.remove(unmarked.iter().position(|&x| x == node).unwrap());
-    output.push(node.clone());
-
-    Ok(())
+/// The result of [`sort_lenient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortReport {
+    pub sorted: Vec<String>,
+    /// The soft rules that had to be ignored to break cycles.
+    pub ignored: Vec<SortEdge>,
+    /// The mods that had to move, only filled in [`SortMode::Stable`].
+    pub moved: Vec<Move>,
 }
 
-fn build_deps<'a, 'b>(
-    mods: &'a [String],
-    mod_metadata: &'b MutexGuard<HashMap<String, ModMetaData>>,
-) -> Result<HashMap<&'a String, Vec<&'b String>>, SortError> {
-    #[allow(clippy::needless_pass_by_value)]
-    fn ext<'c>(md: &mut Vec<&'c String>, m: Vec<&'c String>) {
-        md.extend(m.iter());
-    }
-    fn ins<'c, 'd>(
-        deps: &mut HashMap<&'c String, HashSet<&'d String>>,
-        m: &'c String,
-        mod_deps: Vec<&'d String>,
-    ) {
-        if !deps.contains_key(m) {
-            deps.insert(m, HashSet::new());
+/// Like [`sort_for_version`], but treats `loadBefore`/`loadAfter` as advisory, like the game does.
+///
+/// Whenever a cycle is found, one of its soft rules is ignored and sorting tries again,
+/// so only cycles made entirely of hard rules (`modDependencies`, `forceLoadBefore`, `forceLoadAfter`) fail.
+/// At most one rule is dropped per cycle, preferring `loadBefore` over `loadAfter`.
+/// Once it sorts, each ignored rule is added back if that no longer makes a cycle,
+/// so every rule in [`SortReport::ignored`] is needed to break one.
+/// That isn't always the smallest set of rules that would do it though, as finding that is NP-hard.
+///
+/// With [`SortMode::Stable`] the current order of `mods` is kept wherever the rules allow it,
+/// and [`SortReport::moved`] lists what had to change.
+///
+/// # Errors
+/// See [`sort`], although [`SortError::CyclicError`] will only contain hard rules.
+#[allow(clippy::implicit_hasher)]
+pub fn sort_lenient(
+    mods: &[String],
+    mod_metadata: &Arc<Mutex<HashMap<String, ModMetaData>>>,
+    version: Option<&str>,
+    sort_mode: SortMode,
+) -> Result<SortReport, SortError> {
+    let mmd = mod_metadata.lock_ignore_poisoned();
+    let versioned;
+    let mmd = match version {
+        Some(version) => {
+            versioned = effective_metadata(&mmd, mods, version);
+            &versioned
         }
-        deps.get_mut(m).unwrap().extend(mod_deps);
-    }
+        None => &*mmd,
+    };
 
-    let mut deps: HashMap<&'a String, HashSet<&'b String>> = HashMap::new();
+    let mut sorted = Vec::from(mods);
+    if sort_mode == SortMode::Alphabetical {
+        sorted.sort();
+    }
+    top_and_bottom(&mut sorted, mmd);
+    let mut graph = ModGraph::new(&sorted, mmd);
+    if graph.missing_metadata().next().is_some() {
+        return Err(SortError::MissingData);
+    }
+    let mut ignored = Vec::new();
 
-    for m in mods {
-        let mut mod_deps = Vec::new();
+    let order = loop {
+        match graph.toposort() {
+            Ok(order) => break order,
+            Err(cycle) => {
+                let Some(edge) = cycle
+                    .0
+                    .iter()
+                    .filter(|edge| edge.rule.is_soft())
+                    .max_by_key(|edge| edge.rule)
+                    .cloned()
+                else {
+                    return Err(SortError::CyclicError(cycle));
+                };
 
-        if let Some(mmd) = mod_metadata.get(m) {
-            if let Some(d) = mmd.modDependencies.as_ref() {
-                ext(
-                    &mut mod_deps,
-                    d.iter().filter_map(|d| d.packageId.as_ref()).collect(),
-                );
+                let removed = match (
+                    graph.index_of(&edge.package_id),
+                    graph.index_of(&edge.after),
+                ) {
+                    (Some(from), Some(to)) => graph.remove_edges(from, to),
+                    _ => Vec::new(),
+                };
+                ignored.push((edge, removed));
             }
-            if let Some(d) = mmd.loadAfter.as_ref() {
-                ext(&mut mod_deps, d.iter().collect());
+        }
+    };
+    let (order, ignored) = restore_ignored(&mut graph, order, ignored);
+
+    let sorted: Vec<String> = order
+        .into_iter()
+        .map(|i| graph.package_id(i).clone())
+        .collect();
+    let moved = match sort_mode {
+        SortMode::Alphabetical => Vec::new(),
+        SortMode::Stable => find_moves(mods, &sorted, &graph),
+    };
+    Ok(SortReport {
+        sorted,
+        ignored,
+        moved,
+    })
+}
+
+/// Adds the rules [`sort_lenient`] ignored (with the edges it removed for them) back into `graph`,
+/// wherever they no longer make a cycle, since breaking a later cycle can also break an earlier one.
+/// Returns the order with those rules followed, and the rules that still have to be ignored.
+fn restore_ignored(
+    graph: &mut ModGraph<'_>,
+    mut order: Vec<usize>,
+    mut ignored: Vec<(SortEdge, Vec<ModEdge>)>,
+) -> (Vec<usize>, Vec<SortEdge>) {
+    // the strictest rules are the ones most worth keeping
+    ignored.sort_by_key(|(edge, _)| edge.rule);
+
+    let mut still_ignored = Vec::new();
+    for (edge, removed) in ignored {
+        if let (Some(from), Some(to)) = (
+            graph.index_of(&edge.package_id),
+            graph.index_of(&edge.after),
+        ) {
+            for &e in &removed {
+                graph.add_edge(from, e);
             }
-            if let Some(d) = mmd.forceLoadAfter.as_ref() {
-                ext(&mut mod_deps, d.iter().collect());
+            if let Ok(new_order) = graph.toposort() {
+                order = new_order;
+                continue;
             }
-        } else {
-            return Err(SortError::MissingData);
+            graph.remove_edges(from, to);
         }
+        still_ignored.push(edge);
+    }
+    (order, still_ignored)
+}
 
-        ins(&mut deps, m, mod_deps);
+/// Maps each mod to where it first appears in `list`.
+fn positions(list: &[String]) -> HashMap<&str, usize> {
+    let mut positions = HashMap::with_capacity(list.len());
+    for (i, pid) in list.iter().enumerate() {
+        positions.entry(pid.as_str()).or_insert(i);
     }
+    positions
+}
 
-    // i am O(n^2)'s strongest soldier
-    // this should be done in the other loop but lifetimes are being annoying
-    let combinations = mods.iter().combinations(2);
-    for combination in combinations {
-        let (m, n) = (combination[0], combination[1]);
-        let mut mod_deps = Vec::new();
-
-        if let Some(mmd) = mod_metadata.get(n) {
-            if let Some(d) = mmd.loadBefore.as_ref() {
-                if d.contains(m) {
-                    mod_deps.push(mmd.packageId.as_ref().ok_or(SortError::MissingData)?);
-                }
+/// Works out which mods moved between `before` and `after`, using their longest common subsequence
+/// so that mods that only shifted along because of others aren't counted.
+fn find_moves(before: &[String], after: &[String], graph: &ModGraph<'_>) -> Vec<Move> {
+    let (pos_before, pos_after) = (positions(before), positions(after));
+
+    let mut moved: BTreeMap<usize, Move> = diff(before, after)
+        .into_iter()
+        .filter_map(|change| match change {
+            Change::Added(package_id) => Some(package_id),
+            _ => None,
+        })
+        .map(|package_id| {
+            let (from, to) = (
+                pos_before[package_id.as_str()],
+                pos_after[package_id.as_str()],
+            );
+            (
+                from,
+                Move {
+                    package_id,
+                    from,
+                    to,
+                    because: Vec::new(),
+                },
+            )
+        })
+        .collect();
+
+    // an edge explains a move if the old order broke it
+    for i in 0..graph.len() {
+        for &edge in graph.edges(i) {
+            let (Some(&from), Some(&to)) = (
+                pos_before.get(graph.package_id(i).as_str()),
+                pos_before.get(graph.package_id(edge.to).as_str()),
+            ) else {
+                continue;
+            };
+            if from > to {
+                continue;
             }
-            if let Some(d) = mmd.forceLoadBefore.as_ref() {
-                if d.contains(m) {
-                    mod_deps.push(mmd.packageId.as_ref().ok_or(SortError::MissingData)?);
+            let edge = graph.sort_edge(i, edge);
+            for pos in [from, to] {
+                if let Some(m) = moved.get_mut(&pos) {
+                    m.because.push(edge.clone());
                 }
             }
-        } else {
-            return Err(SortError::MissingData);
         }
-
-        ins(&mut deps, m, mod_deps);
     }
 
-    Ok(deps
+    moved
+        .into_values()
+        .map(|mut m| {
+            m.because.sort_by(|a, b| {
+                (a.rule, &a.package_id, &a.after).cmp(&(b.rule, &b.package_id, &b.after))
+            });
+            m
+        })
+        .collect()
+}
+
+fn sort_with(
+    mods: &[String],
+    mmd: &HashMap<String, ModMetaData>,
+) -> Result<Vec<String>, SortError> {
+    let mut sorted = Vec::from(mods);
+    sorted.sort();
+    top_and_bottom(&mut sorted, mmd);
+
+    let graph = ModGraph::new(&sorted, mmd);
+    if graph.missing_metadata().next().is_some() {
+        return Err(SortError::MissingData);
+    }
+    let order = graph.toposort().map_err(SortError::CyclicError)?;
+    Ok(order
         .into_iter()
-        .map(|(key, value)| (key, value.into_iter().collect()))
+        .map(|i| graph.package_id(i).clone())
         .collect())
 }
+
+/// Moves the mods that should be loaded first or last (see [`ModMetaData::load_top`] and [`ModMetaData::load_bottom`])
+/// to the start or end of the tie-breaking order,
+/// so they end up at the top or bottom unless other rules say otherwise.
+fn top_and_bottom(mods: &mut [String], mmd: &HashMap<String, ModMetaData>) {
+    mods.sort_by_key(|pid| match mmd.get(pid) {
+        Some(mmd) if mmd.load_top() => 0,
+        Some(mmd) if mmd.load_bottom() => 2,
+        _ => 1,
+    });
+}
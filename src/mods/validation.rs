@@ -1,4 +1,11 @@
-use crate::{traits::LockIgnorePoisoned, ModMetaData};
+use crate::{
+    mods::{
+        mod_meta_data::{effective_metadata, major_minor_version},
+        Dependency, Diagnostic, LoadOrderRule, ModGraph, Severity, SortRule,
+    },
+    traits::LockIgnorePoisoned,
+    ModMetaData,
+};
 use std::{
     collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
@@ -9,11 +16,11 @@ use std::{
 pub enum ModListValidationResult {
     Ok,
     Warn {
-        warnings: Vec<String>,
+        warnings: Vec<Diagnostic>,
     },
     Err {
-        warnings: Vec<String>,
-        errors: Vec<String>,
+        warnings: Vec<Diagnostic>,
+        errors: Vec<Diagnostic>,
     },
 }
 
@@ -22,64 +29,129 @@ pub enum ModListValidationResult {
 pub fn validate(
     mmd: &Arc<Mutex<HashMap<String, ModMetaData>>>,
     mod_list: &[String],
+) -> ModListValidationResult {
+    validate_for_version(mmd, mod_list, None)
+}
+
+/// Like [`validate`], but also checks the `*ByVersion` rules for the given game version, if any,
+/// and warns about mods that don't list it in their `supportedVersions`.
+/// See [`ModMetaData::effective_for`].
+#[allow(clippy::implicit_hasher)]
+#[must_use]
+pub fn validate_for_version(
+    mmd: &Arc<Mutex<HashMap<String, ModMetaData>>>,
+    mod_list: &[String],
+    version: Option<&str>,
 ) -> ModListValidationResult {
     log::debug!("Revalidating active mod list...");
 
     let mod_list: Vec<_> = mod_list.iter().map(|pid| pid.to_lowercase()).collect();
+    let mmd = mmd.lock_ignore_poisoned();
+    match version {
+        Some(version) => validate_with(
+            &effective_metadata(&mmd, &mod_list, version),
+            &mod_list,
+            Some(version),
+        ),
+        None => validate_with(&mmd, &mod_list, None),
+    }
+}
 
-    let mut loaded_so_far: HashSet<String> = HashSet::new();
-    let mut should_load_after: HashMap<String, &HashSet<String>> = HashMap::new(); // key should be loaded after anything in hashset
-    let mut incompatible: HashMap<String, &HashSet<String>> = HashMap::new();
+fn validate_with(
+    mmd: &HashMap<String, ModMetaData>,
+    mod_list: &[String],
+    version: Option<&str>,
+) -> ModListValidationResult {
+    let graph = ModGraph::new(mod_list, mmd);
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut incompatible: Vec<Diagnostic> = Vec::new();
 
-    let mut errors: Vec<String> = Vec::new();
-    let mut warnings: Vec<String> = Vec::new();
+    let mut seen: HashSet<&String> = HashSet::new();
+    let mut duplicates: HashSet<&String> = HashSet::new();
+    for package_id in mod_list {
+        if !seen.insert(package_id) && duplicates.insert(package_id) {
+            diagnostics.push(Diagnostic::Duplicate {
+                package_id: package_id.clone(),
+            });
+        }
+    }
 
-    let mmd = mmd.lock_ignore_poisoned();
-    for package_id in &mod_list {
-        if let Some(meta_data) = mmd.get(package_id) {
-            if let Some(incompat) = &meta_data.incompatibleWith {
-                incompatible.insert(package_id.to_lowercase(), incompat);
+    // the graph is indexed by first appearance, so `j > i` means `j` is loaded after `i`
+    for i in 0..graph.len() {
+        let package_id = graph.package_id(i);
+        let Some(meta_data) = graph.metadata(i) else {
+            diagnostics.push(Diagnostic::MissingMetadata {
+                package_id: package_id.clone(),
+            });
+            continue;
+        };
+
+        if let Some(version) = version {
+            if meta_data.supports_version(version) == Some(false) {
+                diagnostics.push(Diagnostic::UnsupportedVersion {
+                    package_id: package_id.clone(),
+                    version: String::from(major_minor_version(version)),
+                });
             }
-            if let Some(hard_reqs) = &meta_data.modDependencies {
-                for hard_req in hard_reqs.iter().filter_map(|r| r.packageId.as_ref()) {
-                    if !loaded_so_far.contains(&hard_req.to_lowercase()) {
-                        errors.push(format!("{package_id} requires {hard_req}"));
-                    }
+        }
+
+        let inactive_dependencies = meta_data
+            .modDependencies
+            .iter()
+            .flatten()
+            .filter_map(|d| d.packageId.as_ref())
+            .map(|d| d.to_lowercase())
+            .filter(|d| graph.index_of(d).is_none());
+        for dependency in inactive_dependencies {
+            diagnostics.push(Diagnostic::MissingDependency {
+                package_id: package_id.clone(),
+                dependency,
+                is_active: false,
+            });
+        }
+
+        for edge in graph.edges(i) {
+            let other = graph.package_id(edge.to);
+            let loaded_before = edge.to < i && graph.metadata(edge.to).is_some();
+            match edge.rule {
+                SortRule::ModDependency if !loaded_before => {
+                    diagnostics.push(Diagnostic::MissingDependency {
+                        package_id: package_id.clone(),
+                        dependency: other.clone(),
+                        is_active: true,
+                    });
                 }
-            }
-            if let Some(load_before) = &meta_data.loadBefore {
-                for lbf in loaded_so_far.intersection(load_before) {
-                    warnings.push(format!("{package_id} should be loaded before {lbf}"));
+                SortRule::LoadAfter if edge.to > i && graph.metadata(edge.to).is_some() => {
+                    diagnostics.push(Diagnostic::LoadOrderViolation {
+                        package_id: package_id.clone(),
+                        other: other.clone(),
+                        rule: LoadOrderRule::LoadAfter,
+                    });
                 }
-            }
-            for (a, load_after) in &should_load_after {
-                if load_after.contains(package_id) {
-                    warnings.push(format!("{a} should be loaded after {package_id}"));
+                // from `other`'s loadBefore
+                SortRule::LoadBefore if edge.to > i => {
+                    diagnostics.push(Diagnostic::LoadOrderViolation {
+                        package_id: other.clone(),
+                        other: package_id.clone(),
+                        rule: LoadOrderRule::LoadBefore,
+                    });
                 }
+                _ => {}
             }
-
-            if let Some(load_after) = &meta_data.loadAfter {
-                should_load_after.insert(package_id.to_lowercase(), load_after);
-            }
-            loaded_so_far.insert(package_id.clone());
-        } else {
-            warnings.push(format!("Couldn't find metadata for {package_id}"));
         }
-    }
 
-    for (a, incompatiblities) in incompatible {
-        for b in incompatiblities.intersection(&loaded_so_far) {
-            errors.push(format!("{a} is incompatible with {b}"));
+        for &j in graph.incompatible(i) {
+            if graph.metadata(j).is_some() {
+                incompatible.push(Diagnostic::Incompatible {
+                    package_id: package_id.clone(),
+                    other: graph.package_id(j).clone(),
+                });
+            }
         }
     }
 
-    if !errors.is_empty() {
-        ModListValidationResult::Err { warnings, errors }
-    } else if !warnings.is_empty() {
-        ModListValidationResult::Warn { warnings }
-    } else {
-        ModListValidationResult::Ok
-    }
+    diagnostics.append(&mut incompatible);
+    ModListValidationResult::from(diagnostics)
 }
 
 impl ModListValidationResult {
@@ -105,7 +177,7 @@ impl ModListValidationResult {
     }
 
     #[must_use]
-    pub fn warnings(&self) -> Option<&Vec<String>> {
+    pub fn warnings(&self) -> Option<&Vec<Diagnostic>> {
         match self {
             Self::Ok => None,
             Self::Warn { warnings }
@@ -117,7 +189,7 @@ impl ModListValidationResult {
     }
 
     #[must_use]
-    pub fn errors(&self) -> Option<&Vec<String>> {
+    pub fn errors(&self) -> Option<&Vec<Diagnostic>> {
         if let Self::Err {
             warnings: _,
             errors,
@@ -128,18 +200,122 @@ impl ModListValidationResult {
             None
         }
     }
+
+    /// Iterates over all the warnings, then all the errors.
+    pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.warnings().into_iter().chain(self.errors()).flatten()
+    }
+
+    /// Gets the dependencies that aren't installed at all,
+    /// with the details the mods requiring them give in their `About.xml`.
+    #[allow(clippy::implicit_hasher)]
+    #[must_use]
+    pub fn missing_dependencies(
+        &self,
+        mod_meta_data: &HashMap<String, ModMetaData>,
+    ) -> Vec<MissingDependency> {
+        let mut missing: Vec<MissingDependency> = Vec::new();
+
+        for diagnostic in self.diagnostics() {
+            let Diagnostic::MissingDependency {
+                package_id,
+                dependency,
+                ..
+            } = diagnostic
+            else {
+                continue;
+            };
+            if mod_meta_data.contains_key(dependency) {
+                continue;
+            }
+
+            if let Some(m) = missing.iter_mut().find(|m| &m.package_id == dependency) {
+                m.required_by.push(package_id.clone());
+                continue;
+            }
+            let details = mod_meta_data
+                .get(package_id)
+                .and_then(|mmd| find_dependency(mmd, dependency))
+                .cloned()
+                .unwrap_or_default();
+            missing.push(MissingDependency {
+                package_id: dependency.clone(),
+                details,
+                required_by: vec![package_id.clone()],
+            });
+        }
+
+        missing
+    }
+}
+
+/// A dependency that isn't installed, see [`ModListValidationResult::missing_dependencies`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingDependency {
+    /// Package id of the dependency (lowercase).
+    pub package_id: String,
+    /// What the first mod requiring it says about it, e.g. where to download it.
+    pub details: Dependency,
+    /// The active mods that require it.
+    pub required_by: Vec<String>,
+}
+
+impl MissingDependency {
+    /// Gets the name to show for the dependency.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.details
+            .displayName
+            .as_deref()
+            .unwrap_or(&self.package_id)
+    }
+}
+
+fn find_dependency<'a>(mmd: &'a ModMetaData, package_id: &str) -> Option<&'a Dependency> {
+    mmd.modDependencies
+        .iter()
+        .flatten()
+        .chain(
+            mmd.modDependenciesByVersion
+                .iter()
+                .flat_map(HashMap::values)
+                .flatten(),
+        )
+        .find(|d| {
+            d.packageId
+                .as_deref()
+                .is_some_and(|pid| pid.eq_ignore_ascii_case(package_id))
+        })
+}
+
+impl From<Vec<Diagnostic>> for ModListValidationResult {
+    fn from(diagnostics: Vec<Diagnostic>) -> Self {
+        let (errors, warnings): (Vec<_>, Vec<_>) = diagnostics
+            .into_iter()
+            .partition(|d| d.severity() == Severity::Error);
+
+        if !errors.is_empty() {
+            Self::Err { warnings, errors }
+        } else if !warnings.is_empty() {
+            Self::Warn { warnings }
+        } else {
+            Self::Ok
+        }
+    }
 }
 
 impl From<ModListValidationResult> for Result<(), Vec<String>> {
     fn from(res: ModListValidationResult) -> Self {
         match res {
             ModListValidationResult::Ok => Ok(()),
-            ModListValidationResult::Warn { warnings } => Err(warnings),
-            ModListValidationResult::Err { warnings, errors } => {
-                let mut e = warnings;
-                e.extend(errors);
-                Err(e)
+            ModListValidationResult::Warn { warnings } => {
+                Err(warnings.iter().map(ToString::to_string).collect())
             }
+            ModListValidationResult::Err { warnings, errors } => Err(warnings
+                .iter()
+                .chain(&errors)
+                .map(ToString::to_string)
+                .collect()),
         }
     }
 }
@@ -8,9 +8,15 @@ pub use paths::*;
 mod mods;
 pub use mods::*;
 
+mod profiles;
+pub use profiles::*;
+
 mod hint;
 pub use hint::*;
 
+mod setup;
+pub use setup::*;
+
 pub fn panel_using_widget(ui: &mut Ui, widget: impl Widget) {
     ui.add(widget);
 }
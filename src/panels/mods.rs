@@ -1,20 +1,38 @@
 use crate::{
-    helpers::vec_ops::MultiVecOp,
-    widgets::{ButtonsContainer, ModInfo, ModListing, ModListingItem, Status, STATUS_HEIGHT},
-    writer_thread, ModList, ModsConfig, RimPyConfig,
+    backups::Backups,
+    helpers::{
+        history::{History, HistoryStep},
+        paths::push_mods_config_path,
+        vec_ops::MultiVecOp,
+    },
+    serialization::{community_rules::CommunityRules, user_rules::UserRules},
+    traits::{LockIgnorePoisoned, LogIfErr},
+    watcher::{WatchEvent, Watcher},
+    widgets::{
+        AutoFixPreview, ButtonsContainer, DiskChoice, ExportWindow, MissingModsWindow, ModInfo,
+        ModListing, ModListingItem, ModsConfigChangedWindow, NoticeWindow, RestoreBackupWindow,
+        SaveStatus, SharedUserRules, Status, UserRulesEditor, STATUS_HEIGHT,
+    },
+    writer_thread, ModList, ModsConfig, ModsConfigFile, Settings, CHANGED_ACTIVE_MODS,
 };
-use eframe::egui::{Response, Ui, Widget};
+use eframe::egui::{Key, Modifiers, Response, Ui, Widget};
 use egui_extras::{Column, TableBuilder};
 use std::{
-    cell::RefCell,
-    path::PathBuf,
+    cell::{Cell, OnceCell, RefCell},
+    collections::HashSet,
+    path::{Path, PathBuf},
     rc::Rc,
     sync::{
         mpsc::{channel, Receiver, Sender, SyncSender, TryRecvError},
         Arc,
     },
+    time::{Duration, Instant},
 };
 
+/// How long to wait after the last change on disk before reading the changes,
+/// so e.g. a mod that's still being downloaded is only read once it's done.
+const SETTLE_TIME: Duration = Duration::from_millis(750);
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct ModsPanel<'a> {
@@ -23,7 +41,7 @@ pub struct ModsPanel<'a> {
     active: Rc<RefCell<ModListing<'a>>>,
     mod_info_widget: ModInfo,
     btns: ButtonsContainer<'a>,
-    rimpy_config: Rc<RimPyConfig>,
+    settings: Rc<Settings>,
     mods_config: Arc<ModsConfig>,
     direct_vecop_rx: Receiver<MultiVecOp<'a, ModListingItem<'a>>>,
     direct_vecop_tx: Sender<MultiVecOp<'a, ModListingItem<'a>>>,
@@ -31,48 +49,138 @@ pub struct ModsPanel<'a> {
     change_mod_list_tx: Sender<Vec<String>>,
     selected: Rc<RefCell<Option<String>>>,
     status: Status<'a>,
+    auto_fix_preview: AutoFixPreview,
+    notice_window: NoticeWindow,
+    export_window: ExportWindow<'a>,
+    missing_mods_window: MissingModsWindow,
+    restore_backup_window: RestoreBackupWindow<'a>,
+    save_status: Rc<RefCell<SaveStatus>>,
+    /// Past states of the active mods, for undo/redo.
+    history: Rc<RefCell<History<Vec<String>>>>,
+    history_rx: Receiver<HistoryStep>,
+    history_tx: Sender<HistoryStep>,
+    writer_thread_tx: SyncSender<writer_thread::Message>,
+    user_rules: SharedUserRules,
+    /// Read the first time a new mod is found.
+    community_rules: OnceCell<Option<CommunityRules>>,
+    watcher: Option<Watcher>,
+    /// Whether the watcher has been told how to wake up the UI.
+    waker_set: bool,
+    /// Changes on disk waiting to settle, and when the last one was seen.
+    fs_changes: HashSet<WatchEvent>,
+    last_fs_change: Option<Instant>,
+    mods_config_path: Option<PathBuf>,
+    /// The active mods in `ModsConfig.xml`, as of when it was last read or saved.
+    on_disk: Vec<String>,
+    mods_config_changed_window: ModsConfigChangedWindow<'a>,
     should_update_status: *mut bool,
 }
 
-impl ModsPanel<'_> {
+impl<'a> ModsPanel<'a> {
     /// Makes a new mods panel
     #[must_use]
-    #[allow(clippy::too_many_arguments)] // stay mad
+    #[allow(clippy::too_many_arguments, clippy::too_many_lines)] // stay mad
     pub fn new(
-        rimpy_config: Rc<RimPyConfig>,
+        settings: Rc<Settings>,
         mods_config: Arc<ModsConfig>,
         mods: ModList,
         hint_tx: &SyncSender<String>,
         writer_thread_tx: SyncSender<writer_thread::Message>,
+        backups: Option<Backups>,
         exe_path: PathBuf,
         args: Option<String>,
     ) -> Self {
         let selected = Rc::new(RefCell::new(None));
         let (direct_vecop_tx, direct_vecop_rx) = channel();
         let (change_mod_list_tx, change_mod_list_rx) = channel();
+        let (history_tx, history_rx) = channel();
 
         let (active, inactive) = ModListing::new_pair(
             mods_config.activeMods.clone(),
             &mods,
             &selected,
+            mods_config.version.as_deref(),
             &direct_vecop_tx,
         );
         let active = Rc::new(RefCell::new(active));
 
-        let mod_info_widget = ModInfo::new(mods.mods.clone(), selected.clone());
+        let user_rules =
+            SharedUserRules::new(UserRules::load(&settings), UserRules::path(&settings));
+        let mod_info_widget = ModInfo::new(
+            mods.mods.clone(),
+            selected.clone(),
+            UserRulesEditor::new(user_rules.clone()),
+        );
+
+        let pending_auto_fix = Rc::new(RefCell::new(None));
+        let auto_fix_preview = AutoFixPreview::new(
+            pending_auto_fix.clone(),
+            mods.mods.clone(),
+            change_mod_list_tx.clone(),
+        );
+
+        let notice = Rc::new(RefCell::new(None));
+        let notice_window = NoticeWindow::new(notice.clone());
+
+        let pending_import = Rc::new(RefCell::new(None));
+        let missing_mods_window =
+            MissingModsWindow::new(pending_import.clone(), change_mod_list_tx.clone());
+
+        let export_window_open = Rc::new(Cell::new(false));
+        let export_window = ExportWindow::new(
+            export_window_open.clone(),
+            writer_thread_tx.clone(),
+            active.clone(),
+            mods.mods.clone(),
+            mods_config.version.clone(),
+        );
+
+        let restore_backup_window_open = Rc::new(Cell::new(false));
+        let restore_backup_window = RestoreBackupWindow::new(
+            restore_backup_window_open.clone(),
+            backups,
+            active.clone(),
+            mods.mods.clone(),
+            change_mod_list_tx.clone(),
+        );
+
+        let save_status = Rc::new(RefCell::new(SaveStatus::default()));
+        let history = Rc::new(RefCell::new(History::default()));
 
         let btns = ButtonsContainer::generate(
             hint_tx,
-            writer_thread_tx,
+            writer_thread_tx.clone(),
             change_mod_list_tx.clone(),
             active.clone(),
             mods.mods.clone(),
+            mods_config.version.clone(),
+            pending_auto_fix,
+            notice,
+            user_rules.clone(),
+            pending_import,
+            export_window_open,
+            restore_backup_window_open,
+            save_status.clone(),
+            history_tx.clone(),
+            history.clone(),
             exe_path,
             args,
         );
 
+        let (mods_config_path, watcher) = Self::watch(&settings);
+        let mods_config_changed_window =
+            ModsConfigChangedWindow::new(active.clone(), mods.mods.clone());
+
         let should_update_status = Box::into_raw(Box::from(true));
-        let status = Status::new(active.clone(), mods.mods.clone(), should_update_status);
+        let status = Status::new(
+            active.clone(),
+            mods.mods.clone(),
+            mods_config.version.clone(),
+            settings.folders.steamcmd.clone(),
+            should_update_status,
+        );
+
+        let on_disk = mods_config.activeMods.clone();
 
         Self {
             mods,
@@ -80,7 +188,7 @@ impl ModsPanel<'_> {
             active,
             mod_info_widget,
             btns,
-            rimpy_config,
+            settings,
             mods_config,
             direct_vecop_rx,
             direct_vecop_tx,
@@ -88,13 +196,192 @@ impl ModsPanel<'_> {
             change_mod_list_tx,
             selected,
             status,
+            auto_fix_preview,
+            notice_window,
+            export_window,
+            missing_mods_window,
+            restore_backup_window,
+            save_status,
+            history,
+            history_rx,
+            history_tx,
+            writer_thread_tx,
+            user_rules,
+            community_rules: OnceCell::new(),
+            watcher,
+            waker_set: false,
+            fs_changes: HashSet::new(),
+            last_fs_change: None,
+            mods_config_path,
+            on_disk,
+            mods_config_changed_window,
             should_update_status,
         }
     }
 
+    /// The active mods listing, for widgets outside the panel to read.
+    #[must_use]
+    pub fn active_mod_listing_ref(&self) -> Rc<RefCell<ModListing<'a>>> {
+        self.active.clone()
+    }
+
+    /// Replaces the active mods from outside the panel, the same way the buttons do.
+    #[must_use]
+    pub fn change_mod_list_tx(&self) -> Sender<Vec<String>> {
+        self.change_mod_list_tx.clone()
+    }
+
+    /// Starts watching the mod folders and `ModsConfig.xml` in `settings`.
+    fn watch(settings: &Settings) -> (Option<PathBuf>, Option<Watcher>) {
+        let mods_config_path = settings
+            .folders
+            .config_folder
+            .clone()
+            .map(push_mods_config_path);
+        let watcher = mods_config_path.as_ref().and_then(|mods_config_path| {
+            Watcher::new(&settings.folders.mod_dirs(), mods_config_path).log_if_err()
+        });
+        (mods_config_path, watcher)
+    }
+
     fn tick(&mut self) {
         self.run_vecops();
         self.change_mod_lists();
+        self.step_history();
+        self.check_save();
+        self.check_fs_changes();
+    }
+
+    fn check_save(&mut self) {
+        let mut save_status = self.save_status.borrow_mut();
+        if save_status.is_pending() {
+            save_status.poll(&Vec::from(&*self.active.borrow()));
+        }
+        if let Some(saved) = save_status.take_saved() {
+            self.on_disk = saved;
+        }
+    }
+
+    /// Reads all the mods again, replacing the ones already known, then rebuilds the listings.
+    /// Used when changes on disk might have been missed.
+    pub fn reload_mods(&mut self) {
+        match ModList::try_from(&*self.settings) {
+            Ok(mod_list) => {
+                let fresh = std::mem::take(&mut *mod_list.mods.lock_ignore_poisoned());
+                // replaced in place, as the widgets share the map
+                *self.mods.mods.lock_ignore_poisoned() = fresh;
+                self.on_mods_change();
+            }
+            Err(err) => log::error!("{err}"),
+        }
+    }
+
+    /// Rebuilds the listings after mods were added, updated or removed, keeping the active mods.
+    fn on_mods_change(&mut self) {
+        let active_mods = Vec::from(&*self.active.borrow());
+        self.replace_mod_lists(active_mods);
+
+        // SAFETY: only using this bool on the main thread
+        unsafe {
+            *self.should_update_status = true;
+        }
+    }
+
+    /// Collects what the watcher saw, then deals with it once it's been quiet for [`SETTLE_TIME`].
+    fn check_fs_changes(&mut self) {
+        let Some(watcher) = self.watcher.as_ref() else {
+            return;
+        };
+        for event in watcher.try_iter() {
+            self.fs_changes.insert(event);
+            self.last_fs_change = Some(Instant::now());
+        }
+        if self
+            .last_fs_change
+            .is_none_or(|last| last.elapsed() < SETTLE_TIME)
+        {
+            return;
+        }
+        self.last_fs_change = None;
+
+        let events = std::mem::take(&mut self.fs_changes);
+        let rescan = events.contains(&WatchEvent::Rescan);
+        if rescan {
+            self.reload_mods();
+        } else {
+            let mut mods_changed = false;
+            for event in &events {
+                if let WatchEvent::ModChanged(mod_dir) = event {
+                    mods_changed |= !self.rescan_mod(mod_dir).is_empty();
+                }
+            }
+            if mods_changed {
+                self.on_mods_change();
+            }
+        }
+        if rescan || events.contains(&WatchEvent::ModsConfigChanged) {
+            self.check_mods_config();
+        }
+    }
+
+    /// Reads the mod in `mod_dir` again, giving new mods their community and user rules.
+    fn rescan_mod(&self, mod_dir: &Path) -> Vec<String> {
+        let community_rules = self
+            .community_rules
+            .get_or_init(|| CommunityRules::load(&self.settings));
+        self.mods.rescan_mod(mod_dir, |mmd| {
+            let pid = mmd.packageId.as_deref().unwrap_or_default().to_lowercase();
+            mmd.community_rules = community_rules
+                .as_ref()
+                .and_then(|rules| rules.rules.get(&pid).cloned());
+            mmd.user_rules = self.user_rules.get(&pid);
+        })
+    }
+
+    /// Reads `ModsConfig.xml` again after it changed on disk,
+    /// asking what to do if its active mods are new and differ from the ones here.
+    fn check_mods_config(&mut self) {
+        let Some(path) = self.mods_config_path.as_ref() else {
+            return;
+        };
+        // might be half-written, in which case there'll be another change once it's done
+        let Some(mods_config_file) = ModsConfigFile::try_from(path.as_path()).log_if_err() else {
+            return;
+        };
+        let mods_config = Arc::new(mods_config_file.mods_config.clone());
+        // so saving keeps whatever else changed in the file
+        self.writer_thread_tx
+            .try_send(writer_thread::Message::SetModsConfig(Arc::new(
+                mods_config_file,
+            )))
+            .log_if_err();
+        let on_disk = mods_config.activeMods.clone();
+        self.mods_config = mods_config;
+
+        if on_disk == Vec::from(&*self.active.borrow()) {
+            self.on_disk = on_disk;
+            CHANGED_ACTIVE_MODS.reset();
+        } else if on_disk != self.on_disk {
+            self.mods_config_changed_window.show(on_disk);
+        }
+    }
+
+    fn on_disk_choice(&mut self, choice: DiskChoice) {
+        match choice {
+            DiskChoice::Load(on_disk) => {
+                let before = Vec::from(&*self.active.borrow());
+                self.replace_mod_lists(on_disk.clone());
+                self.record_history(before);
+                self.on_active_modlist_change();
+                // it's what's on disk now, so there's nothing to save
+                CHANGED_ACTIVE_MODS.reset();
+                self.on_disk = on_disk;
+            }
+            DiskChoice::Keep(on_disk) => {
+                self.on_disk = on_disk;
+                CHANGED_ACTIVE_MODS.set();
+            }
+        }
     }
 
     /// Used to update various crate-wide state.
@@ -107,19 +394,29 @@ impl ModsPanel<'_> {
         }
     }
 
+    /// Records the active mods from before a change in the undo history,
+    /// unless the change didn't actually do anything.
+    fn record_history(&self, before: Vec<String>) {
+        if before != Vec::from(&*self.active.borrow()) {
+            self.history.borrow_mut().record(before);
+        }
+    }
+
     fn run_vecops(&mut self) {
         let mut active_guard = self.active.borrow_mut();
         let mut changed = false;
+        let mut before = None;
 
         loop {
             let res = self.direct_vecop_rx.try_recv().map(|msg| {
+                before.get_or_insert_with(|| Vec::from(&*active_guard));
                 msg.run(
                     (&mut self.inactive.items).into(),
                     (&mut active_guard.items).into(),
                 )
             });
             match res {
-                Ok(Ok(_)) => changed = true,
+                Ok(Ok(())) => changed = true,
                 Ok(Err(err)) => log::error!("{err:?}"),
                 Err(TryRecvError::Empty) => break,
                 Err(TryRecvError::Disconnected) => {
@@ -131,26 +428,34 @@ impl ModsPanel<'_> {
         drop(active_guard);
 
         if changed {
+            if let Some(before) = before {
+                self.record_history(before);
+            }
             self.on_active_modlist_change();
         }
     }
 
+    /// Rebuilds both mod listings, with the given mods active.
+    fn replace_mod_lists(&mut self, active_mods: Vec<String>) {
+        let (active, inactive) = ModListing::new_pair(
+            active_mods,
+            &self.mods,
+            &self.selected,
+            self.mods_config.version.as_deref(),
+            &self.direct_vecop_tx,
+        );
+        *self.active.borrow_mut() = active;
+        self.inactive = inactive;
+    }
+
     fn change_mod_lists(&mut self) {
-        let mut active_guard = self.active.borrow_mut();
-        let mut changed = false;
+        let mut before = None;
 
         loop {
             match self.change_mod_list_rx.try_recv() {
                 Ok(new_mod_list) => {
-                    let (active, inactive) = ModListing::new_pair(
-                        new_mod_list,
-                        &self.mods,
-                        &self.selected,
-                        &self.direct_vecop_tx,
-                    );
-                    *active_guard = active;
-                    self.inactive = inactive;
-                    changed = true;
+                    before.get_or_insert_with(|| Vec::from(&*self.active.borrow()));
+                    self.replace_mod_lists(new_mod_list);
                 }
                 Err(TryRecvError::Empty) => break,
                 Err(TryRecvError::Disconnected) => {
@@ -159,13 +464,57 @@ impl ModsPanel<'_> {
             }
         }
 
-        drop(active_guard);
-
-        if changed {
+        if let Some(before) = before {
+            self.record_history(before);
             self.on_active_modlist_change();
         }
     }
 
+    /// Undoes or redoes changes to the active mods, as asked for by the buttons or shortcuts.
+    /// Going back and forth isn't recorded in the history itself.
+    fn step_history(&mut self) {
+        loop {
+            match self.history_rx.try_recv() {
+                Ok(step) => {
+                    let current = Vec::from(&*self.active.borrow());
+                    let state = self.history.borrow_mut().step(step, current);
+                    if let Some(state) = state {
+                        self.replace_mod_lists(state);
+                        self.on_active_modlist_change();
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    panic!("mods panel mpsc channel unexpectedly disconnected")
+                }
+            }
+        }
+    }
+
+    /// Ctrl+Z to undo, and Ctrl+Y or Ctrl+Shift+Z to redo,
+    /// unless they're meant for a text field.
+    fn handle_shortcuts(&self, ui: &Ui) {
+        if ui.ctx().wants_keyboard_input() {
+            return;
+        }
+
+        let mut input = ui.input_mut();
+        let mut steps = Vec::new();
+        if input.consume_key(Modifiers::COMMAND, Key::Z) {
+            steps.push(HistoryStep::Undo);
+        }
+        if input.consume_key(Modifiers::COMMAND, Key::Y)
+            || input.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z)
+        {
+            steps.push(HistoryStep::Redo);
+        }
+        drop(input);
+
+        for step in steps {
+            self.history_tx.send(step).log_if_err();
+        }
+    }
+
     fn render(&mut self, ui: &mut Ui) -> Response {
         let scope = ui.scope(|ui| {
             let w = ui.available_width() / 10.0;
@@ -198,16 +547,47 @@ impl ModsPanel<'_> {
                     });
                 });
 
+            if self.mod_info_widget.take_rules_changed() {
+                // SAFETY: only using this bool on the main thread
+                unsafe {
+                    *self.should_update_status = true;
+                }
+            }
+
             ui.add(&mut self.status);
+            ui.add(&mut self.auto_fix_preview);
+            ui.add(&mut self.notice_window);
+            ui.add(&mut self.export_window);
+            ui.add(&mut self.missing_mods_window);
+            ui.add(&mut self.restore_backup_window);
+            ui.add(&mut self.mods_config_changed_window);
         });
 
+        if let Some(choice) = self.mods_config_changed_window.take_choice() {
+            self.on_disk_choice(choice);
+        }
+
         scope.response
     }
 }
 
 impl Widget for &mut ModsPanel<'_> {
     fn ui(self, ui: &mut Ui) -> Response {
+        self.handle_shortcuts(ui);
+        if let Some(watcher) = self.watcher.as_ref().filter(|_| !self.waker_set) {
+            let ctx = ui.ctx().clone();
+            watcher.set_waker(move || ctx.request_repaint());
+            self.waker_set = true;
+        }
         self.tick();
+        if self.last_fs_change.is_some() {
+            // deal with the changes once they've settled, even if nothing else happens
+            ui.ctx().request_repaint_after(SETTLE_TIME);
+        }
+        if self.save_status.borrow().is_pending() {
+            // keep checking for the writer thread's reply, even if nothing else happens
+            ui.ctx().request_repaint();
+        }
         self.render(ui)
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
     helpers::traits::{LogIfErr, ToStringOrEmpty},
-    serialization::rimpy_config::RimPyConfig,
+    serialization::settings::Settings,
 };
 use eframe::egui::{Response, Ui, Widget};
 use egui_extras::{Column, TableBuilder, TableRow};
@@ -8,20 +8,16 @@ use std::{path::PathBuf, rc::Rc, sync::mpsc::SyncSender};
 
 #[derive(Debug, Clone)]
 pub struct PathsPanel {
-    rimpy_config: Rc<RimPyConfig>,
+    settings: Rc<Settings>,
     version: String,
     hint_tx: SyncSender<String>,
 }
 
 impl PathsPanel {
     #[must_use]
-    pub fn new(
-        rimpy_config: Rc<RimPyConfig>,
-        version: String,
-        hint_tx: SyncSender<String>,
-    ) -> Self {
+    pub fn new(settings: Rc<Settings>, version: String, hint_tx: SyncSender<String>) -> Self {
         Self {
-            rimpy_config,
+            settings,
             version,
             hint_tx,
         }
@@ -30,13 +26,13 @@ impl PathsPanel {
 
 impl Widget for &mut PathsPanel {
     fn ui(self, ui: &mut Ui) -> Response {
-        build_table(ui, &self.rimpy_config, &self.version, &self.hint_tx)
+        build_table(ui, &self.settings, &self.version, &self.hint_tx)
     }
 }
 
 fn build_table(
     ui: &mut Ui,
-    conf: &Rc<RimPyConfig>,
+    conf: &Rc<Settings>,
     version: &String,
     hint_tx: &SyncSender<String>,
 ) -> Response {
@@ -66,14 +62,14 @@ fn build_table(
 
 fn row_1(row: &mut TableRow, version: &String, hint_tx: &SyncSender<String>) {
     row.col(|ui| {
-        open_rimpy_button(ui, hint_tx);
+        open_settings_button(ui, hint_tx);
     });
     row.col(|ui| {
         ui.label(format!("Game version: {version}"));
     });
 }
 
-fn row_2(row: &mut TableRow, conf: &Rc<RimPyConfig>, hint_tx: &SyncSender<String>) {
+fn row_2(row: &mut TableRow, conf: &Rc<Settings>, hint_tx: &SyncSender<String>) {
     row.col(|ui| {
         open_button(
             ui,
@@ -88,7 +84,7 @@ fn row_2(row: &mut TableRow, conf: &Rc<RimPyConfig>, hint_tx: &SyncSender<String
     });
 }
 
-fn row_3(row: &mut TableRow, conf: &Rc<RimPyConfig>, hint_tx: &SyncSender<String>) {
+fn row_3(row: &mut TableRow, conf: &Rc<Settings>, hint_tx: &SyncSender<String>) {
     row.col(|ui| {
         open_button(
             ui,
@@ -103,7 +99,7 @@ fn row_3(row: &mut TableRow, conf: &Rc<RimPyConfig>, hint_tx: &SyncSender<String
     });
 }
 
-fn row_4(row: &mut TableRow, conf: &Rc<RimPyConfig>, hint_tx: &SyncSender<String>) {
+fn row_4(row: &mut TableRow, conf: &Rc<Settings>, hint_tx: &SyncSender<String>) {
     row.col(|ui| {
         open_button(
             ui,
@@ -118,7 +114,7 @@ fn row_4(row: &mut TableRow, conf: &Rc<RimPyConfig>, hint_tx: &SyncSender<String
     });
 }
 
-fn row_5(row: &mut TableRow, conf: &Rc<RimPyConfig>, hint_tx: &SyncSender<String>) {
+fn row_5(row: &mut TableRow, conf: &Rc<Settings>, hint_tx: &SyncSender<String>) {
     row.col(|ui| {
         open_button(
             ui,
@@ -133,17 +129,17 @@ fn row_5(row: &mut TableRow, conf: &Rc<RimPyConfig>, hint_tx: &SyncSender<String
     });
 }
 
-pub fn open_rimpy_button(ui: &mut Ui, hint_tx: &SyncSender<String>) {
+pub fn open_settings_button(ui: &mut Ui, hint_tx: &SyncSender<String>) {
     let settings_btn = ui.button("Settings");
     if settings_btn.clicked() {
-        crate::helpers::config::get_config_dir()
+        crate::helpers::config::get_rimrs_dir()
             .map(open::that)
             .log_if_err();
     }
     if settings_btn.hovered {
         hint_tx
             .try_send(String::from(
-                "Open folder where RimPy stores its config files",
+                "Open folder where rimrs stores its settings file",
             ))
             .ok();
     }
@@ -0,0 +1,304 @@
+use crate::{
+    profiles::{ProfileError, Profiles},
+    traits::LogIfErr,
+    widgets::ModListing,
+    CHANGED_ACTIVE_MODS,
+};
+use eframe::egui::{self, ComboBox, Response, Ui, Widget};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::mpsc::{Sender, SyncSender},
+};
+
+/// What a profile name is being asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NameAction {
+    Create,
+    Rename,
+    Duplicate,
+}
+
+impl NameAction {
+    fn title(self) -> &'static str {
+        match self {
+            Self::Create => "New profile",
+            Self::Rename => "Rename profile",
+            Self::Duplicate => "Duplicate profile",
+        }
+    }
+}
+
+/// The window currently open over the profile selector, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Dialog {
+    Name(NameAction, String),
+    /// Switching to the profile would lose unsaved changes.
+    ConfirmSwitch(String),
+    ConfirmDelete(String),
+}
+
+/// Selects which of the [`Profiles`] the active mods come from,
+/// and creates, renames, duplicates or deletes them.
+#[derive(Debug)]
+pub struct ProfilesPanel<'a> {
+    profiles: Profiles,
+    names: Vec<String>,
+    current: Option<String>,
+    active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+    change_mod_list_tx: Sender<Vec<String>>,
+    version: Option<String>,
+    hint_tx: SyncSender<String>,
+    dialog: Option<Dialog>,
+    error: Option<String>,
+}
+
+impl<'a> ProfilesPanel<'a> {
+    #[must_use]
+    pub fn new(
+        profiles: Profiles,
+        active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+        change_mod_list_tx: Sender<Vec<String>>,
+        version: Option<String>,
+        hint_tx: SyncSender<String>,
+    ) -> Self {
+        let mut panel = Self {
+            current: profiles.current(),
+            profiles,
+            names: Vec::new(),
+            active_mod_listing_ref,
+            change_mod_list_tx,
+            version,
+            hint_tx,
+            dialog: None,
+            error: None,
+        };
+        panel.refresh();
+        panel
+    }
+
+    fn refresh(&mut self) {
+        self.names = self.profiles.list().log_if_err().unwrap_or_default();
+        self.current = self.profiles.current();
+    }
+
+    fn active_mods(&self) -> Vec<String> {
+        Vec::from(&*self.active_mod_listing_ref.borrow())
+    }
+
+    /// Whether the active mods differ from what's stored in the current profile,
+    /// or from `ModsConfig.xml` if there isn't one.
+    fn has_unsaved_changes(&self) -> bool {
+        match &self.current {
+            Some(name) => self
+                .profiles
+                .load(name)
+                .map_or(true, |mods| mods != self.active_mods()),
+            None => CHANGED_ACTIVE_MODS.check(),
+        }
+    }
+
+    /// Shows the outcome of a change to the profiles, and reads them again.
+    fn finish(&mut self, res: Result<(), ProfileError>) {
+        match res {
+            Ok(()) => {
+                self.error = None;
+                self.dialog = None;
+            }
+            Err(err) => {
+                log::error!("{err}");
+                self.error = Some(err.to_string());
+            }
+        }
+        self.refresh();
+    }
+
+    fn try_switch(&mut self, name: String) {
+        if self.has_unsaved_changes() {
+            self.dialog = Some(Dialog::ConfirmSwitch(name));
+        } else {
+            let res = self.switch(&name);
+            self.finish(res);
+        }
+    }
+
+    /// Replaces the active mods with the profile's.
+    fn switch(&self, name: &str) -> Result<(), ProfileError> {
+        let mods = self.profiles.load(name)?;
+        self.profiles.set_current(Some(name))?;
+        self.change_mod_list_tx.send(mods).log_if_err();
+        Ok(())
+    }
+
+    fn save_current(&self) -> Result<(), ProfileError> {
+        match &self.current {
+            Some(name) => self
+                .profiles
+                .save(name, &self.active_mods(), self.version.as_deref()),
+            None => Ok(()),
+        }
+    }
+
+    fn apply_name(&self, action: NameAction, new_name: &str) -> Result<(), ProfileError> {
+        let new_name = Profiles::validate_name(new_name)?;
+        match (action, &self.current) {
+            (NameAction::Create, _) => {
+                self.profiles
+                    .create(new_name, &self.active_mods(), self.version.as_deref())?;
+            }
+            (NameAction::Rename, Some(name)) => self.profiles.rename(name, new_name)?,
+            (NameAction::Duplicate, Some(name)) => self.profiles.duplicate(name, new_name)?,
+            (NameAction::Rename | NameAction::Duplicate, None) => return Ok(()),
+        }
+        if action != NameAction::Rename {
+            // the active mods stay as they are, the new profile just becomes the current one
+            self.profiles.set_current(Some(new_name))?;
+        }
+        Ok(())
+    }
+
+    fn button(&self, ui: &mut Ui, enabled: bool, label: &str, hint: &str) -> bool {
+        let resp = ui.add_enabled(enabled, egui::Button::new(label));
+        if resp.hovered() {
+            self.hint_tx.try_send(String::from(hint)).ok();
+        }
+        resp.clicked()
+    }
+
+    fn selector(&mut self, ui: &mut Ui) {
+        let mut selected = self.current.clone();
+        ui.horizontal(|ui| {
+            ui.label("Profile:");
+            ComboBox::from_id_source("profile_selector")
+                .width(150.0)
+                .selected_text(self.current.as_deref().unwrap_or("(none)"))
+                .show_ui(ui, |ui| {
+                    for name in &self.names {
+                        ui.selectable_value(&mut selected, Some(name.clone()), name);
+                    }
+                });
+        });
+        if let Some(name) = selected.filter(|name| self.current.as_ref() != Some(name)) {
+            self.try_switch(name);
+        }
+
+        let has_current = self.current.is_some();
+        ui.horizontal(|ui| {
+            if self.button(
+                ui,
+                has_current,
+                "Save",
+                "Store the active mods in the current profile",
+            ) {
+                let res = self.save_current();
+                self.finish(res);
+            }
+            if self.button(ui, true, "New", "Make a profile from the active mods") {
+                self.dialog = Some(Dialog::Name(NameAction::Create, String::new()));
+            }
+            if self.button(ui, has_current, "Rename", "Rename the current profile") {
+                let name = self.current.clone().unwrap_or_default();
+                self.dialog = Some(Dialog::Name(NameAction::Rename, name));
+            }
+            if self.button(ui, has_current, "Duplicate", "Copy the current profile") {
+                let name = format!("{} (copy)", self.current.as_deref().unwrap_or_default());
+                self.dialog = Some(Dialog::Name(NameAction::Duplicate, name));
+            }
+            if self.button(ui, has_current, "Delete", "Delete the current profile") {
+                self.dialog = self.current.clone().map(Dialog::ConfirmDelete);
+            }
+        });
+
+        if let Some(err) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, err);
+        }
+    }
+
+    fn dialog(&mut self, ui: &Ui) {
+        let Some(mut dialog) = self.dialog.take() else {
+            return;
+        };
+        let mut open = true;
+        let mut done = false;
+        let title = match &dialog {
+            Dialog::Name(action, _) => action.title(),
+            Dialog::ConfirmSwitch(_) => "Unsaved changes",
+            Dialog::ConfirmDelete(_) => "Delete profile",
+        };
+
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ui.ctx(), |ui| match &mut dialog {
+                Dialog::Name(action, name) => {
+                    let resp = ui.text_edit_singleline(name);
+                    let entered = resp.lost_focus() && ui.input().key_pressed(egui::Key::Enter);
+                    if let Some(err) = &self.error {
+                        ui.colored_label(ui.visuals().error_fg_color, err);
+                    }
+                    let valid = Profiles::validate_name(name).is_ok();
+                    if ui.add_enabled(valid, egui::Button::new("OK")).clicked()
+                        || (entered && valid)
+                    {
+                        let res = self.apply_name(*action, name);
+                        done = res.is_ok();
+                        self.finish(res);
+                    }
+                }
+                Dialog::ConfirmSwitch(name) => {
+                    match &self.current {
+                        Some(current) => ui.label(format!(
+                            "The active mods have changes that aren't in \"{current}\"."
+                        )),
+                        None => ui.label("The active mods have changes that haven't been saved."),
+                    };
+                    ui.horizontal(|ui| {
+                        if let Some(current) = &self.current {
+                            if ui
+                                .button(format!("Save to \"{current}\" and switch"))
+                                .clicked()
+                            {
+                                let res = self.save_current().and_then(|()| self.switch(name));
+                                done = true;
+                                self.finish(res);
+                            }
+                        }
+                        if ui.button("Switch anyway").clicked() {
+                            let res = self.switch(name);
+                            done = true;
+                            self.finish(res);
+                        }
+                        done |= ui.button("Cancel").clicked();
+                    });
+                }
+                Dialog::ConfirmDelete(name) => {
+                    ui.label(format!(
+                        "Delete the profile \"{name}\"? This can't be undone."
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button("Delete").clicked() {
+                            let res = self.profiles.delete(name);
+                            done = true;
+                            self.finish(res);
+                        }
+                        done |= ui.button("Cancel").clicked();
+                    });
+                }
+            });
+
+        if open && !done {
+            self.dialog = Some(dialog);
+        }
+    }
+}
+
+impl Widget for &mut ProfilesPanel<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        ui.scope(|ui| {
+            self.selector(ui);
+            self.dialog(ui);
+        })
+        .response
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{
+    helpers::{
+        folders::{Folder, FolderError},
+        paths::path_to_str,
+    },
+    traits::{LogIfErr, PushChained},
+    Settings,
+};
+use eframe::egui::{Color32, Response, Ui, Widget};
+use egui_extras::{Column, TableBuilder};
+use std::path::PathBuf;
+
+/// Shown instead of the mods panel when a folder rimrs needs is missing or doesn't look right.
+/// Lets the user pick each folder, then hands the [`Settings`] back once they're all valid.
+#[derive(Debug)]
+pub struct SetupPanel {
+    settings: Settings,
+    error: Option<String>,
+    finished: bool,
+}
+
+impl SetupPanel {
+    /// `error` is shown above the folders, e.g. if the main UI failed to start with the previous settings.
+    #[must_use]
+    pub fn new(settings: Settings, error: Option<String>) -> Self {
+        Self {
+            settings,
+            error,
+            finished: false,
+        }
+    }
+
+    /// Checks if any required folder is missing or invalid.
+    #[must_use]
+    pub fn is_needed(settings: &Settings) -> bool {
+        Folder::ALL
+            .into_iter()
+            .any(|folder| folder.is_required() && folder.check(&settings.folders).is_err())
+    }
+
+    /// Returns the settings once the user has confirmed them.
+    pub fn take_finished(&mut self) -> Option<Settings> {
+        if self.finished {
+            self.finished = false;
+            Some(self.settings.clone())
+        } else {
+            None
+        }
+    }
+
+    fn can_finish(&self) -> bool {
+        !Self::is_needed(&self.settings)
+            && Folder::check(Folder::SteamMods, &self.settings.folders)
+                .map_or_else(|err| err == FolderError::NotSet, |()| true)
+    }
+
+    fn browse(&mut self, folder: Folder) {
+        let current = folder
+            .get(&self.settings.folders)
+            .cloned()
+            .map(|p| p.push_chained("")); // so it opens in the dir rather than its parent
+        let current = current.as_ref().and_then(path_to_str).unwrap_or_default();
+
+        if let Some(picked) =
+            tinyfiledialogs::select_folder_dialog(&format!("Select {}", folder.label()), current)
+        {
+            folder.set(&mut self.settings.folders, Some(PathBuf::from(picked)));
+        }
+    }
+
+    fn folder_rows(&mut self, ui: &mut Ui) {
+        let mut to_browse = None;
+        let mut to_clear = None;
+
+        TableBuilder::new(ui)
+            .column(Column::auto())
+            .column(Column::remainder())
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::auto())
+            .body(|mut body| {
+                for folder in Folder::ALL {
+                    body.row(24.0, |mut row| {
+                        row.col(|ui| {
+                            ui.label(folder.label()).on_hover_text(folder.description());
+                        });
+                        row.col(|ui| {
+                            let path = folder.get(&self.settings.folders);
+                            ui.label(path.map(|p| p.to_string_lossy()).unwrap_or_default());
+                        });
+                        row.col(|ui| match folder.check(&self.settings.folders) {
+                            Ok(()) => {
+                                ui.colored_label(Color32::GREEN, "OK");
+                            }
+                            Err(FolderError::NotSet) if !folder.is_required() => {
+                                ui.label("optional");
+                            }
+                            Err(err) => {
+                                ui.colored_label(Color32::RED, err.to_string());
+                            }
+                        });
+                        row.col(|ui| {
+                            if ui.button("Browse").clicked() {
+                                to_browse = Some(folder);
+                            }
+                        });
+                        row.col(|ui| {
+                            if !folder.is_required() && ui.button("Clear").clicked() {
+                                to_clear = Some(folder);
+                            }
+                        });
+                    });
+                }
+            });
+
+        if let Some(folder) = to_browse {
+            self.browse(folder);
+        }
+        if let Some(folder) = to_clear {
+            folder.set(&mut self.settings.folders, None);
+        }
+    }
+}
+
+impl Widget for &mut SetupPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        ui.scope(|ui| {
+            ui.heading("Setup");
+            ui.label("rimrs couldn't find some of the folders it needs. Please select them below.");
+
+            if let Some(error) = self.error.as_ref() {
+                ui.colored_label(Color32::RED, error);
+            }
+
+            ui.separator();
+            self.folder_rows(ui);
+            ui.separator();
+
+            if ui
+                .add_enabled(self.can_finish(), eframe::egui::Button::new("Continue"))
+                .clicked()
+            {
+                self.settings.save().log_if_err();
+                self.finished = true;
+            }
+        })
+        .response
+    }
+}
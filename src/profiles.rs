@@ -0,0 +1,260 @@
+use crate::{
+    helpers::{config::get_rimrs_dir, paths::push_profiles_path},
+    serialization::ParseXMLError,
+    traits::PushChained,
+    writer_thread::write_mods_config_file,
+    ModsConfig, ModsConfigFile,
+};
+use std::{
+    env::VarError,
+    fs, io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+const PROFILE_EXTENSION: &str = ".xml";
+/// Holds the name of the profile that was last switched to.
+const CURRENT_FILE_NAME: &str = "current";
+/// Characters that can't be in file names on at least one platform.
+const FORBIDDEN_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+/// Names Windows keeps for devices, which can't be used as file names even with an extension.
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// The folder of named mod lists, e.g. one for a vanilla-plus run and one for testing.
+/// Each is stored as its own `ModsConfig.xml`-style file, named after the profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profiles {
+    dir: PathBuf,
+}
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("\"{0}\" can't be used as a profile name")]
+    InvalidName(String),
+    #[error("there's already a profile called \"{0}\"")]
+    AlreadyExists(String),
+    #[error("there's no profile called \"{0}\"")]
+    NotFound(String),
+    #[error("couldn't read profile: {0}")]
+    ParseError(#[from] ParseXMLError),
+    #[error(transparent)]
+    IOError(#[from] io::Error),
+}
+
+impl Profiles {
+    #[must_use]
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The `profiles` folder in [`get_rimrs_dir`].
+    ///
+    /// # Errors
+    /// If the rimrs folder can't be found, see [`get_rimrs_dir`].
+    pub fn in_rimrs_dir() -> Result<Self, VarError> {
+        get_rimrs_dir().map(|dir| Self::new(push_profiles_path(dir)))
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Checks that `name` can be used as a profile's file name, returning it trimmed.
+    ///
+    /// # Errors
+    /// [`ProfileError::InvalidName`] if it's empty, starts or ends with a `.`,
+    /// has characters that aren't allowed in file names, or is a name Windows reserves (e.g. `CON`).
+    pub fn validate_name(name: &str) -> Result<&str, ProfileError> {
+        let trimmed = name.trim();
+        let stem = trimmed.split('.').next().unwrap_or_default().trim_end();
+        if trimmed.is_empty()
+            || trimmed.starts_with('.')
+            || trimmed.ends_with('.')
+            || trimmed
+                .chars()
+                .any(|c| c.is_control() || FORBIDDEN_CHARS.contains(&c))
+            || RESERVED_NAMES
+                .iter()
+                .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+        {
+            return Err(ProfileError::InvalidName(String::from(name)));
+        }
+        Ok(trimmed)
+    }
+
+    /// Lists the profiles' names, sorted case-insensitively.
+    /// There aren't any if the folder doesn't exist yet.
+    ///
+    /// # Errors
+    /// If it can't read the profiles folder.
+    pub fn list(&self) -> io::Result<Vec<String>> {
+        if !self.dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let name = entry?.file_name();
+            let name = name
+                .to_str()
+                .and_then(|name| name.strip_suffix(PROFILE_EXTENSION))
+                .filter(|name| Self::validate_name(name).is_ok_and(|valid| valid == *name));
+            if let Some(name) = name {
+                names.push(String::from(name));
+            }
+        }
+
+        names.sort_by_key(|name| name.to_lowercase());
+        Ok(names)
+    }
+
+    #[must_use]
+    pub fn exists(&self, name: &str) -> bool {
+        self.path(name).is_file()
+    }
+
+    /// Reads the active mods stored in the profile.
+    ///
+    /// # Errors
+    /// If there's no such profile, or it can't be read.
+    pub fn load(&self, name: &str) -> Result<Vec<String>, ProfileError> {
+        self.existing_path(name)
+            .and_then(|path| Ok(ModsConfig::try_from(path.as_path())?))
+            .map(|mods_config| mods_config.activeMods)
+    }
+
+    /// Stores the active mods in the profile, replacing what it had if it already exists.
+    ///
+    /// # Errors
+    /// If the name isn't valid, or it can't write the file.
+    pub fn save(
+        &self,
+        name: &str,
+        active_mods: &[String],
+        version: Option<&str>,
+    ) -> Result<(), ProfileError> {
+        let path = self.path(Self::validate_name(name)?);
+        let mut file = if path.is_file() {
+            ModsConfigFile::try_from(path.as_path())
+                .unwrap_or_else(|_| ModsConfigFile::from(ModsConfig::default()))
+        } else {
+            ModsConfigFile::from(ModsConfig::default())
+        };
+        file.mods_config.activeMods = active_mods.to_vec();
+        file.mods_config.version = version.map(String::from);
+
+        fs::create_dir_all(&self.dir)?;
+        write_mods_config_file(path, &file)?;
+        Ok(())
+    }
+
+    /// Makes a new profile with the active mods.
+    ///
+    /// # Errors
+    /// If the name isn't valid or is already used, or it can't write the file.
+    pub fn create(
+        &self,
+        name: &str,
+        active_mods: &[String],
+        version: Option<&str>,
+    ) -> Result<(), ProfileError> {
+        let name = self.unused_name(name)?;
+        self.save(name, active_mods, version)
+    }
+
+    /// Renames the profile, keeping it as the current one if it was.
+    ///
+    /// # Errors
+    /// If there's no such profile, the new name isn't valid or is already used,
+    /// or it can't rename the file.
+    pub fn rename(&self, name: &str, new_name: &str) -> Result<(), ProfileError> {
+        let path = self.existing_path(name)?;
+        let new_name = self.unused_name(new_name)?;
+        let was_current = self.current().as_deref() == Some(name);
+        fs::rename(path, self.path(new_name))?;
+
+        if was_current {
+            self.set_current(Some(new_name))?;
+        }
+        Ok(())
+    }
+
+    /// Copies the profile to a new one.
+    ///
+    /// # Errors
+    /// If there's no such profile, the new name isn't valid or is already used,
+    /// or it can't copy the file.
+    pub fn duplicate(&self, name: &str, new_name: &str) -> Result<(), ProfileError> {
+        let path = self.existing_path(name)?;
+        let new_name = self.unused_name(new_name)?;
+        fs::copy(path, self.path(new_name))?;
+        Ok(())
+    }
+
+    /// Deletes the profile, so it's no longer the current one either.
+    ///
+    /// # Errors
+    /// If there's no such profile, or it can't delete the file.
+    pub fn delete(&self, name: &str) -> Result<(), ProfileError> {
+        fs::remove_file(self.existing_path(name)?)?;
+
+        if self.current().as_deref() == Some(name) {
+            self.set_current(None)?;
+        }
+        Ok(())
+    }
+
+    /// The profile that was last switched to, if it still exists.
+    #[must_use]
+    pub fn current(&self) -> Option<String> {
+        let name = fs::read_to_string(self.current_path()).ok()?;
+        let name = name.trim();
+        self.exists(name).then(|| String::from(name))
+    }
+
+    /// Remembers which profile was last switched to, or that none was.
+    ///
+    /// # Errors
+    /// If it can't write or delete the file it's remembered in.
+    pub fn set_current(&self, name: Option<&str>) -> io::Result<()> {
+        let path = self.current_path();
+        match name {
+            Some(name) => {
+                fs::create_dir_all(&self.dir)?;
+                fs::write(path, name)
+            }
+            None if path.exists() => fs::remove_file(path),
+            None => Ok(()),
+        }
+    }
+
+    fn unused_name<'n>(&self, name: &'n str) -> Result<&'n str, ProfileError> {
+        let name = Self::validate_name(name)?;
+        if self.exists(name) {
+            return Err(ProfileError::AlreadyExists(String::from(name)));
+        }
+        Ok(name)
+    }
+
+    fn existing_path(&self, name: &str) -> Result<PathBuf, ProfileError> {
+        let path = self.path(Self::validate_name(name)?);
+        if !path.is_file() {
+            return Err(ProfileError::NotFound(String::from(name)));
+        }
+        Ok(path)
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir
+            .clone()
+            .push_chained(format!("{name}{PROFILE_EXTENSION}"))
+    }
+
+    fn current_path(&self) -> PathBuf {
+        self.dir.clone().push_chained(CURRENT_FILE_NAME)
+    }
+}
@@ -2,7 +2,10 @@ use crate::{
     helpers::strip_bom,
     mods::{Dependency, ModMetaData},
 };
-use std::{collections::HashSet, convert::AsRef};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::AsRef,
+};
 use xml::{
     name::OwnedName,
     reader::{EventReader, XmlEvent},
@@ -12,9 +15,6 @@ use xml::{
 ///
 /// # Errors
 /// * [`xml::reader::Error`]: if it fails to parse an [`XmlEvent`]
-///
-/// # Panics
-/// * If `bytes.len() < 3`. See [`strip_bom`].
 #[allow(clippy::module_name_repetitions)]
 pub fn parse_about(bytes: &[u8]) -> Result<ModMetaData, xml::reader::Error> {
     let reader = EventReader::new(strip_bom(bytes));
@@ -49,7 +49,7 @@ fn add_data_to_mmd(mmd: &mut ModMetaData, xml_path: &[String], text: String, mem
         s
     }
 
-    if xml_path.get(0).map(AsRef::as_ref) != Some("ModMetaData") {
+    if xml_path.first().map(AsRef::as_ref) != Some("ModMetaData") {
         return;
     }
 
@@ -95,13 +95,32 @@ fn add_data_to_mmd(mmd: &mut ModMetaData, xml_path: &[String], text: String, mem
         }
 
         // mod dependencies
-        Some("modDependencies") => match xml_path.get(3).map(AsRef::as_ref) {
-            Some("packageId") => mem.curr_modDependencies.packageId = Some(lc(text)),
-            Some("displayName") => mem.curr_modDependencies.displayName = Some(text),
-            Some("steamWorkshopUrl") => mem.curr_modDependencies.steamWorkshopUrl = Some(text),
-            Some("downloadUrl") => mem.curr_modDependencies.downloadUrl = Some(text),
-            _ => {}
-        },
+        Some("modDependencies") => {
+            add_dependency_field(&mut mem.curr_modDependencies, xml_path.get(3), text);
+        }
+
+        // by version, e.g. `<loadAfterByVersion><v1.4><li>...</li></v1.4></loadAfterByVersion>`
+        Some("descriptionsByVersion") => {
+            if let Some(version) = xml_path.get(2) {
+                mem.descriptionsByVersion.insert(version_key(version), text);
+            }
+        }
+        Some("loadAfterByVersion") => {
+            insert_by_version(&mut mem.loadAfterByVersion, xml_path.get(2), lc(text));
+        }
+        Some("loadBeforeByVersion") => {
+            insert_by_version(&mut mem.loadBeforeByVersion, xml_path.get(2), lc(text));
+        }
+        Some("incompatibleWithByVersion") => {
+            insert_by_version(
+                &mut mem.incompatibleWithByVersion,
+                xml_path.get(2),
+                lc(text),
+            );
+        }
+        Some("modDependenciesByVersion") => {
+            add_dependency_field(&mut mem.curr_modDependencies, xml_path.get(4), text);
+        }
 
         // anything else
         _ => {
@@ -110,16 +129,47 @@ fn add_data_to_mmd(mmd: &mut ModMetaData, xml_path: &[String], text: String, mem
     }
 }
 
+fn add_dependency_field(dep: &mut Dependency, field: Option<&String>, text: String) {
+    match field.map(AsRef::as_ref) {
+        Some("packageId") => dep.packageId = Some(text.to_ascii_lowercase()),
+        Some("displayName") => dep.displayName = Some(text),
+        Some("steamWorkshopUrl") => dep.steamWorkshopUrl = Some(text),
+        Some("downloadUrl") => dep.downloadUrl = Some(text),
+        _ => {}
+    }
+}
+
+fn insert_by_version(
+    map: &mut HashMap<String, HashSet<String>>,
+    version: Option<&String>,
+    text: String,
+) {
+    if let Some(version) = version {
+        map.entry(version_key(version)).or_default().insert(text);
+    }
+}
+
+/// Version tags are like `<v1.4>`, but we want to key them like `supportedVersions`, i.e. `1.4`.
+fn version_key(tag: &str) -> String {
+    String::from(tag.strip_prefix('v').unwrap_or(tag))
+}
+
 fn end_element(xml_path: &mut Vec<String>, name: &OwnedName, mem: &mut ParsingMem) {
-    if xml_path.get(1).map(AsRef::as_ref) == Some("modDependencies") && name.local_name == "li" {
-        let dep = mem.curr_modDependencies.clone();
-        mem.modDependencies.insert(dep);
-
-        // should be better than mem.curr_modDependencies = Dependency::default(), i think?
-        mem.curr_modDependencies.packageId = None;
-        mem.curr_modDependencies.displayName = None;
-        mem.curr_modDependencies.downloadUrl = None;
-        mem.curr_modDependencies.steamWorkshopUrl = None;
+    if name.local_name == "li" {
+        match (xml_path.get(1).map(AsRef::as_ref), xml_path.len()) {
+            (Some("modDependencies"), 3) => {
+                let dep = std::mem::take(&mut mem.curr_modDependencies);
+                mem.modDependencies.insert(dep);
+            }
+            (Some("modDependenciesByVersion"), 4) => {
+                let dep = std::mem::take(&mut mem.curr_modDependencies);
+                mem.modDependenciesByVersion
+                    .entry(version_key(&xml_path[2]))
+                    .or_default()
+                    .insert(dep);
+            }
+            _ => {}
+        }
     }
 
     xml_path.pop();
@@ -138,6 +188,12 @@ struct ParsingMem {
 
     pub curr_modDependencies: Dependency,
     pub modDependencies: HashSet<Dependency>,
+
+    pub descriptionsByVersion: HashMap<String, String>,
+    pub modDependenciesByVersion: HashMap<String, HashSet<Dependency>>,
+    pub loadAfterByVersion: HashMap<String, HashSet<String>>,
+    pub loadBeforeByVersion: HashMap<String, HashSet<String>>,
+    pub incompatibleWithByVersion: HashMap<String, HashSet<String>>,
 }
 
 impl ParsingMem {
@@ -167,5 +223,21 @@ impl ParsingMem {
         if !self.modDependencies.is_empty() {
             mmd.modDependencies = Some(self.modDependencies);
         }
+
+        if !self.descriptionsByVersion.is_empty() {
+            mmd.descriptionsByVersion = Some(self.descriptionsByVersion);
+        }
+        if !self.modDependenciesByVersion.is_empty() {
+            mmd.modDependenciesByVersion = Some(self.modDependenciesByVersion);
+        }
+        if !self.loadAfterByVersion.is_empty() {
+            mmd.loadAfterByVersion = Some(self.loadAfterByVersion);
+        }
+        if !self.loadBeforeByVersion.is_empty() {
+            mmd.loadBeforeByVersion = Some(self.loadBeforeByVersion);
+        }
+        if !self.incompatibleWithByVersion.is_empty() {
+            mmd.incompatibleWithByVersion = Some(self.incompatibleWithByVersion);
+        }
     }
 }
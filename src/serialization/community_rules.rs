@@ -0,0 +1,70 @@
+use crate::{
+    helpers::{
+        config::{get_config_dir, get_rimrs_dir},
+        paths::push_community_rules_path,
+    },
+    serialization::rules_file::{self, RulesFileError},
+    traits::{LockIgnorePoisoned, LogIfErr, PushChained},
+    LoadRules, ModList, Settings,
+};
+use std::{collections::HashMap, fs, path::Path};
+
+/// `RimPy`'s community rules database, extra load order rules for mods that are maintained by its users.
+/// See [`rules_file::parse`] for the layout.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommunityRules {
+    pub timestamp: Option<u64>,
+    /// Keyed by lowercase package id.
+    pub rules: HashMap<String, LoadRules>,
+}
+
+impl CommunityRules {
+    /// Reads the community rules file at the given path.
+    ///
+    /// # Errors
+    /// * If it can't read the file
+    /// * If it isn't valid JSON, or isn't laid out like a rules file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, RulesFileError> {
+        Self::try_from(fs::read(path)?.as_slice())
+    }
+
+    /// Finds and reads the community rules, from the first of these that exists:
+    /// * the file set in [`Settings::community_rules`]
+    /// * `communityRules.json` in [`get_rimrs_dir`]
+    /// * `RimPy`'s copy, in the `db` folder in [`get_config_dir`]
+    ///
+    /// Errors are logged rather than returned, as sorting still works without the rules.
+    #[must_use]
+    pub fn load(settings: &Settings) -> Option<Self> {
+        let path = settings
+            .community_rules
+            .clone()
+            .into_iter()
+            .chain(get_rimrs_dir().ok().map(push_community_rules_path))
+            .chain(
+                get_config_dir()
+                    .ok()
+                    .map(|dir| push_community_rules_path(dir.push_chained("db"))),
+            )
+            .find(|path| path.exists())?;
+
+        log::info!("Reading community rules from {}", path.display());
+        Self::from_path(path).log_if_err()
+    }
+
+    /// Attaches the rules to each mod in the list that they're for, see [`crate::ModMetaData::community_rules`].
+    pub fn apply(&self, mod_list: &ModList) {
+        for (pid, mmd) in mod_list.mods.lock_ignore_poisoned().iter_mut() {
+            mmd.community_rules = self.rules.get(pid).cloned();
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for CommunityRules {
+    type Error = RulesFileError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let (timestamp, rules) = rules_file::parse(bytes)?;
+        Ok(Self { timestamp, rules })
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{
+    helpers::{fold_lis, steam_workshop_url},
+    ModMetaData,
+};
+use serde_json::{json, Value};
+use std::{collections::HashMap, fmt, fmt::Write};
+use xml::escape::escape_str_pcdata;
+
+/// The formats the active mod list can be exported as, other than `ModsConfig.xml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// `RimPy`'s mod list, laid out like the `<meta>` block of a save (see [`crate::serialization::save_file::SaveMeta`]).
+    RimPy,
+    /// A line per mod, for pasting into chats.
+    Text,
+    /// A table, linking to each mod's page.
+    Markdown,
+    /// A list of objects with each mod's details.
+    Json,
+}
+
+impl ExportFormat {
+    pub const ALL: [Self; 4] = [Self::RimPy, Self::Text, Self::Markdown, Self::Json];
+
+    /// The file extension usually used for the format.
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::RimPy => "xml",
+            Self::Text => "txt",
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::RimPy => "RimPy mod list",
+            Self::Text => "Plain text",
+            Self::Markdown => "Markdown table",
+            Self::Json => "JSON",
+        })
+    }
+}
+
+/// The details of a mod that get exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedMod {
+    /// The mod's name, or its package id if it doesn't have one.
+    pub name: String,
+    pub package_id: String,
+    /// See [`ModMetaData::steam_workshop_id`].
+    pub steam_workshop_id: Option<String>,
+    /// The `url` from its `About.xml`.
+    pub url: Option<String>,
+}
+
+impl ExportedMod {
+    #[must_use]
+    pub fn new(package_id: &str, mmd: Option<&ModMetaData>) -> Self {
+        Self {
+            name: mmd
+                .and_then(|mmd| mmd.name.clone())
+                .unwrap_or_else(|| String::from(package_id)),
+            package_id: String::from(package_id),
+            steam_workshop_id: mmd
+                .and_then(ModMetaData::steam_workshop_id)
+                .map(String::from),
+            url: mmd.and_then(|mmd| mmd.url.clone()),
+        }
+    }
+
+    /// Gets the details of each of the given mods, looking them up in `mod_meta_data`.
+    #[allow(clippy::implicit_hasher)]
+    #[must_use]
+    pub fn collect(mods: &[String], mod_meta_data: &HashMap<String, ModMetaData>) -> Vec<Self> {
+        mods.iter()
+            .map(|pid| Self::new(pid, mod_meta_data.get(&pid.to_lowercase())))
+            .collect()
+    }
+
+    /// The link to use for the mod: its `url`, or its workshop page if it doesn't have one.
+    #[must_use]
+    pub fn link(&self) -> Option<String> {
+        self.url
+            .clone()
+            .or_else(|| self.steam_workshop_id.as_deref().map(steam_workshop_url))
+    }
+}
+
+/// Writes the mods out in the given format.
+/// `version` is the game version, only used by [`ExportFormat::RimPy`].
+#[must_use]
+pub fn export(format: ExportFormat, mods: &[ExportedMod], version: Option<&str>) -> String {
+    match format {
+        ExportFormat::RimPy => rimpy(mods, version),
+        ExportFormat::Text => text(mods),
+        ExportFormat::Markdown => markdown(mods),
+        ExportFormat::Json => json(mods),
+    }
+}
+
+fn rimpy(mods: &[ExportedMod], version: Option<&str>) -> String {
+    let list = |items: Vec<String>| {
+        let items: Vec<_> = items
+            .iter()
+            .map(|item| escape_str_pcdata(item).into_owned())
+            .collect();
+        fold_lis(&items, 3)
+    };
+
+    let mut out =
+        String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<savegame>\n    <meta>\n");
+    if let Some(version) = version {
+        let _ = writeln!(
+            out,
+            "        <gameVersion>{}</gameVersion>",
+            escape_str_pcdata(version)
+        );
+    }
+    for (tag, items) in [
+        (
+            "modIds",
+            mods.iter().map(|m| m.package_id.clone()).collect(),
+        ),
+        (
+            "modSteamIds",
+            mods.iter()
+                .map(|m| {
+                    m.steam_workshop_id
+                        .clone()
+                        .unwrap_or_else(|| String::from("0"))
+                })
+                .collect(),
+        ),
+        ("modNames", mods.iter().map(|m| m.name.clone()).collect()),
+    ] {
+        let _ = write!(out, "        <{tag}>\n{}        </{tag}>\n", list(items));
+    }
+    out.push_str("    </meta>\n</savegame>\n");
+
+    out
+}
+
+fn text(mods: &[ExportedMod]) -> String {
+    mods.iter().fold(String::new(), |mut out, m| {
+        let _ = write!(out, "{} ({})", m.name, m.package_id);
+        // the angle brackets stop discord from embedding every link
+        if let Some(link) = m.link() {
+            let _ = write!(out, " <{link}>");
+        }
+        out.push('\n');
+        out
+    })
+}
+
+fn markdown(mods: &[ExportedMod]) -> String {
+    /// Backslash escapes anything Markdown (or the HTML it allows) could treat as formatting,
+    /// e.g. the `[1.4]` many mods start their names with.
+    fn escape(s: &str) -> String {
+        const SPECIAL: &[char] = &['\\', '`', '*', '_', '[', ']', '<', '>', '&', '|', '~'];
+
+        s.chars()
+            .fold(String::with_capacity(s.len()), |mut out, c| {
+                if SPECIAL.contains(&c) {
+                    out.push('\\');
+                }
+                out.push(c);
+                out
+            })
+    }
+
+    let mut out = String::from("| # | Name | Package id | Workshop id |\n|---|---|---|---|\n");
+    for (i, m) in mods.iter().enumerate() {
+        let name = match m.link() {
+            Some(link) => format!("[{}]({link})", escape(&m.name)),
+            None => escape(&m.name),
+        };
+        let workshop_id = m
+            .steam_workshop_id
+            .as_deref()
+            .map(|id| format!("[{id}]({})", steam_workshop_url(id)))
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "| {} | {name} | {} | {workshop_id} |",
+            i + 1,
+            escape(&m.package_id)
+        );
+    }
+    out
+}
+
+fn json(mods: &[ExportedMod]) -> String {
+    let mods: Vec<Value> = mods
+        .iter()
+        .map(|m| {
+            json!({
+                "name": m.name,
+                "packageId": m.package_id,
+                "steamWorkshopId": m.steam_workshop_id,
+                "url": m.url,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&mods).unwrap_or_default()
+}
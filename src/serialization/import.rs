@@ -0,0 +1,235 @@
+use crate::{
+    helpers::{steam_workshop_url, strip_bom},
+    serialization::{
+        mod_manager::{ModManagerList, ModManagerManifest},
+        rules_file::{self, RulesFileError},
+        save_file::SaveMeta,
+        user_rules::UserRules,
+        ParseXMLError,
+    },
+    ModMetaData, ModsConfig,
+};
+use std::{collections::HashMap, fmt, fs::File, io::Read, path::Path};
+use thiserror::Error;
+use xml::reader::{EventReader, XmlEvent};
+
+/// The kinds of file that can be imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// `RimWorld`'s `ModsConfig.xml`, which is also what `RimPy` and rimrs export, see [`ModsConfig`].
+    ModsConfig,
+    /// A `RimWorld` save, or a `RimPy` mod list laid out like one, see [`SaveMeta`].
+    Save,
+    /// A mod list saved by Fluffy's Mod Manager, see [`ModManagerList`].
+    ModManagerList,
+    /// Load order rules in Mod Manager's `Manifest.xml` format, see [`ModManagerManifest`].
+    ModManagerManifest,
+    /// Load order rules in the same JSON format as the community and user rules, see [`rules_file::parse`].
+    RulesFile,
+}
+
+impl ImportFormat {
+    /// Works out the format of a file from its contents:
+    /// JSON is a rules file, and XML is told apart by its root tag.
+    #[must_use]
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        let bytes = if bytes.len() < 3 {
+            bytes
+        } else {
+            strip_bom(bytes)
+        };
+        if bytes.trim_ascii_start().starts_with(b"{") {
+            return Some(Self::RulesFile);
+        }
+
+        let root = EventReader::new(bytes)
+            .into_iter()
+            .find_map(|event| match event {
+                Ok(XmlEvent::StartElement { name, .. }) => Some(Ok(name.local_name)),
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            })?
+            .ok()?;
+
+        match root.as_str() {
+            "ModsConfigData" => Some(Self::ModsConfig),
+            "savegame" => Some(Self::Save),
+            "ModList" => Some(Self::ModManagerList),
+            "Manifest" => Some(Self::ModManagerManifest),
+            _ => None,
+        }
+    }
+}
+
+/// What was in an imported file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Imported {
+    /// Mods to make the active mods, in order.
+    ModList(Vec<ImportedMod>),
+    /// Load order rules to add to the user's rules.
+    Rules(UserRules),
+}
+
+/// A mod from an imported mod list, with whatever else the file says about it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportedMod {
+    pub package_id: String,
+    pub name: Option<String>,
+    pub steam_workshop_id: Option<String>,
+}
+
+impl ImportedMod {
+    #[must_use]
+    pub fn new(package_id: String) -> Self {
+        Self {
+            package_id,
+            ..Default::default()
+        }
+    }
+
+    /// Gets the mod's steam workshop page, if the file said what its workshop id is.
+    #[must_use]
+    pub fn steam_workshop_url(&self) -> Option<String> {
+        self.steam_workshop_id.as_deref().map(steam_workshop_url)
+    }
+}
+
+impl fmt::Display for ImportedMod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name.as_ref() {
+            Some(name) => write!(f, "{name} ({})", self.package_id),
+            None => f.write_str(&self.package_id),
+        }
+    }
+}
+
+/// An imported mod list, split into the mods that are installed and the ones that aren't.
+/// Package ids are lowercased, like the keys of the mod metadata they're checked against.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportResult {
+    /// Every mod in the list, in order.
+    pub mods: Vec<ImportedMod>,
+    /// Package ids of the mods that are installed, in order.
+    pub found: Vec<String>,
+    /// The mods that aren't installed, in order.
+    pub missing: Vec<ImportedMod>,
+}
+
+impl ImportResult {
+    /// Checks which of the imported mods are in `mod_meta_data`.
+    #[allow(clippy::implicit_hasher)]
+    #[must_use]
+    pub fn new(mut mods: Vec<ImportedMod>, mod_meta_data: &HashMap<String, ModMetaData>) -> Self {
+        for m in &mut mods {
+            m.package_id = m.package_id.to_lowercase();
+        }
+        let (found, missing): (Vec<_>, Vec<_>) = mods
+            .iter()
+            .partition(|m| mod_meta_data.contains_key(&m.package_id));
+
+        Self {
+            found: found.into_iter().map(|m| m.package_id.clone()).collect(),
+            missing: missing.into_iter().cloned().collect(),
+            mods,
+        }
+    }
+
+    /// Gets the package ids to make the active mods,
+    /// either keeping the missing mods in the list or dropping them.
+    #[must_use]
+    pub fn into_mod_list(self, keep_missing: bool) -> Vec<String> {
+        if keep_missing {
+            self.mods.into_iter().map(|m| m.package_id).collect()
+        } else {
+            self.found
+        }
+    }
+}
+
+/// How much of a file [`import_file`] reads to work out its format.
+const DETECT_LEN: u64 = 16 * 1024;
+
+/// Reads a mod list or load order rules from a file, working out which format it's in.
+/// Saves are only read up to the end of their `<meta>` block, see [`SaveMeta::from_path`].
+///
+/// # Errors
+/// * If it can't read the file
+/// * See [`import`]
+pub fn import_file<P: AsRef<Path>>(path: P) -> Result<Imported, ImportError> {
+    let mut file = File::open(path.as_ref())?;
+    let mut bytes = Vec::new();
+    file.by_ref().take(DETECT_LEN).read_to_end(&mut bytes)?;
+
+    match ImportFormat::detect(&bytes).ok_or(ImportError::UnknownFormat)? {
+        ImportFormat::Save => Ok(Imported::ModList(SaveMeta::from_path(path)?.mods())),
+        format => {
+            file.read_to_end(&mut bytes)?;
+            import_as(format, &bytes)
+        }
+    }
+}
+
+/// Reads a mod list or load order rules, working out which format they're in.
+/// See [`ImportFormat`] for which formats are supported.
+///
+/// # Errors
+/// * [`ImportError::UnknownFormat`]: if it isn't any of the supported formats
+/// * If it's in one of them, but isn't valid
+pub fn import(bytes: &[u8]) -> Result<Imported, ImportError> {
+    import_as(
+        ImportFormat::detect(bytes).ok_or(ImportError::UnknownFormat)?,
+        bytes,
+    )
+}
+
+fn import_as(format: ImportFormat, bytes: &[u8]) -> Result<Imported, ImportError> {
+    match format {
+        ImportFormat::ModsConfig => Ok(Imported::ModList(
+            ModsConfig::try_from(bytes)?
+                .activeMods
+                .into_iter()
+                .map(ImportedMod::new)
+                .collect(),
+        )),
+        ImportFormat::Save => Ok(Imported::ModList(SaveMeta::from_reader(bytes)?.mods())),
+        ImportFormat::ModManagerList => {
+            Ok(Imported::ModList(ModManagerList::try_from(bytes)?.mods()))
+        }
+        ImportFormat::ModManagerManifest => {
+            let manifest = ModManagerManifest::try_from(bytes)?;
+            let package_id = manifest.identifier.ok_or_else(|| {
+                ImportError::InvalidData(String::from("manifest has no `identifier`"))
+            })?;
+            let mut rules = UserRules::default();
+            rules.set(&package_id, manifest.rules);
+            Ok(Imported::Rules(rules))
+        }
+        ImportFormat::RulesFile => {
+            let (_, rules) = rules_file::parse(bytes)?;
+            Ok(Imported::Rules(UserRules { rules }))
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("couldn't read file: {0}")]
+    IOError(#[from] std::io::Error),
+    #[error("invalid XML: {0}")]
+    XMLError(#[from] xml::reader::Error),
+    #[error("{0}")]
+    RulesFileError(#[from] RulesFileError),
+    #[error("invalid file: {0}")]
+    InvalidData(String),
+    #[error("not a mod list or rules file that rimrs knows how to read")]
+    UnknownFormat,
+}
+
+impl From<ParseXMLError> for ImportError {
+    fn from(err: ParseXMLError) -> Self {
+        match err {
+            ParseXMLError::IOError(err) => Self::IOError(err),
+            ParseXMLError::XMLError(err) => Self::XMLError(err),
+        }
+    }
+}
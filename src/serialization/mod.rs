@@ -7,6 +7,30 @@ pub mod mods_config;
 /// For parsing the rimpy's configuration file.
 pub mod rimpy_config;
 
+/// For rimrs's own settings, which can be imported from rimpy's.
+pub mod settings;
+
+/// For `RimPy`'s community rules database.
+pub mod community_rules;
+
+/// For the user's own load order rules.
+pub mod user_rules;
+
+/// The JSON layout shared by the community and user rules files.
+pub mod rules_file;
+
+/// For Fluffy's Mod Manager's saved mod lists and rules.
+pub mod mod_manager;
+
+/// For reading the mod list from `RimWorld` save files.
+pub mod save_file;
+
+/// For exporting the active mod list in formats meant for sharing.
+pub mod export;
+
+/// For importing mod lists and rules from the files various mod managers use.
+pub mod import;
+
 /// For parsing files in the `.ini` format.
 pub mod ini;
 
@@ -0,0 +1,148 @@
+use crate::{helpers::strip_bom, serialization::import::ImportedMod, LoadRules};
+use xml::reader::{EventReader, XmlEvent};
+
+/// A mod list saved by Fluffy's Mod Manager, in its `ModLists` folder.
+///
+/// The file looks like
+/// ```xml
+/// <ModList>
+///     <Name>My list</Name>
+///     <modIds>
+///         <li>ludeon.rimworld</li>
+///         <li>brrainz.harmony</li>
+///     </modIds>
+///     <modNames>
+///         <li>Core</li>
+///         <li>Harmony</li>
+///     </modNames>
+/// </ModList>
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModManagerList {
+    pub name: Option<String>,
+    /// Package ids, in load order.
+    pub mod_ids: Vec<String>,
+    /// Names of the mods in `mod_ids`, if the file has them.
+    pub mod_names: Vec<String>,
+}
+
+impl ModManagerList {
+    /// Gets the mods in the list, along with their names.
+    #[must_use]
+    pub fn mods(&self) -> Vec<ImportedMod> {
+        self.mod_ids
+            .iter()
+            .enumerate()
+            .map(|(i, pid)| ImportedMod {
+                package_id: pid.clone(),
+                name: self.mod_names.get(i).cloned(),
+                steam_workshop_id: None,
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<&[u8]> for ModManagerList {
+    type Error = xml::reader::Error;
+
+    /// Parses a Mod Manager mod list.
+    ///
+    /// # Errors
+    /// * [`xml::reader::Error`]: invalid XML
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let mut list = Self::default();
+
+        for_each_text(bytes, |xml_path, text| match xml_path {
+            [_, name] if name == "Name" => list.name = Some(text),
+            [_, ids, _] if ids == "modIds" => list.mod_ids.push(text),
+            [_, names, _] if names == "modNames" => list.mod_names.push(text),
+            _ => {}
+        })?;
+
+        Ok(list)
+    }
+}
+
+/// Load order rules in Mod Manager's `Manifest.xml` format,
+/// which is also how it shares rules for mods that don't have their own manifest.
+///
+/// The file looks like
+/// ```xml
+/// <Manifest>
+///     <identifier>some.mod</identifier>
+///     <loadAfter>
+///         <li>brrainz.harmony</li>
+///     </loadAfter>
+///     <loadBefore />
+///     <incompatibleWith>
+///         <li>other.mod >= 1.2</li>
+///     </incompatibleWith>
+/// </Manifest>
+/// ```
+/// Any version requirements after the package ids are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModManagerManifest {
+    /// Package id of the mod the rules are for (lowercase).
+    pub identifier: Option<String>,
+    pub rules: LoadRules,
+}
+
+impl TryFrom<&[u8]> for ModManagerManifest {
+    type Error = xml::reader::Error;
+
+    /// Parses a Mod Manager manifest.
+    ///
+    /// # Errors
+    /// * [`xml::reader::Error`]: invalid XML
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let mut manifest = Self::default();
+
+        for_each_text(bytes, |xml_path, text| {
+            let Some(package_id) = text.split_whitespace().next().map(str::to_lowercase) else {
+                return;
+            };
+            match xml_path {
+                [_, tag] if tag == "identifier" => manifest.identifier = Some(package_id),
+                [_, tag, _] if tag == "loadAfter" => {
+                    manifest.rules.load_after.insert(package_id);
+                }
+                [_, tag, _] if tag == "loadBefore" => {
+                    manifest.rules.load_before.insert(package_id);
+                }
+                [_, tag, _] if tag == "incompatibleWith" => {
+                    manifest.rules.incompatible_with.insert(package_id);
+                }
+                _ => {}
+            }
+        })?;
+
+        Ok(manifest)
+    }
+}
+
+/// Calls `f` with the path of tags leading to each bit of text in the XML, including the root tag.
+fn for_each_text<F: FnMut(&[String], String)>(
+    bytes: &[u8],
+    mut f: F,
+) -> Result<(), xml::reader::Error> {
+    let bytes = if bytes.len() < 3 {
+        bytes
+    } else {
+        strip_bom(bytes)
+    };
+    let mut xml_path = Vec::new();
+
+    for event in EventReader::new(bytes) {
+        match event? {
+            XmlEvent::StartElement { name, .. } => xml_path.push(name.local_name),
+            XmlEvent::EndElement { .. } => {
+                xml_path.pop();
+            }
+            XmlEvent::Characters(text) => f(&xml_path, text),
+            XmlEvent::EndDocument => break,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
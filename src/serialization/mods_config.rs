@@ -1,16 +1,28 @@
-use crate::helpers::{fold_lis, strip_bom};
-use std::path::Path;
-use xml::reader::{EventReader, XmlEvent};
+use crate::helpers::strip_bom;
+use std::{fmt::Write, ops::Range, path::Path};
+use xml::{
+    escape::escape_str_pcdata,
+    reader::{ParserConfig, XmlEvent},
+};
+
+/// What a new `ModsConfig.xml` starts as, before the sections are added.
+const NEW_FILE: &str =
+    "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<ModsConfigData>\n</ModsConfigData>";
+
+/// The indent used when there's nothing in the file to copy it from.
+const DEFAULT_INDENT: &str = "    ";
 
 /// Represents the file `ModsConfig.xml` in rimworld's config directory,
 /// `.../AppData/LocalLow/Ludeon Studios/RimWorld by Ludeon Studios/Config/`.
 ///
 /// Fields directly represent the XML tag names, so aren't snakecase.
 /// The `ModsConfig.xml` file uses `<li>` tags for elements of lists, represented in this struct as [`Vec`]s.
+///
+/// Serializing it writes a new file; see [`ModsConfigFile`] to keep the rest of an existing one.
 #[allow(non_snake_case)]
 #[derive(Debug, Clone, Default)]
 pub struct ModsConfig {
-    /// RimWorld version.
+    /// `RimWorld` version.
     /// E.g. `1.4.3613 rev641`
     pub version: Option<String>,
 
@@ -19,61 +31,189 @@ pub struct ModsConfig {
     /// represented in-file as a list of package IDs using `<li>{packageid}</li>` for the items.
     pub activeMods: Vec<String>,
 
-    /// List of official RimWorld DLCs.
+    /// List of official `RimWorld` DLCs.
     /// Stored in the same way as `activeMods`.
     pub knownExpansions: Vec<String>,
 }
 
+/// A [`ModsConfig`] along with the file it was parsed from,
+/// so that serializing it again only rewrites the sections that were changed.
+/// Anything else in the file (unknown elements, comments, formatting) is kept as it was,
+/// and an unchanged config is written back byte-for-byte.
+#[derive(Debug, Clone)]
+pub struct ModsConfigFile {
+    pub mods_config: ModsConfig,
+    source: Source,
+}
+
+/// The elements of `ModsConfig.xml` that [`ModsConfig`] reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Version,
+    ActiveMods,
+    KnownExpansions,
+}
+
+impl Section {
+    const ALL: [Self; 3] = [Self::Version, Self::ActiveMods, Self::KnownExpansions];
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|section| section.tag() == tag)
+    }
+
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Version => "version",
+            Self::ActiveMods => "activeMods",
+            Self::KnownExpansions => "knownExpansions",
+        }
+    }
+}
+
+/// The original text of a parsed `ModsConfig.xml`, and where its sections are.
+#[derive(Debug, Clone)]
+struct Source {
+    text: String,
+    /// What was parsed from `text`, to tell which sections have changed since.
+    parsed: ModsConfig,
+    /// The byte ranges of the known elements directly under the root, in order.
+    sections: Vec<(Section, Range<usize>)>,
+    /// Where the root's end tag starts, if it isn't self-closing.
+    root_end: Option<usize>,
+}
+
 impl TryFrom<&[u8]> for ModsConfig {
     type Error = xml::reader::Error;
 
     /// Tries to convert from the given bytes into [`ModsConfig`].
+    /// Text in the known sections is trimmed, and anything else is ignored.
     ///
     /// # Errors
     /// * [`xml::reader::Error`]: invalid XML
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse_values(bytes)
+    }
+}
+
+impl TryFrom<&Path> for ModsConfig {
+    type Error = super::ParseXMLError;
+
+    /// Tries to read a file, then parse its XML as [`ModsConfig`].
+    ///
+    /// # Errors
+    /// * [`xml::reader::Error`]: invalid XML (see `Self::TryFrom<&[u8]>`)
+    /// * [`std::io::Error`]: failed to read the file at the given path
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        let bytes = std::fs::read(path)?;
+        Self::try_from(bytes.as_slice()).map_err(Into::into)
+    }
+}
+
+impl From<&ModsConfig> for String {
+    fn from(mods_config: &ModsConfig) -> Self {
+        Source::new_file().write(mods_config)
+    }
+}
+
+impl From<&ModsConfig> for Vec<u8> {
+    fn from(mods_config: &ModsConfig) -> Self {
+        String::from(mods_config).into_bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for ModsConfigFile {
+    type Error = xml::reader::Error;
+
+    /// Tries to parse the given bytes as [`ModsConfig`], keeping them to write back to.
+    /// If they aren't UTF-8, it's written as a new file instead.
     ///
-    /// # Panics
-    /// * If [`XmlEvent::Characters`] is detected outside of one of the expected tags.
-    /// * [`bytes.len() < 3`]
+    /// # Errors
+    /// * [`xml::reader::Error`]: invalid XML
     fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        enum Section {
-            Version,
-            ActiveMods,
-            KnownExpansions,
+        let mods_config = ModsConfig::try_from(bytes)?;
+        let source = match std::str::from_utf8(bytes) {
+            Ok(text) => Source::new(String::from(text), mods_config.clone()),
+            Err(_) => Source::new_file(),
+        };
+        Ok(Self {
+            mods_config,
+            source,
+        })
+    }
+}
+
+impl TryFrom<&Path> for ModsConfigFile {
+    type Error = super::ParseXMLError;
+
+    /// Tries to read a file, then parse it as [`ModsConfigFile`].
+    ///
+    /// # Errors
+    /// * [`xml::reader::Error`]: invalid XML (see `Self::TryFrom<&[u8]>`)
+    /// * [`std::io::Error`]: failed to read the file at the given path
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        let bytes = std::fs::read(path)?;
+        Self::try_from(bytes.as_slice()).map_err(Into::into)
+    }
+}
+
+impl From<ModsConfig> for ModsConfigFile {
+    /// A file that doesn't exist yet.
+    fn from(mods_config: ModsConfig) -> Self {
+        Self {
+            mods_config,
+            source: Source::new_file(),
         }
+    }
+}
+
+impl From<&ModsConfigFile> for String {
+    fn from(file: &ModsConfigFile) -> Self {
+        file.source.write(&file.mods_config)
+    }
+}
 
-        let reader = EventReader::new(strip_bom(bytes));
+impl From<&ModsConfigFile> for Vec<u8> {
+    fn from(file: &ModsConfigFile) -> Self {
+        String::from(file).into_bytes()
+    }
+}
+
+impl ModsConfig {
+    fn parse_values(bytes: &[u8]) -> Result<Self, xml::reader::Error> {
+        let reader = ParserConfig::new()
+            .cdata_to_characters(true)
+            .create_reader(strip_bom(bytes));
         let mut mods_config = Self::default();
 
-        let mut section: Option<Section> = None;
+        let mut xml_path: Vec<String> = Vec::new();
+        let mut text = String::new();
         for event in reader {
             match event? {
                 XmlEvent::StartElement { name, .. } => {
-                    if name.local_name == "version" {
-                        section = Some(Section::Version);
-                    } else if name.local_name == "activeMods" {
-                        section = Some(Section::ActiveMods);
-                    } else if name.local_name == "knownExpansions" {
-                        section = Some(Section::KnownExpansions);
-                    }
+                    xml_path.push(name.local_name);
+                    text.clear();
                 }
-                XmlEvent::EndElement { name } => {
-                    if name.local_name == "version"
-                        || name.local_name == "activeMods"
-                        || name.local_name == "knownExpansions"
-                    {
-                        section = None;
-                    }
-                }
-                XmlEvent::Characters(text) => {
-                    match section
-                        .as_ref()
-                        .expect("Unexpected Characters when parsing `ModsConfig`")
-                    {
-                        Section::Version => mods_config.version = Some(text),
-                        Section::ActiveMods => mods_config.activeMods.push(text),
-                        Section::KnownExpansions => mods_config.knownExpansions.push(text),
+                XmlEvent::Characters(chars) | XmlEvent::Whitespace(chars) => text.push_str(&chars),
+                XmlEvent::EndElement { .. } => {
+                    if let [_, section, rest @ ..] = xml_path.as_slice() {
+                        let text = text.trim();
+                        match (Section::from_tag(section), rest) {
+                            (Some(Section::Version), []) => {
+                                mods_config.version = Some(String::from(text));
+                            }
+                            (Some(Section::ActiveMods), [li]) if li == "li" && !text.is_empty() => {
+                                mods_config.activeMods.push(String::from(text));
+                            }
+                            (Some(Section::KnownExpansions), [li])
+                                if li == "li" && !text.is_empty() =>
+                            {
+                                mods_config.knownExpansions.push(String::from(text));
+                            }
+                            _ => {}
+                        }
                     }
+                    xml_path.pop();
+                    text.clear();
                 }
                 _ => {}
             }
@@ -81,49 +221,255 @@ impl TryFrom<&[u8]> for ModsConfig {
 
         Ok(mods_config)
     }
-}
 
-impl TryFrom<&Path> for ModsConfig {
-    type Error = super::ParseXMLError;
+    fn changed(&self, parsed: &Self, section: Section) -> bool {
+        match section {
+            Section::Version => self.version != parsed.version,
+            Section::ActiveMods => self.activeMods != parsed.activeMods,
+            Section::KnownExpansions => self.knownExpansions != parsed.knownExpansions,
+        }
+    }
 
-    /// Tries to read a file, then parse its XML as [`ModsConfig`].
-    ///
-    /// # Errors
-    /// * [`xml::reader::Error`]: invalid XML (see `Self::TryFrom<&[u8]>`)
-    /// * [`std::io::Error`]: failed to read the file at the given path
-    fn try_from(path: &Path) -> Result<Self, Self::Error> {
-        let bytes = std::fs::read(path)?;
-        Self::try_from(bytes.as_slice()).map_err(Into::into)
+    /// Serializes the element for `section`, or `None` if it should be left out.
+    /// `old` is the element it's replacing (if any), to copy the indenting of the items from.
+    fn render(&self, section: Section, old: &str, indent: &str, newline: &str) -> Option<String> {
+        let items = match section {
+            Section::Version => {
+                return self
+                    .version
+                    .as_ref()
+                    .map(|version| format!("<version>{}</version>", escape_str_pcdata(version)));
+            }
+            Section::ActiveMods => &self.activeMods,
+            Section::KnownExpansions => &self.knownExpansions,
+        };
+
+        let tag = section.tag();
+        if items.is_empty() {
+            return Some(format!("<{tag} />"));
+        }
+
+        let item_indent = old
+            .find("<li>")
+            .map(|i| line_indent(old, i))
+            .filter(|item_indent| !item_indent.is_empty())
+            .map_or_else(
+                || {
+                    if indent.is_empty() {
+                        String::from(DEFAULT_INDENT)
+                    } else {
+                        indent.repeat(2)
+                    }
+                },
+                String::from,
+            );
+
+        let mut out = format!("<{tag}>");
+        for item in items {
+            let _ = write!(
+                out,
+                "{newline}{item_indent}<li>{}</li>",
+                escape_str_pcdata(item)
+            );
+        }
+        let _ = write!(out, "{newline}{indent}</{tag}>");
+        Some(out)
     }
 }
 
-impl From<&ModsConfig> for String {
-    fn from(mods_config: &ModsConfig) -> Self {
-        let mut out =
-            String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<ModsConfigData>\n");
+impl Source {
+    /// Finds the sections in `text`, which must be well-formed XML.
+    fn new(text: String, parsed: ModsConfig) -> Self {
+        let mut sections = Vec::new();
+        let mut root_end = None;
+        let mut open: Option<(Section, usize)> = None;
+        let mut depth = 0_usize;
+
+        let mut i = 0;
+        while let Some(offset) = text[i..].find('<') {
+            let start = i + offset;
+            let rest = &text[start..];
+            let len = if rest.starts_with("<!--") {
+                find_end(rest, 4, "-->")
+            } else if rest.starts_with("<![CDATA[") {
+                find_end(rest, 9, "]]>")
+            } else if rest.starts_with("<?") {
+                find_end(rest, 2, "?>")
+            } else {
+                tag_end(rest)
+            };
+            let Some(len) = len else {
+                break;
+            };
+            let tag = &rest[..len];
+            i = start + len;
 
-        if let Some(version) = &mods_config.version {
-            out.push_str(&format!("    <version>{version}</version>\n"));
+            if tag.starts_with("</") {
+                depth = depth.saturating_sub(1);
+                match depth {
+                    0 => root_end = Some(start),
+                    1 => {
+                        if let Some((section, from)) = open.take() {
+                            sections.push((section, from..i));
+                        }
+                    }
+                    _ => {}
+                }
+            } else if !tag.starts_with("<!") && !tag.starts_with("<?") {
+                let self_closing = tag.ends_with("/>");
+                if depth == 1 {
+                    if let Some(section) = Section::from_tag(tag_name(tag)) {
+                        if self_closing {
+                            sections.push((section, start..i));
+                        } else {
+                            open = Some((section, start));
+                        }
+                    }
+                }
+                if !self_closing {
+                    depth += 1;
+                }
+            }
         }
-        if !mods_config.activeMods.is_empty() {
-            out.push_str("    <activeMods>\n");
-            out.push_str(&fold_lis(&mods_config.activeMods, 2));
-            out.push_str("    </activeMods>\n");
+
+        Self {
+            text,
+            parsed,
+            sections,
+            root_end,
         }
-        if !mods_config.knownExpansions.is_empty() {
-            out.push_str("    <knownExpansions>\n");
-            out.push_str(&fold_lis(&mods_config.knownExpansions, 2));
-            out.push_str("    </knownExpansions>\n");
+    }
+
+    fn new_file() -> Self {
+        Self::new(String::from(NEW_FILE), ModsConfig::default())
+    }
+
+    /// Serializes `mods_config`, only replacing the sections of the original text that changed.
+    fn write(&self, mods_config: &ModsConfig) -> String {
+        let text = self.text.as_str();
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let indent = self
+            .sections
+            .first()
+            .map_or(DEFAULT_INDENT, |(_, range)| line_indent(text, range.start));
+
+        let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+        let mut inserted = String::new();
+        for section in Section::ALL {
+            if !mods_config.changed(&self.parsed, section) {
+                continue;
+            }
+
+            let mut ranges = self
+                .sections
+                .iter()
+                .filter(|(s, _)| *s == section)
+                .map(|(_, range)| range.clone());
+            match ranges.next() {
+                Some(range) => {
+                    let element = mods_config.render(
+                        section,
+                        &text[range.clone()],
+                        line_indent(text, range.start),
+                        newline,
+                    );
+                    match element {
+                        Some(element) => edits.push((range, element)),
+                        None => edits.push((whole_lines(text, range), String::new())),
+                    }
+                }
+                None => {
+                    if let Some(element) = mods_config.render(section, "", indent, newline) {
+                        let _ = write!(inserted, "{indent}{element}{newline}");
+                    }
+                }
+            }
+            // any duplicates of the section would be read as part of it, so they're merged into the first
+            edits.extend(ranges.map(|range| (whole_lines(text, range), String::new())));
         }
 
-        out.push_str("</ModsConfigData>");
+        if !inserted.is_empty() {
+            let Some(root_end) = self.root_end else {
+                // a self-closing root, so there's nowhere to put them
+                return Self::new_file().write(mods_config);
+            };
+            let line_start = line_start(text, root_end);
+            if text[line_start..root_end].trim().is_empty() {
+                edits.push((line_start..line_start, inserted));
+            } else {
+                edits.push((root_end..root_end, format!("{newline}{inserted}")));
+            }
+        }
 
+        edits.sort_by_key(|(range, _)| range.start);
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for (range, replacement) in edits {
+            out.push_str(&text[last..range.start]);
+            out.push_str(&replacement);
+            last = range.end;
+        }
+        out.push_str(&text[last..]);
         out
     }
 }
 
-impl From<&ModsConfig> for Vec<u8> {
-    fn from(mods_config: &ModsConfig) -> Self {
-        String::from(mods_config).into_bytes()
+/// The length of the markup at the start of `rest` that ends with `end`, skipping the first `skip` bytes.
+fn find_end(rest: &str, skip: usize, end: &str) -> Option<usize> {
+    rest.get(skip..)?.find(end).map(|i| skip + i + end.len())
+}
+
+/// The length of the tag (or declaration) at the start of `rest`, ignoring `>`s in quotes and brackets.
+fn tag_end(rest: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    let mut brackets = 0_usize;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if q == c => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[') => brackets += 1,
+            (None, ']') => brackets = brackets.saturating_sub(1),
+            (None, '>') if brackets == 0 => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The local name of a start tag like `<activeMods>` or `<x:version a="b"/>`.
+fn tag_name(tag: &str) -> &str {
+    let name = tag
+        .trim_start_matches('<')
+        .split(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .next()
+        .unwrap_or_default();
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// The whitespace before `pos` on its line, or nothing if there's something else before it.
+fn line_indent(text: &str, pos: usize) -> &str {
+    let prefix = &text[line_start(text, pos)..pos];
+    if prefix.chars().all(char::is_whitespace) {
+        prefix
+    } else {
+        ""
+    }
+}
+
+/// Extends `range` to take its line with it, if nothing else is on that line before it.
+fn whole_lines(text: &str, range: Range<usize>) -> Range<usize> {
+    let line_start = line_start(text, range.start);
+    if line_start == 0 || !text[line_start..range.start].trim().is_empty() {
+        return range;
     }
+    let newline_len = if text[..line_start].ends_with("\r\n") {
+        2
+    } else {
+        1
+    };
+    line_start - newline_len..range.end
 }
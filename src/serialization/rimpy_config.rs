@@ -1,5 +1,10 @@
 use crate::{
-    helpers::{config::get_config_dir, paths::push_mods_config_path},
+    helpers::{
+        config::{
+            find_game_dir, find_workshop_dir, get_config_dir, get_game_config_dir_from_env, Env,
+        },
+        paths::push_mods_config_path,
+    },
     serialization::ini::{INIError, INIReader},
     traits::LogIfErr,
 };
@@ -32,6 +37,88 @@ impl RimPyConfigFolders {
     pub fn mods_config_path(&self) -> Option<PathBuf> {
         get_config_dir().log_if_err().map(push_mods_config_path)
     }
+
+    /// Sets the folder with the given key, as used in the `[Folders]` section of `config.ini`.
+    /// Unknown keys are ignored.
+    pub(crate) fn set(&mut self, key: &str, value: String) {
+        let value = Some(PathBuf::from(value));
+        match key {
+            "Config folder" => self.config_folder = value,
+            "Game folder" => self.game_folder = value,
+            "Local mods" => self.local_mods = value,
+            "Expansions" => self.expansions = value,
+            "Steam mods" => self.steam_mods = value,
+            "SteamCMD" => self.steamcmd = value,
+            _ => {}
+        }
+    }
+
+    /// Pairs each folder that's set with its key in the `[Folders]` section of `config.ini`.
+    pub(crate) fn keyed(&self) -> impl Iterator<Item = (&'static str, &PathBuf)> {
+        [
+            ("Config folder", &self.config_folder),
+            ("Game folder", &self.game_folder),
+            ("Local mods", &self.local_mods),
+            ("Expansions", &self.expansions),
+            ("Steam mods", &self.steam_mods),
+            ("SteamCMD", &self.steamcmd),
+        ]
+        .into_iter()
+        .filter_map(|(key, path)| path.as_ref().map(|path| (key, path)))
+    }
+
+    /// The folders mods are installed to: expansions, then steam mods, then local mods.
+    #[must_use]
+    pub fn mod_dirs(&self) -> Vec<PathBuf> {
+        [&self.expansions, &self.steam_mods, &self.local_mods]
+            .into_iter()
+            .filter_map(Clone::clone)
+            .collect()
+    }
+
+    /// Looks for the game's folders in their default locations for the current OS.
+    /// Folders that don't exist are left as `None`.
+    #[must_use]
+    pub fn discover(env: &impl Env) -> Self {
+        let config_folder = get_game_config_dir_from_env(env)
+            .ok()
+            .filter(|path| path.is_dir());
+        let game_folder = find_game_dir(env);
+        let local_mods = game_folder
+            .as_ref()
+            .map(|game| game.join("Mods"))
+            .filter(|path| path.is_dir());
+        let expansions = game_folder
+            .as_ref()
+            .map(|game| game.join("Data"))
+            .filter(|path| path.is_dir());
+        let steam_mods = find_workshop_dir(env);
+
+        Self {
+            config_folder,
+            game_folder,
+            local_mods,
+            expansions,
+            steam_mods,
+            steamcmd: None,
+        }
+    }
+
+    /// Fills in any folders that are `None` with those from `other`.
+    pub fn fill_missing(&mut self, other: Self) {
+        fn fill(field: &mut Option<PathBuf>, other: Option<PathBuf>) {
+            if field.is_none() {
+                *field = other;
+            }
+        }
+
+        fill(&mut self.config_folder, other.config_folder);
+        fill(&mut self.game_folder, other.game_folder);
+        fill(&mut self.local_mods, other.local_mods);
+        fill(&mut self.expansions, other.expansions);
+        fill(&mut self.steam_mods, other.steam_mods);
+        fill(&mut self.steamcmd, other.steamcmd);
+    }
 }
 
 impl RimPyConfig {
@@ -51,13 +138,21 @@ impl RimPyConfig {
     /// * If it can't open the file (e.g. doesn't exist, perms)
     /// * If it can't parse that file as INI-syntax
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, INIError> {
-        Self::try_from(INIReader::new(path).map_err(INIError::IOError)?).map_err(Into::into)
+        Self::try_from(INIReader::new(path).map_err(INIError::IOError)?)
     }
 
     #[must_use]
     pub fn mods_config_path(&self) -> Option<PathBuf> {
         self.folders.mods_config_path()
     }
+
+    /// Fills in any folders missing from the config by looking in their default locations.
+    /// See [`RimPyConfigFolders::discover`].
+    #[must_use]
+    pub fn with_discovered_folders(mut self, env: &impl Env) -> Self {
+        self.folders.fill_missing(RimPyConfigFolders::discover(env));
+        self
+    }
 }
 
 impl TryFrom<INIReader<'_>> for RimPyConfig {
@@ -69,15 +164,7 @@ impl TryFrom<INIReader<'_>> for RimPyConfig {
         for kvp in reader {
             let kvp = kvp?; // kinda annoying
             match kvp.section.as_deref() {
-                Some("Folders") => match kvp.key.as_str() {
-                    "Config folder" => conf.folders.config_folder = Some(PathBuf::from(kvp.value)),
-                    "Game folder" => conf.folders.game_folder = Some(PathBuf::from(kvp.value)),
-                    "Local mods" => conf.folders.local_mods = Some(PathBuf::from(kvp.value)),
-                    "Expansions" => conf.folders.expansions = Some(PathBuf::from(kvp.value)),
-                    "Steam mods" => conf.folders.steam_mods = Some(PathBuf::from(kvp.value)),
-                    "SteamCMD" => conf.folders.steamcmd = Some(PathBuf::from(kvp.value)),
-                    _ => {}
-                },
+                Some("Folders") => conf.folders.set(&kvp.key, kvp.value),
                 Some("Colors") => {
                     colors.insert(kvp.key, kvp.value);
                 }
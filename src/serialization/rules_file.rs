@@ -0,0 +1,123 @@
+use crate::LoadRules;
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+};
+use thiserror::Error;
+
+/// Parses a rules file, as used by `RimPy`'s community rules and rimrs's user rules.
+///
+/// The file looks like
+/// ```json
+/// {
+///     "timestamp": 1672531200,
+///     "rules": {
+///         "some.mod": {
+///             "loadAfter": { "other.mod": { "name": ["Other Mod"], "comment": ["why"] } },
+///             "loadBefore": {},
+///             "incompatibleWith": {},
+///             "loadTop": { "value": false },
+///             "loadBottom": { "value": true }
+///         }
+///     }
+/// }
+/// ```
+/// Package ids are lowercased.
+///
+/// # Errors
+/// If it isn't valid JSON, or isn't laid out like a rules file.
+pub fn parse(bytes: &[u8]) -> Result<(Option<u64>, HashMap<String, LoadRules>), RulesFileError> {
+    let root: Value = serde_json::from_slice(bytes)?;
+    let rules = root
+        .get("rules")
+        .and_then(Value::as_object)
+        .ok_or_else(|| RulesFileError::InvalidData(String::from("missing `rules`")))?;
+
+    let rules = rules
+        .iter()
+        .map(|(pid, rule)| {
+            let rule = rule.as_object().ok_or_else(|| {
+                RulesFileError::InvalidData(format!("rules for `{pid}` aren't an object"))
+            })?;
+            Ok((pid.to_lowercase(), parse_rule(rule)))
+        })
+        .collect::<Result<_, RulesFileError>>()?;
+
+    Ok((root.get("timestamp").and_then(Value::as_u64), rules))
+}
+
+fn parse_rule(rule: &Map<String, Value>) -> LoadRules {
+    // usually objects keyed by package id, but accept plain lists too
+    let package_ids = |key: &str| match rule.get(key) {
+        Some(Value::Object(map)) => map.keys().map(|pid| pid.to_lowercase()).collect(),
+        Some(Value::Array(list)) => list
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_lowercase)
+            .collect(),
+        _ => HashSet::new(),
+    };
+    let flag = |key: &str| match rule.get(key) {
+        Some(Value::Object(map)) => map.get("value").and_then(Value::as_bool) == Some(true),
+        Some(Value::Bool(b)) => *b,
+        _ => false,
+    };
+
+    LoadRules {
+        load_after: package_ids("loadAfter"),
+        load_before: package_ids("loadBefore"),
+        incompatible_with: package_ids("incompatibleWith"),
+        load_top: flag("loadTop"),
+        load_bottom: flag("loadBottom"),
+    }
+}
+
+/// The reverse of [`parse`], leaving out empty rules.
+#[allow(clippy::implicit_hasher)]
+#[must_use]
+pub fn to_json(timestamp: Option<u64>, rules: &HashMap<String, LoadRules>) -> Value {
+    let package_ids = |pids: &HashSet<String>| {
+        pids.iter()
+            .map(|pid| (pid.clone(), json!({})))
+            .collect::<Map<_, _>>()
+    };
+
+    let rules: Map<_, _> = rules
+        .iter()
+        .filter(|(_, rule)| !rule.is_empty())
+        .map(|(pid, rule)| {
+            let mut out = Map::new();
+            for (key, pids) in [
+                ("loadAfter", &rule.load_after),
+                ("loadBefore", &rule.load_before),
+                ("incompatibleWith", &rule.incompatible_with),
+            ] {
+                if !pids.is_empty() {
+                    out.insert(String::from(key), Value::Object(package_ids(pids)));
+                }
+            }
+            for (key, value) in [("loadTop", rule.load_top), ("loadBottom", rule.load_bottom)] {
+                if value {
+                    out.insert(String::from(key), json!({ "value": true }));
+                }
+            }
+            (pid.clone(), Value::Object(out))
+        })
+        .collect();
+
+    json!({
+        "timestamp": timestamp,
+        "rules": rules,
+    })
+}
+
+#[derive(Debug, Error)]
+pub enum RulesFileError {
+    #[error("couldn't read file: {0}")]
+    IOError(#[from] io::Error),
+    #[error("invalid JSON: {0}")]
+    JSONError(#[from] serde_json::Error),
+    #[error("invalid rules file: {0}")]
+    InvalidData(String),
+}
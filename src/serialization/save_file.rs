@@ -0,0 +1,111 @@
+use super::{import::ImportedMod, ParseXMLError};
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+use xml::reader::{EventReader, XmlEvent};
+
+/// The `<meta>` block at the start of a `RimWorld` save (`.rws`) file,
+/// which lists the mods the save was played with.
+///
+/// The file looks like
+/// ```xml
+/// <savegame>
+///     <meta>
+///         <gameVersion>1.4.3613 rev641</gameVersion>
+///         <modIds>
+///             <li>ludeon.rimworld</li>
+///             <li>brrainz.harmony</li>
+///         </modIds>
+///         <modSteamIds>
+///             <li>0</li>
+///             <li>2009463077</li>
+///         </modSteamIds>
+///         <modNames>
+///             <li>Core</li>
+///             <li>Harmony</li>
+///         </modNames>
+///     </meta>
+///     <game>...</game>
+/// </savegame>
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SaveMeta {
+    pub game_version: Option<String>,
+    /// Package ids, in load order.
+    pub mod_ids: Vec<String>,
+    /// Steam workshop ids of the mods in `mod_ids`, `0` if they aren't from the workshop.
+    pub mod_steam_ids: Vec<String>,
+    /// Names of the mods in `mod_ids`.
+    pub mod_names: Vec<String>,
+}
+
+impl SaveMeta {
+    /// Reads the `<meta>` block from a save, stopping as soon as it's done
+    /// so the (usually huge) rest of the save isn't read.
+    ///
+    /// # Errors
+    /// * [`xml::reader::Error`]: invalid XML
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, xml::reader::Error> {
+        let mut meta = Self::default();
+        let mut xml_path: Vec<String> = Vec::new();
+
+        for event in EventReader::new(reader) {
+            match event? {
+                XmlEvent::StartElement { name, .. } => xml_path.push(name.local_name),
+                XmlEvent::EndElement { name } => {
+                    xml_path.pop();
+                    if name.local_name == "meta" {
+                        break;
+                    }
+                }
+                XmlEvent::Characters(text) => match xml_path.as_slice() {
+                    [_, meta_tag, tag] if meta_tag == "meta" && tag == "gameVersion" => {
+                        meta.game_version = Some(text);
+                    }
+                    [_, meta_tag, list, _] if meta_tag == "meta" => match list.as_str() {
+                        "modIds" => meta.mod_ids.push(text),
+                        "modSteamIds" => meta.mod_steam_ids.push(text),
+                        "modNames" => meta.mod_names.push(text),
+                        _ => {}
+                    },
+                    _ => {}
+                },
+                XmlEvent::EndDocument => break,
+                _ => {}
+            }
+        }
+
+        Ok(meta)
+    }
+
+    /// Reads the `<meta>` block from the save at the given path.
+    ///
+    /// # Errors
+    /// * [`std::io::Error`]: failed to open the file
+    /// * [`xml::reader::Error`]: invalid XML
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ParseXMLError> {
+        let file = File::open(path)?;
+        Self::from_reader(BufReader::new(file)).map_err(Into::into)
+    }
+
+    /// Gets the mods from the save, along with their names and workshop ids.
+    #[must_use]
+    pub fn mods(&self) -> Vec<ImportedMod> {
+        self.mod_ids
+            .iter()
+            .enumerate()
+            .map(|(i, pid)| ImportedMod {
+                package_id: pid.clone(),
+                name: self.mod_names.get(i).cloned(),
+                // non-workshop mods have an id of 0
+                steam_workshop_id: self
+                    .mod_steam_ids
+                    .get(i)
+                    .filter(|id| id.as_str() != "0")
+                    .cloned(),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    helpers::config::{get_config_ini_path, get_settings_path, Env},
+    serialization::{
+        ini::{INIError, INIReader},
+        rimpy_config::{RimPyConfig, RimPyConfigFolders},
+    },
+    traits::LogIfErr,
+};
+use std::{
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// rimrs's own settings, stored in `settings.ini` in [`crate::helpers::config::get_rimrs_dir`].
+///
+/// Uses the same sections and keys as `RimPy`'s `config.ini` where they overlap,
+/// so an imported config looks familiar.
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    pub folders: RimPyConfigFolders,
+    pub startup_params: Option<String>,
+    /// A community rules file to use instead of the default ones, see [`crate::serialization::community_rules::CommunityRules::load`].
+    pub community_rules: Option<PathBuf>,
+    /// A user rules file to use instead of the default one, see [`crate::serialization::user_rules::UserRules::path`].
+    pub user_rules: Option<PathBuf>,
+    pub ui: UiSettings,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UiSettings {
+    pub dark_mode: bool,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self { dark_mode: true }
+    }
+}
+
+impl Settings {
+    /// Loads the settings from their default location, importing `RimPy`'s `config.ini` if there are none yet.
+    /// See [`Settings::load_or_migrate`].
+    ///
+    /// # Errors
+    /// * If it can't find the user's config directory
+    /// * See [`Settings::load_or_migrate`]
+    pub fn load() -> Result<Self, SettingsError> {
+        let settings_path = get_settings_path()?;
+        let rimpy_config_path = get_config_ini_path().ok();
+        Self::load_or_migrate(&settings_path, rimpy_config_path.as_deref())
+    }
+
+    /// Reads the settings at `settings_path`.
+    ///
+    /// If that doesn't exist, imports them from the `RimPy` `config.ini` at `rimpy_config_path` instead,
+    /// then saves them to `settings_path` so that `RimPy` isn't needed from then on.
+    /// If neither exist, returns the default settings.
+    ///
+    /// # Errors
+    /// * If it can't read or parse whichever file it picks
+    pub fn load_or_migrate(
+        settings_path: &Path,
+        rimpy_config_path: Option<&Path>,
+    ) -> Result<Self, SettingsError> {
+        if settings_path.exists() {
+            return Self::from_path(settings_path).map_err(Into::into);
+        }
+
+        if let Some(rimpy_config_path) = rimpy_config_path.filter(|p| p.exists()) {
+            log::info!(
+                "No settings found at {}, importing {}",
+                settings_path.display(),
+                rimpy_config_path.display()
+            );
+            let settings = Self::from(RimPyConfig::from_path(rimpy_config_path)?);
+            settings.save_to(settings_path).log_if_err();
+            return Ok(settings);
+        }
+
+        Ok(Self::default())
+    }
+
+    /// Tries to read the settings from a given path.
+    ///
+    /// # Errors
+    /// * If it can't open the file (e.g. doesn't exist, perms)
+    /// * If it can't parse that file as INI-syntax
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, INIError> {
+        Self::try_from(INIReader::new(path)?)
+    }
+
+    /// Writes the settings to the given path, creating its parent directories if needed.
+    ///
+    /// # Errors
+    /// If it can't create the directories or write the file.
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<(), io::Error> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, String::from(self))
+    }
+
+    /// Saves the settings to their default location.
+    ///
+    /// # Errors
+    /// * If it can't find the user's config directory
+    /// * See [`Settings::save_to`]
+    pub fn save(&self) -> Result<(), SettingsError> {
+        self.save_to(get_settings_path()?).map_err(Into::into)
+    }
+
+    /// Fills in any folders missing from the settings by looking in their default locations.
+    /// See [`RimPyConfigFolders::discover`].
+    #[must_use]
+    pub fn with_discovered_folders(mut self, env: &impl Env) -> Self {
+        self.folders.fill_missing(RimPyConfigFolders::discover(env));
+        self
+    }
+}
+
+impl From<RimPyConfig> for Settings {
+    fn from(rimpy_config: RimPyConfig) -> Self {
+        Self {
+            folders: rimpy_config.folders,
+            startup_params: rimpy_config.startup_params.filter(|p| !p.is_empty()),
+            community_rules: None,
+            user_rules: None,
+            ui: UiSettings::default(),
+        }
+    }
+}
+
+impl TryFrom<INIReader<'_>> for Settings {
+    type Error = INIError;
+
+    fn try_from(reader: INIReader<'_>) -> Result<Self, Self::Error> {
+        let mut settings = Self::default();
+
+        for kvp in reader {
+            let kvp = kvp?;
+            match (kvp.section.as_deref(), kvp.key.as_str()) {
+                (Some("Folders"), key) => settings.folders.set(key, kvp.value),
+                (Some("StartupParams"), _) => {
+                    settings.startup_params = Some(kvp.value).filter(|p| !p.is_empty());
+                }
+                (Some("Rules"), "Community rules") => {
+                    settings.community_rules = Some(PathBuf::from(kvp.value));
+                }
+                (Some("Rules"), "User rules") => {
+                    settings.user_rules = Some(PathBuf::from(kvp.value));
+                }
+                (Some("UI"), "Dark mode") => {
+                    settings.ui.dark_mode = kvp.value.parse().map_err(|_| {
+                        INIError::InvalidData(format!(
+                            "expected `true` or `false` for `Dark mode`, found `{}`",
+                            kvp.value
+                        ))
+                    })?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(settings)
+    }
+}
+
+impl From<&Settings> for String {
+    fn from(settings: &Settings) -> Self {
+        let mut out = String::from("[Folders]\n");
+        for (key, path) in settings.folders.keyed() {
+            let _ = writeln!(out, "{key} = {}", path.to_string_lossy());
+        }
+
+        if let Some(params) = settings.startup_params.as_ref() {
+            let _ = write!(out, "\n[StartupParams]\nParams = {params}\n");
+        }
+
+        let rules = [
+            ("Community rules", &settings.community_rules),
+            ("User rules", &settings.user_rules),
+        ];
+        if rules.iter().any(|(_, path)| path.is_some()) {
+            out.push_str("\n[Rules]\n");
+            for (key, path) in rules {
+                if let Some(path) = path {
+                    let _ = writeln!(out, "{key} = {}", path.to_string_lossy());
+                }
+            }
+        }
+
+        let _ = write!(out, "\n[UI]\nDark mode = {}\n", settings.ui.dark_mode);
+
+        out
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("couldn't read env variable: {0}")]
+    VarError(#[from] std::env::VarError),
+    #[error("{0}")]
+    INIError(#[from] INIError),
+    #[error("couldn't write settings: {0}")]
+    IOError(#[from] io::Error),
+}
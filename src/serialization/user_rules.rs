@@ -0,0 +1,106 @@
+use crate::{
+    helpers::{config::get_rimrs_dir, paths::push_user_rules_path},
+    serialization::rules_file::{self, RulesFileError},
+    traits::{LockIgnorePoisoned, LogIfErr},
+    LoadRules, ModList, Settings,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The user's own load order rules, for fixing mods with missing or wrong rules in their `About.xml`.
+/// Uses the same layout as the community rules, see [`rules_file::parse`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserRules {
+    /// Keyed by lowercase package id.
+    pub rules: HashMap<String, LoadRules>,
+}
+
+impl UserRules {
+    /// Where the user rules are kept: the file set in [`Settings::user_rules`],
+    /// or `userRules.json` in [`get_rimrs_dir`].
+    #[must_use]
+    pub fn path(settings: &Settings) -> Option<PathBuf> {
+        settings
+            .user_rules
+            .clone()
+            .or_else(|| get_rimrs_dir().log_if_err().map(push_user_rules_path))
+    }
+
+    /// Reads the user rules file at the given path.
+    ///
+    /// # Errors
+    /// * If it can't read the file
+    /// * If it isn't valid JSON, or isn't laid out like a rules file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, RulesFileError> {
+        let (_, rules) = rules_file::parse(&fs::read(path)?)?;
+        Ok(Self { rules })
+    }
+
+    /// Reads the user rules from [`UserRules::path`].
+    /// If there aren't any yet, or they can't be read, there are no rules (and the error is logged).
+    #[must_use]
+    pub fn load(settings: &Settings) -> Self {
+        Self::path(settings)
+            .filter(|path| path.exists())
+            .and_then(|path| Self::from_path(path).log_if_err())
+            .unwrap_or_default()
+    }
+
+    /// Writes the rules to the given path, creating its parent directories if needed.
+    ///
+    /// # Errors
+    /// If it can't create the directories or write the file.
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<(), RulesFileError> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+        let json = serde_json::to_string_pretty(&rules_file::to_json(timestamp, &self.rules))?;
+        fs::write(path, json).map_err(Into::into)
+    }
+
+    /// Gets the rules for the given mod, if there are any.
+    #[must_use]
+    pub fn get(&self, package_id: &str) -> Option<&LoadRules> {
+        self.rules.get(&package_id.to_lowercase())
+    }
+
+    /// Replaces the rules for the given mod, removing them if they're empty.
+    pub fn set(&mut self, package_id: &str, rules: LoadRules) {
+        let package_id = package_id.to_lowercase();
+        if rules.is_empty() {
+            self.rules.remove(&package_id);
+        } else {
+            self.rules.insert(package_id, rules);
+        }
+    }
+
+    /// Adds the rules in `other` to these ones.
+    /// If `other` says a mod should always load first or last, that replaces what these rules say.
+    pub fn merge(&mut self, other: UserRules) {
+        for (package_id, new) in other.rules {
+            let rules = self.rules.entry(package_id).or_default();
+            rules.load_after.extend(new.load_after);
+            rules.load_before.extend(new.load_before);
+            rules.incompatible_with.extend(new.incompatible_with);
+            if new.load_top || new.load_bottom {
+                rules.load_top = new.load_top;
+                rules.load_bottom = new.load_bottom;
+            }
+        }
+    }
+
+    /// Attaches the rules to each mod in the list that they're for, see [`crate::ModMetaData::user_rules`].
+    pub fn apply(&self, mod_list: &ModList) {
+        for (pid, mmd) in mod_list.mods.lock_ignore_poisoned().iter_mut() {
+            mmd.user_rules = self.rules.get(pid).cloned();
+        }
+    }
+}
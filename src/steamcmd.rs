@@ -0,0 +1,143 @@
+use crate::{helpers::config::RIMWORLD_APP_ID, traits::PushChained};
+use std::{
+    ffi::OsString,
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+use thiserror::Error;
+
+#[cfg(target_os = "windows")]
+const EXECUTABLE_NAME: &str = "steamcmd.exe";
+#[cfg(not(target_os = "windows"))]
+const EXECUTABLE_NAME: &str = "steamcmd.sh";
+
+const SCRIPT_FILE_NAME: &str = "rimrs_download.txt";
+
+/// Runs external programs. Lets tests swap out `SteamCMD` for a fake.
+pub trait CommandRunner {
+    /// Runs `program` with `args`, waiting for it to finish.
+    ///
+    /// # Errors
+    /// If it couldn't be run.
+    fn run(&self, program: &Path, args: &[OsString]) -> io::Result<Output>;
+}
+
+/// Runs programs with [`std::process::Command`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, program: &Path, args: &[OsString]) -> io::Result<Output> {
+        Command::new(program).args(args).output()
+    }
+}
+
+/// A `SteamCMD` install, as set in the `SteamCMD` folder setting.
+/// Used to download mods from the workshop without subscribing to them in steam.
+#[derive(Debug, Clone)]
+pub struct SteamCmd<R: CommandRunner = SystemRunner> {
+    folder: PathBuf,
+    runner: R,
+}
+
+impl SteamCmd {
+    #[must_use]
+    pub fn new(folder: PathBuf) -> Self {
+        Self::with_runner(folder, SystemRunner)
+    }
+}
+
+impl<R: CommandRunner> SteamCmd<R> {
+    #[must_use]
+    pub fn with_runner(folder: PathBuf, runner: R) -> Self {
+        Self { folder, runner }
+    }
+
+    /// The `SteamCMD` executable in the folder.
+    #[must_use]
+    pub fn executable(&self) -> PathBuf {
+        self.folder.clone().push_chained(EXECUTABLE_NAME)
+    }
+
+    /// Where `SteamCMD` puts the mods it downloads, in folders named after their workshop ids.
+    #[must_use]
+    pub fn download_dir(&self) -> PathBuf {
+        self.folder
+            .clone()
+            .push_chained("steamapps")
+            .push_chained("workshop")
+            .push_chained("content")
+            .push_chained(RIMWORLD_APP_ID)
+    }
+
+    /// Makes the `SteamCMD` script that downloads the given workshop items.
+    #[must_use]
+    pub fn script(&self, workshop_ids: &[String]) -> String {
+        let mut script = format!(
+            "@ShutdownOnFailedCommand 0\n@NoPromptForPassword 1\nforce_install_dir \"{}\"\nlogin anonymous\n",
+            self.folder.display()
+        );
+        for id in workshop_ids {
+            let _ = writeln!(script, "workshop_download_item {RIMWORLD_APP_ID} {id}");
+        }
+        script.push_str("quit\n");
+        script
+    }
+
+    /// Downloads the given workshop items, waiting for `SteamCMD` to finish.
+    ///
+    /// # Errors
+    /// * If it can't write the script to the `SteamCMD` folder
+    /// * If it can't run `SteamCMD`
+    pub fn download(&self, workshop_ids: &[String]) -> Result<DownloadReport, SteamCmdError> {
+        let executable = self.executable();
+        if !executable.is_file() {
+            return Err(SteamCmdError::NotFound(executable));
+        }
+
+        let script_path = self.folder.clone().push_chained(SCRIPT_FILE_NAME);
+        fs::write(&script_path, self.script(workshop_ids))?;
+
+        let args = [OsString::from("+runscript"), script_path.into_os_string()];
+        let output = self.runner.run(&executable, &args)?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(DownloadReport::from_output(&stdout, workshop_ids))
+    }
+}
+
+/// Which of the requested items `SteamCMD` downloaded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DownloadReport {
+    pub downloaded: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+impl DownloadReport {
+    /// Works out which items were downloaded from `SteamCMD`'s output,
+    /// which has a line like `Success. Downloaded item 2009463077 to "..."` for each one.
+    #[must_use]
+    pub fn from_output(stdout: &str, workshop_ids: &[String]) -> Self {
+        let succeeded: Vec<&str> = stdout
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Success. Downloaded item "))
+            .filter_map(|rest| rest.split_whitespace().next())
+            .collect();
+
+        let (downloaded, failed) = workshop_ids
+            .iter()
+            .cloned()
+            .partition(|id| succeeded.contains(&id.as_str()));
+        Self { downloaded, failed }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SteamCmdError {
+    #[error("couldn't find SteamCMD at {}", .0.display())]
+    NotFound(PathBuf),
+    #[error("couldn't run SteamCMD: {0}")]
+    IOError(#[from] io::Error),
+}
@@ -0,0 +1,200 @@
+use crate::traits::LockIgnorePoisoned;
+use notify::{
+    Config, Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher as _,
+};
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::{
+        mpsc::{channel, Receiver, Sender, TryIter},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+/// How often the polling fallback looks for changes.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Called whenever something changes, e.g. to wake up the UI.
+type Waker = Arc<Mutex<Option<Box<dyn Fn() + Send>>>>;
+
+/// Something that changed on disk, see [`Watcher`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WatchEvent {
+    /// Something in a mod's folder was added, changed or removed (or the folder itself).
+    ModChanged(PathBuf),
+    /// `ModsConfig.xml` was written, e.g. by the game when it exits.
+    ModsConfigChanged,
+    /// Changes might have been missed, so everything should be read again.
+    Rescan,
+}
+
+/// Watches the mod folders and `ModsConfig.xml` for changes, on a background thread.
+/// Uses the OS's file notifications (e.g. inotify on Linux) where it can, and polls otherwise.
+pub struct Watcher {
+    /// Stops watching when dropped.
+    _inner: Box<dyn notify::Watcher>,
+    rx: Receiver<WatchEvent>,
+    waker: Waker,
+    polling: bool,
+}
+
+impl std::fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watcher")
+            .field("polling", &self.polling)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Watcher {
+    /// Starts watching the given mod folders (recursively) and `ModsConfig.xml`.
+    /// Falls back to polling every [`DEFAULT_POLL_INTERVAL`] if the OS's notifications can't be used,
+    /// e.g. if there are too many folders for inotify.
+    ///
+    /// # Errors
+    /// If it can't watch them by polling either.
+    pub fn new(mod_dirs: &[PathBuf], mods_config_path: &Path) -> notify::Result<Self> {
+        let waker = Waker::default();
+        let (tx, rx) = channel();
+        let handler = event_handler(
+            mod_dirs.to_vec(),
+            mods_config_path.to_path_buf(),
+            tx,
+            &waker,
+        );
+
+        match RecommendedWatcher::new(handler, Config::default())
+            .and_then(|watcher| watch(watcher, mod_dirs, mods_config_path))
+        {
+            Ok(watcher) => Ok(Self {
+                _inner: Box::new(watcher),
+                rx,
+                waker,
+                polling: false,
+            }),
+            Err(err) => {
+                log::warn!("couldn't watch for changes ({err}), polling instead");
+                Self::polling(mod_dirs, mods_config_path, DEFAULT_POLL_INTERVAL)
+            }
+        }
+    }
+
+    /// Starts watching the given mod folders and `ModsConfig.xml` by checking them every `interval`.
+    /// Files are compared by when they were last modified, to the second.
+    ///
+    /// # Errors
+    /// If it can't read the folders.
+    pub fn polling(
+        mod_dirs: &[PathBuf],
+        mods_config_path: &Path,
+        interval: Duration,
+    ) -> notify::Result<Self> {
+        let waker = Waker::default();
+        let (tx, rx) = channel();
+        let handler = event_handler(
+            mod_dirs.to_vec(),
+            mods_config_path.to_path_buf(),
+            tx,
+            &waker,
+        );
+        let watcher = PollWatcher::new(handler, Config::default().with_poll_interval(interval))?;
+
+        Ok(Self {
+            _inner: Box::new(watch(watcher, mod_dirs, mods_config_path)?),
+            rx,
+            waker,
+            polling: true,
+        })
+    }
+
+    /// Whether it fell back to polling.
+    #[must_use]
+    pub fn is_polling(&self) -> bool {
+        self.polling
+    }
+
+    /// Sets what's called from the watcher's thread whenever something changes,
+    /// so the changes can be picked up without waiting for something else to happen.
+    pub fn set_waker(&self, waker: impl Fn() + Send + 'static) {
+        *self.waker.lock_ignore_poisoned() = Some(Box::new(waker));
+    }
+
+    /// The changes seen since this was last called, without blocking.
+    #[must_use]
+    pub fn try_iter(&self) -> TryIter<'_, WatchEvent> {
+        self.rx.try_iter()
+    }
+}
+
+/// Works out what a change to `path` means.
+/// Changes anywhere inside a mod are reported as a change to its folder.
+#[must_use]
+pub fn classify(path: &Path, mod_dirs: &[PathBuf], mods_config_path: &Path) -> Option<WatchEvent> {
+    if path == mods_config_path {
+        return Some(WatchEvent::ModsConfigChanged);
+    }
+
+    mod_dirs.iter().find_map(|dir| {
+        let rest = path.strip_prefix(dir).ok()?;
+        match rest.components().next()? {
+            Component::Normal(mod_folder) => Some(WatchEvent::ModChanged(dir.join(mod_folder))),
+            _ => None,
+        }
+    })
+}
+
+fn watch<W: notify::Watcher>(
+    mut watcher: W,
+    mod_dirs: &[PathBuf],
+    mods_config_path: &Path,
+) -> notify::Result<W> {
+    for dir in mod_dirs.iter().filter(|dir| dir.is_dir()) {
+        watcher.watch(dir, RecursiveMode::Recursive)?;
+    }
+    // the game might replace the file rather than write to it, so watch the folder it's in instead
+    if let Some(config_dir) = mods_config_path.parent().filter(|dir| dir.is_dir()) {
+        watcher.watch(config_dir, RecursiveMode::NonRecursive)?;
+    }
+    Ok(watcher)
+}
+
+fn event_handler(
+    mod_dirs: Vec<PathBuf>,
+    mods_config_path: PathBuf,
+    tx: Sender<WatchEvent>,
+    waker: &Waker,
+) -> impl Fn(notify::Result<Event>) + Send + 'static {
+    let waker = waker.clone();
+
+    move |res: notify::Result<Event>| {
+        let events = match res {
+            Ok(event) if event.need_rescan() => vec![WatchEvent::Rescan],
+            // reading files (including our own reads) isn't a change
+            Ok(Event {
+                kind: EventKind::Access(_),
+                ..
+            }) => return,
+            Ok(event) => event
+                .paths
+                .iter()
+                .filter_map(|path| classify(path, &mod_dirs, &mods_config_path))
+                .collect(),
+            Err(err) => {
+                log::warn!("{err}");
+                return;
+            }
+        };
+        if events.is_empty() {
+            return;
+        }
+
+        for event in events {
+            if tx.send(event).is_err() {
+                return;
+            }
+        }
+        if let Some(wake) = waker.lock_ignore_poisoned().as_ref() {
+            wake();
+        }
+    }
+}
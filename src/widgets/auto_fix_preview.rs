@@ -0,0 +1,116 @@
+use crate::{
+    helpers::diff::Change,
+    traits::{LockIgnorePoisoned, LogIfErr},
+    AutoFix, ModMetaData,
+};
+use eframe::egui::{self, Color32, Response, ScrollArea, Ui, Widget};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    sync::{mpsc::Sender, Arc, Mutex},
+};
+
+/// Window showing the changes proposed by the auto-fix button, so they can be checked before applying them.
+///
+/// Shown whenever `pending` holds an [`AutoFix`].
+#[derive(Debug)]
+pub struct AutoFixPreview {
+    pending: Rc<RefCell<Option<AutoFix>>>,
+    mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+    change_mod_list_tx: Sender<Vec<String>>,
+}
+
+impl AutoFixPreview {
+    #[must_use]
+    pub fn new(
+        pending: Rc<RefCell<Option<AutoFix>>>,
+        mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+        change_mod_list_tx: Sender<Vec<String>>,
+    ) -> Self {
+        Self {
+            pending,
+            mod_meta_data,
+            change_mod_list_tx,
+        }
+    }
+
+    fn display_name(&self, package_id: &str) -> String {
+        self.mod_meta_data
+            .lock_ignore_poisoned()
+            .get(package_id)
+            .and_then(|mmd| mmd.name.clone())
+            .unwrap_or_else(|| String::from(package_id))
+    }
+
+    fn contents(&self, ui: &mut Ui, fix: &AutoFix) -> Option<bool> {
+        let mut choice = None;
+
+        if fix.is_empty() {
+            ui.label("Nothing could be fixed automatically.");
+        } else {
+            ui.heading("Fixes");
+            for (diagnostic, suggested) in &fix.applied {
+                ui.label(format!("{diagnostic}: {suggested}"));
+            }
+
+            ui.separator();
+            ui.heading("Active mods");
+            ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                for change in fix.diff().iter().filter(|c| c.is_change()) {
+                    let name = self.display_name(change.item());
+                    match change {
+                        Change::Added(_) => ui.colored_label(Color32::GREEN, format!("+ {name}")),
+                        Change::Removed(_) => ui.colored_label(Color32::RED, format!("- {name}")),
+                        Change::Kept(_) => unreachable!(),
+                    };
+                }
+            });
+        }
+
+        if !fix.remaining.is_empty() {
+            ui.separator();
+            ui.label(format!(
+                "{} problem(s) will remain, e.g. {}",
+                fix.remaining.len(),
+                fix.remaining[0]
+            ));
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!fix.is_empty(), egui::Button::new("Apply"))
+                .clicked()
+            {
+                choice = Some(true);
+            }
+            if ui.button("Cancel").clicked() {
+                choice = Some(false);
+            }
+        });
+
+        choice
+    }
+}
+
+impl Widget for &mut AutoFixPreview {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let choice = self.pending.borrow().as_ref().and_then(|fix| {
+            egui::Window::new("Auto-fix")
+                .collapsible(false)
+                .show(ui.ctx(), |ui| self.contents(ui, fix))
+                .and_then(|resp| resp.inner.flatten())
+        });
+
+        if let Some(apply) = choice {
+            if let Some(fix) = self.pending.borrow_mut().take() {
+                if apply {
+                    self.change_mod_list_tx.send(fix.after).log_if_err();
+                }
+            }
+        }
+
+        ui.scope(|_| {}).response
+    }
+}
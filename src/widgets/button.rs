@@ -4,6 +4,7 @@ use std::sync::mpsc::{SyncSender, TrySendError};
 /// The buttons that appear to the right of the mod lists.
 pub struct Button<'a> {
     label: &'a str,
+    label_fn: Option<Box<dyn Fn() -> String + 'a>>,
     action: Option<Box<dyn Fn() + 'a>>,
     is_enabled_fn: Option<Box<dyn Fn() -> bool + 'a>>,
     hint_sender: Option<HintSender<'a>>,
@@ -29,13 +30,16 @@ impl<'a> Button<'a> {
     /// Returns `true` if `None`.
     #[must_use]
     pub fn is_enabled(&self) -> bool {
-        self.is_enabled_fn.as_ref().map_or(true, |f| f())
+        self.is_enabled_fn.as_ref().is_none_or(|f| f())
     }
 }
 
-impl<'a> Widget for &Button<'a> {
+impl Widget for &Button<'_> {
     fn ui(self, ui: &mut Ui) -> Response {
-        let btn = egui::Button::new(self.label);
+        let btn = match self.label_fn.as_ref() {
+            Some(label_fn) => egui::Button::new(label_fn()),
+            None => egui::Button::new(self.label),
+        };
         let resp = ui.add_enabled(self.is_enabled(), btn);
 
         if resp.clicked() {
@@ -57,6 +61,7 @@ impl<'a> Widget for &Button<'a> {
 #[allow(clippy::module_name_repetitions)]
 pub struct ButtonBuilder<'a> {
     label: &'a str,
+    label_fn: Option<Box<dyn Fn() -> String + 'a>>,
     action: Option<Box<dyn Fn() + 'a>>,
     is_enabled_fn: Option<Box<dyn Fn() -> bool + 'a>>,
     hint_sender: Option<HintSender<'a>>,
@@ -67,6 +72,7 @@ impl<'a> ButtonBuilder<'a> {
     pub fn new(label: &'a str) -> Self {
         Self {
             label,
+            label_fn: None,
             action: None,
             is_enabled_fn: None,
             hint_sender: None,
@@ -90,6 +96,13 @@ impl<'a> ButtonBuilder<'a> {
         self
     }
 
+    /// Makes the label change, using this instead of the static label every time it's shown.
+    #[must_use]
+    pub fn label_fn(mut self, label_fn: Box<dyn Fn() -> String + 'a>) -> Self {
+        self.label_fn = Some(label_fn);
+        self
+    }
+
     #[must_use]
     pub fn is_enabled_fn(mut self, is_enabled_fn: Box<dyn Fn() -> bool + 'a>) -> Self {
         self.is_enabled_fn = Some(is_enabled_fn);
@@ -101,6 +114,7 @@ impl<'a> From<ButtonBuilder<'a>> for Button<'a> {
     fn from(builder: ButtonBuilder<'a>) -> Self {
         Button {
             label: builder.label,
+            label_fn: builder.label_fn,
             action: builder.action,
             hint_sender: builder.hint_sender,
             is_enabled_fn: builder.is_enabled_fn,
@@ -123,7 +137,7 @@ impl<'a> HintSender<'a> {
 
     pub fn try_send(&self) {
         match self.tx.try_send(String::from(self.msg)) {
-            Ok(_) => {}
+            Ok(()) => {}
             Err(TrySendError::Full(_)) => {
                 log::warn!("Hint channel full");
             }
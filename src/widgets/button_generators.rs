@@ -1,11 +1,19 @@
 use crate::{
-    helpers::{config::get_mod_list_path, paths::path_to_str},
-    traits::{LogIfErr, PushChained},
-    widgets::{Button, ModListing},
-    writer_thread, ModMetaData, ModsConfig, CHANGED_ACTIVE_MODS,
+    helpers::{
+        config::{get_mod_list_path, get_saves_path},
+        history::{History, HistoryStep},
+        paths::path_to_str,
+    },
+    serialization::{
+        import::{import_file, ImportResult, Imported, ImportedMod},
+        save_file::SaveMeta,
+    },
+    traits::{LockIgnorePoisoned, LogIfErr, PushChained},
+    widgets::{Button, ModListing, Notice, SaveStatus, SharedUserRules},
+    writer_thread, AutoFix, ModMetaData, SortError, SortMode, CHANGED_ACTIVE_MODS,
 };
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     collections::HashMap,
     path::PathBuf,
     process::Command,
@@ -38,46 +46,142 @@ impl<'a> Button<'a> {
             .build()
     }
 
-    /// Generates the [`Button`] that auto-sorts the active mod list.
+    /// Generates the [`Button`] that auto-sorts the active mod list, see [`crate::sort_lenient`].
     #[must_use]
     pub fn sort(
         hint_tx: SyncSender<String>,
         change_mod_list_tx: Sender<Vec<String>>,
         active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
         mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+        version: Option<String>,
+        notice: Rc<RefCell<Option<Notice>>>,
+        mode: SortMode,
     ) -> Self {
         let action = Box::new(move || {
             let active_mods = Vec::from(&active_mod_listing_ref.borrow().clone());
-            if let Some(sorted) = crate::sort(&active_mods, &mod_meta_data).log_if_err() {
-                change_mod_list_tx.send(sorted).log_if_err();
+            match crate::sort_lenient(&active_mods, &mod_meta_data, version.as_deref(), mode) {
+                Ok(report) => {
+                    let mut lines = Vec::new();
+                    if !report.ignored.is_empty() {
+                        lines.push(String::from(
+                            "Some loadBefore/loadAfter rules contradicted each other, so these were ignored:",
+                        ));
+                        lines.extend(report.ignored.iter().map(|edge| format!("- {edge}")));
+                    }
+                    if !report.moved.is_empty() {
+                        lines.push(String::from("These mods had to move:"));
+                        lines.extend(report.moved.iter().map(|moved| format!("- {moved}")));
+                    }
+                    if !lines.is_empty() {
+                        *notice.borrow_mut() = Some(Notice::new("Sorted", lines));
+                    }
+                    change_mod_list_tx.send(report.sorted).log_if_err();
+                }
+                Err(SortError::CyclicError(cycle)) => {
+                    let mut lines = vec![String::from(
+                        "These mods' About.xml rules contradict each other, and can't be ignored:",
+                    )];
+                    lines.extend(cycle.0.iter().map(|edge| format!("- {edge}")));
+                    *notice.borrow_mut() = Some(Notice::new("Couldn't sort", lines));
+                }
+                Err(err) => {
+                    log::error!("{err}");
+                    *notice.borrow_mut() =
+                        Some(Notice::new("Couldn't sort", vec![err.to_string()]));
+                }
             }
         }) as Box<dyn Fn() + 'a>;
-        let hint = "Auto-sort mods";
+        let (label, hint) = match mode {
+            SortMode::Alphabetical => ("Sort", "Auto-sort mods"),
+            SortMode::Stable => (
+                "Sort (keep order)",
+                "Auto-sort mods, only moving the ones that break a load order rule",
+            ),
+        };
 
-        Self::builder("Sort")
+        Self::builder(label)
+            .action(action)
+            .hint(hint, hint_tx)
+            .build()
+    }
+
+    /// Generates the [`Button`] that works out fixes for the problems in the active mod list,
+    /// for [`crate::widgets::AutoFixPreview`] to show.
+    #[must_use]
+    pub fn auto_fix(
+        hint_tx: SyncSender<String>,
+        active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+        mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+        version: Option<String>,
+        auto_fix_preview: Rc<RefCell<Option<AutoFix>>>,
+    ) -> Self {
+        let action = Box::new(move || {
+            let active_mods = Vec::from(&active_mod_listing_ref.borrow().clone());
+            let fix = crate::auto_fix(&mod_meta_data, &active_mods, version.as_deref());
+            *auto_fix_preview.borrow_mut() = Some(fix);
+        }) as Box<dyn Fn() + 'a>;
+        let hint =
+            "Fix missing dependencies, load order and incompatibilities (shows changes first)";
+
+        Self::builder("Auto-fix")
             .action(action)
             .hint(hint, hint_tx)
             .build()
     }
 
     /// Generates the [`Button`] that saves the active mod list to disk.
+    /// Its label shows whether the save is still going, or whether it went through.
     #[must_use]
     pub fn save(
         hint_tx: SyncSender<String>,
         writer_thread_tx: SyncSender<writer_thread::Message>,
         active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+        save_status: Rc<RefCell<SaveStatus>>,
     ) -> Self {
+        let action_save_status = save_status.clone();
         let action = Box::new(move || {
-            let active_mods = Vec::from(&active_mod_listing_ref.borrow().clone());
-            writer_thread_tx
-                .try_send(writer_thread::Message::SetActiveMods(active_mods))
-                .and_then(|_| writer_thread_tx.try_send(writer_thread::Message::Save))
-                .log_if_err();
+            let active_mods = Vec::from(&*active_mod_listing_ref.borrow());
+            action_save_status
+                .borrow_mut()
+                .save(&writer_thread_tx, active_mods);
         }) as Box<dyn Fn() + 'a>;
         let hint = "Save the mod list to ModsConfig.xml file (applies changes to game mod list)";
-        let is_enabled = Box::new(|| CHANGED_ACTIVE_MODS.check()) as Box<dyn Fn() -> bool + 'a>;
+        let enabled_save_status = save_status.clone();
+        let is_enabled = Box::new(move || {
+            CHANGED_ACTIVE_MODS.check() && !enabled_save_status.borrow().is_pending()
+        }) as Box<dyn Fn() -> bool + 'a>;
+        let label = Box::new(move || save_status.borrow().label()) as Box<dyn Fn() -> String + 'a>;
 
         Self::builder("Save")
+            .action(action)
+            .hint(hint, hint_tx)
+            .is_enabled_fn(is_enabled)
+            .label_fn(label)
+            .build()
+    }
+
+    /// Generates the [`Button`] that undoes or redoes a change to the active mod list,
+    /// enabled while there's something in the [`History`] to go back or forward to.
+    #[must_use]
+    pub fn history(
+        hint_tx: SyncSender<String>,
+        history_tx: Sender<HistoryStep>,
+        history: Rc<RefCell<History<Vec<String>>>>,
+        step: HistoryStep,
+    ) -> Self {
+        let action = Box::new(move || {
+            history_tx.send(step).log_if_err();
+        }) as Box<dyn Fn() + 'a>;
+        let is_enabled = Box::new(move || match step {
+            HistoryStep::Undo => history.borrow().can_undo(),
+            HistoryStep::Redo => history.borrow().can_redo(),
+        }) as Box<dyn Fn() -> bool + 'a>;
+        let (label, hint) = match step {
+            HistoryStep::Undo => ("Undo", "Undo the last change to the active mods (Ctrl+Z)"),
+            HistoryStep::Redo => ("Redo", "Redo the last undone change (Ctrl+Y)"),
+        };
+
+        Self::builder(label)
             .action(action)
             .hint(hint, hint_tx)
             .is_enabled_fn(is_enabled)
@@ -104,20 +208,49 @@ impl<'a> Button<'a> {
             .build()
     }
 
+    /// Generates the [`Button`] that imports a mod list or load order rules from a file,
+    /// working out what kind of file it is. See [`crate::serialization::import::ImportFormat`].
     #[must_use]
     pub fn import_list(
         hint_tx: SyncSender<String>,
         change_mod_list_tx: Sender<Vec<String>>,
+        mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+        user_rules: SharedUserRules,
+        notice: Rc<RefCell<Option<Notice>>>,
+        pending_import: Rc<RefCell<Option<ImportResult>>>,
     ) -> Self {
-        let hint = "Imports mod list from mod list file";
+        let hint =
+            "Imports mod list or load order rules from a RimPy, RimWorld or Mod Manager file";
         let action = Box::new(move || {
             let path = get_mod_list_path().log_if_err().map(|p| p.push_chained("")); // need to push empty so it opens in the dir rather than in its parent with the dir name as the input
             let path = path.as_ref().and_then(path_to_str).unwrap_or_default();
-            if let Some(parsed) =
-                tinyfiledialogs::open_file_dialog("Select mod list", path, Some((&["*.xml"], "")))
-                    .and_then(|p| ModsConfig::try_from(PathBuf::from(p).as_path()).log_if_err())
-            {
-                change_mod_list_tx.send(parsed.activeMods).log_if_err();
+            let Some(selected) = tinyfiledialogs::open_file_dialog(
+                "Select mod list or rules",
+                path,
+                Some((&["*.xml", "*.json"], "")),
+            ) else {
+                return;
+            };
+
+            match import_file(&selected) {
+                Ok(Imported::ModList(mods)) => {
+                    import_mod_list(mods, &mod_meta_data, &change_mod_list_tx, &pending_import);
+                }
+                Ok(Imported::Rules(rules)) => {
+                    let lines = rules
+                        .rules
+                        .keys()
+                        .map(|pid| format!("Added rules for {pid}"))
+                        .collect();
+                    user_rules.merge(rules, &mod_meta_data);
+                    *notice.borrow_mut() = Some(Notice::new("Imported rules", lines));
+                }
+                Err(err) => {
+                    *notice.borrow_mut() = Some(Notice::new(
+                        "Import failed",
+                        vec![format!("Couldn't import {selected}: {err}")],
+                    ));
+                }
             }
         }) as Box<dyn Fn() + 'a>;
 
@@ -127,45 +260,97 @@ impl<'a> Button<'a> {
             .build()
     }
 
+    /// Generates the [`Button`] that sets the active mods to the ones a `RimWorld` save was played with.
     #[must_use]
-    pub fn export_list(
+    pub fn import_save(
         hint_tx: SyncSender<String>,
-        writer_thread_tx: SyncSender<writer_thread::Message>,
-        active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+        change_mod_list_tx: Sender<Vec<String>>,
+        mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+        notice: Rc<RefCell<Option<Notice>>>,
+        pending_import: Rc<RefCell<Option<ImportResult>>>,
     ) -> Self {
-        let hint = "Exports mod list to file";
+        let hint = "Imports the mod list a save was played with";
         let action = Box::new(move || {
-            let mods = active_mod_listing_ref
-                .borrow()
-                .items
-                .iter()
-                .map(|item| item.package_id.clone())
-                .collect();
-
-            let mod_list_path = get_mod_list_path().log_if_err().map(|p| p.push_chained(""));
-            let mod_list_path = mod_list_path
-                .as_ref()
-                .and_then(path_to_str)
-                .unwrap_or_default();
-
-            tinyfiledialogs::save_file_dialog_with_filter(
-                "Save file list",
-                mod_list_path,
-                &["*.xml"],
-                "",
-            )
-            .map(|save_path| {
-                writer_thread_tx.try_send(writer_thread::Message::WriteTo(
-                    PathBuf::from(save_path),
-                    mods,
-                ))
-            })
-            .map(LogIfErr::log_if_err);
+            let path = get_saves_path().log_if_err().map(|p| p.push_chained(""));
+            let path = path.as_ref().and_then(path_to_str).unwrap_or_default();
+            let Some(selected) =
+                tinyfiledialogs::open_file_dialog("Select save", path, Some((&["*.rws"], "")))
+            else {
+                return;
+            };
+
+            let meta = match SaveMeta::from_path(&selected) {
+                Ok(meta) if !meta.mod_ids.is_empty() => meta,
+                Ok(_) => {
+                    *notice.borrow_mut() = Some(Notice::new(
+                        "Import failed",
+                        vec![format!("{selected} doesn't list any mods")],
+                    ));
+                    return;
+                }
+                Err(err) => {
+                    *notice.borrow_mut() = Some(Notice::new(
+                        "Import failed",
+                        vec![format!("Couldn't read {selected}: {err}")],
+                    ));
+                    return;
+                }
+            };
+
+            import_mod_list(
+                meta.mods(),
+                &mod_meta_data,
+                &change_mod_list_tx,
+                &pending_import,
+            );
         }) as Box<dyn Fn() + 'a>;
 
+        Self::builder("Import from save")
+            .hint(hint, hint_tx)
+            .action(action)
+            .build()
+    }
+
+    /// Generates the [`Button`] that opens the [`crate::widgets::ExportWindow`].
+    #[must_use]
+    pub fn export_list(hint_tx: SyncSender<String>, export_window_open: Rc<Cell<bool>>) -> Self {
+        let hint = "Exports mod list to file, e.g. to share it";
+        let action = Box::new(move || export_window_open.set(true)) as Box<dyn Fn() + 'a>;
+
         Self::builder("Export list")
             .hint(hint, hint_tx)
             .action(action)
             .build()
     }
+
+    /// Generates the [`Button`] that opens the [`crate::widgets::RestoreBackupWindow`].
+    #[must_use]
+    pub fn restore_backup(
+        hint_tx: SyncSender<String>,
+        restore_backup_window_open: Rc<Cell<bool>>,
+    ) -> Self {
+        let hint = "Go back to a previously saved mod list";
+        let action = Box::new(move || restore_backup_window_open.set(true)) as Box<dyn Fn() + 'a>;
+
+        Self::builder("Restore backup")
+            .hint(hint, hint_tx)
+            .action(action)
+            .build()
+    }
+}
+
+/// Replaces the active mods with imported ones, unless some of them aren't installed,
+/// in which case [`crate::widgets::MissingModsWindow`] asks whether to keep them first.
+fn import_mod_list(
+    mods: Vec<ImportedMod>,
+    mod_meta_data: &Mutex<HashMap<String, ModMetaData>>,
+    change_mod_list_tx: &Sender<Vec<String>>,
+    pending_import: &RefCell<Option<ImportResult>>,
+) {
+    let result = ImportResult::new(mods, &mod_meta_data.lock_ignore_poisoned());
+    if result.missing.is_empty() {
+        change_mod_list_tx.send(result.found).log_if_err();
+    } else {
+        *pending_import.borrow_mut() = Some(result);
+    }
 }
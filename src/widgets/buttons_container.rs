@@ -1,10 +1,12 @@
 use crate::{
-    widgets::{Button, ModListing},
-    ModMetaData,
+    helpers::history::{History, HistoryStep},
+    serialization::import::ImportResult,
+    widgets::{Button, ModListing, Notice, SaveStatus, SharedUserRules},
+    AutoFix, ModMetaData, SortMode,
 };
 use eframe::egui::{Response, Ui, Widget};
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     collections::HashMap,
     path::PathBuf,
     rc::Rc,
@@ -21,12 +23,23 @@ pub struct ButtonsContainer<'a>(Vec<Button<'a>>);
 impl<'a> ButtonsContainer<'a> {
     /// Creates the various buttons that appear to the right of the active mods listing.
     #[must_use]
+    #[allow(clippy::too_many_arguments)]
     pub fn generate(
         hint_tx: &SyncSender<String>,
         writer_thread_tx: SyncSender<crate::writer_thread::Message>,
         change_mod_list_tx: Sender<Vec<String>>,
         active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
         mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+        version: Option<String>,
+        auto_fix_preview: Rc<RefCell<Option<AutoFix>>>,
+        notice: Rc<RefCell<Option<Notice>>>,
+        user_rules: SharedUserRules,
+        pending_import: Rc<RefCell<Option<ImportResult>>>,
+        export_window_open: Rc<Cell<bool>>,
+        restore_backup_window_open: Rc<Cell<bool>>,
+        save_status: Rc<RefCell<SaveStatus>>,
+        history_tx: Sender<HistoryStep>,
+        history: Rc<RefCell<History<Vec<String>>>>,
         exe_path: PathBuf,
         args: Option<String>,
     ) -> Self {
@@ -36,21 +49,63 @@ impl<'a> ButtonsContainer<'a> {
                 hint_tx.clone(),
                 change_mod_list_tx.clone(),
                 active_mod_listing_ref.clone(),
-                mod_meta_data,
+                mod_meta_data.clone(),
+                version.clone(),
+                notice.clone(),
+                SortMode::Alphabetical,
+            ),
+            Button::sort(
+                hint_tx.clone(),
+                change_mod_list_tx.clone(),
+                active_mod_listing_ref.clone(),
+                mod_meta_data.clone(),
+                version.clone(),
+                notice.clone(),
+                SortMode::Stable,
+            ),
+            Button::history(
+                hint_tx.clone(),
+                history_tx.clone(),
+                history.clone(),
+                HistoryStep::Undo,
             ),
-            Button::import_list(hint_tx.clone(), change_mod_list_tx),
-            Button::export_list(
+            Button::history(hint_tx.clone(), history_tx, history, HistoryStep::Redo),
+            Button::auto_fix(
                 hint_tx.clone(),
-                writer_thread_tx.clone(),
                 active_mod_listing_ref.clone(),
+                mod_meta_data.clone(),
+                version,
+                auto_fix_preview,
+            ),
+            Button::import_list(
+                hint_tx.clone(),
+                change_mod_list_tx.clone(),
+                mod_meta_data.clone(),
+                user_rules,
+                notice.clone(),
+                pending_import.clone(),
+            ),
+            Button::import_save(
+                hint_tx.clone(),
+                change_mod_list_tx,
+                mod_meta_data,
+                notice,
+                pending_import,
+            ),
+            Button::export_list(hint_tx.clone(), export_window_open),
+            Button::save(
+                hint_tx.clone(),
+                writer_thread_tx,
+                active_mod_listing_ref,
+                save_status,
             ),
-            Button::save(hint_tx.clone(), writer_thread_tx, active_mod_listing_ref),
+            Button::restore_backup(hint_tx.clone(), restore_backup_window_open),
             Button::run(hint_tx.clone(), exe_path, args),
         ])
     }
 }
 
-impl<'a> Widget for &ButtonsContainer<'a> {
+impl Widget for &ButtonsContainer<'_> {
     fn ui(self, ui: &mut Ui) -> Response {
         ui.scope(|ui| {
             for btn in &self.0 {
@@ -0,0 +1,118 @@
+use crate::{
+    helpers::{config::get_mod_list_path, paths::path_to_str},
+    serialization::export::{export, ExportFormat, ExportedMod},
+    traits::{LockIgnorePoisoned, LogIfErr, PushChained},
+    widgets::ModListing,
+    writer_thread, ModMetaData,
+};
+use eframe::egui::{self, Response, Ui, Widget};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    path::PathBuf,
+    rc::Rc,
+    sync::{mpsc::SyncSender, Arc, Mutex},
+};
+
+/// Window for picking what format to export the active mods as, opened by the "Export list" button.
+#[derive(Debug)]
+pub struct ExportWindow<'a> {
+    open: Rc<Cell<bool>>,
+    /// [`None`] for `ModsConfig.xml`.
+    format: Option<ExportFormat>,
+    writer_thread_tx: SyncSender<writer_thread::Message>,
+    active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+    mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+    version: Option<String>,
+}
+
+impl<'a> ExportWindow<'a> {
+    #[must_use]
+    pub fn new(
+        open: Rc<Cell<bool>>,
+        writer_thread_tx: SyncSender<writer_thread::Message>,
+        active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+        mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+        version: Option<String>,
+    ) -> Self {
+        Self {
+            open,
+            format: None,
+            writer_thread_tx,
+            active_mod_listing_ref,
+            mod_meta_data,
+            version,
+        }
+    }
+
+    /// Asks where to save the export, then has the writer thread write it.
+    fn export(&self) {
+        let mods: Vec<String> = self
+            .active_mod_listing_ref
+            .borrow()
+            .items
+            .iter()
+            .map(|item| item.package_id.clone())
+            .collect();
+
+        let mod_list_path = get_mod_list_path().log_if_err().map(|p| p.push_chained(""));
+        let mod_list_path = mod_list_path
+            .as_ref()
+            .and_then(path_to_str)
+            .unwrap_or_default();
+        let filter = format!("*.{}", self.format.map_or("xml", ExportFormat::extension));
+
+        let Some(save_path) = tinyfiledialogs::save_file_dialog_with_filter(
+            "Save file list",
+            mod_list_path,
+            &[filter.as_str()],
+            "",
+        ) else {
+            return;
+        };
+        let save_path = PathBuf::from(save_path);
+
+        let contents = self.format.map(|format| {
+            let mods = ExportedMod::collect(&mods, &self.mod_meta_data.lock_ignore_poisoned());
+            export(format, &mods, self.version.as_deref())
+        });
+        // the writer thread shows a hint saying how it went, so there's no need to wait for the reply
+        writer_thread::request(&self.writer_thread_tx, |reply_tx| match contents {
+            None => writer_thread::Message::WriteTo(save_path, mods, reply_tx),
+            Some(contents) => writer_thread::Message::WriteFile(save_path, contents, reply_tx),
+        })
+        .log_if_err();
+    }
+}
+
+impl Widget for &mut ExportWindow<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        if self.open.get() {
+            let mut do_export = false;
+            let mut cancel = false;
+
+            egui::Window::new("Export list")
+                .collapsible(false)
+                .show(ui.ctx(), |ui| {
+                    ui.radio_value(&mut self.format, None, "ModsConfig.xml");
+                    for format in ExportFormat::ALL {
+                        ui.radio_value(&mut self.format, Some(format), format.to_string());
+                    }
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        do_export = ui.button("Export").clicked();
+                        cancel = ui.button("Cancel").clicked();
+                    });
+                });
+
+            if do_export || cancel {
+                self.open.set(false);
+            }
+            if do_export {
+                self.export();
+            }
+        }
+
+        ui.scope(|_| {}).response
+    }
+}
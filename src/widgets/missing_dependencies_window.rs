@@ -0,0 +1,156 @@
+use crate::{
+    steamcmd::{DownloadReport, SteamCmd, SteamCmdError},
+    traits::LogIfErr,
+    MissingDependency,
+};
+use eframe::egui::{self, Response, ScrollArea, Ui, Widget};
+use std::{
+    fmt::Write,
+    path::PathBuf,
+    sync::mpsc::{channel, Receiver, TryRecvError},
+    thread,
+};
+
+/// Window listing the dependencies of active mods that aren't installed,
+/// with links to download them and a way to download them with `SteamCMD`.
+/// Opened from the [`crate::widgets::Status`] bar.
+#[derive(Debug)]
+pub struct MissingDependenciesWindow {
+    pub open: bool,
+    missing: Vec<MissingDependency>,
+    steamcmd: Option<PathBuf>,
+    download_rx: Option<Receiver<Result<DownloadReport, SteamCmdError>>>,
+    download_status: Option<String>,
+}
+
+impl MissingDependenciesWindow {
+    /// Makes a new window, where `steamcmd` is the `SteamCMD` folder, if it's set.
+    #[must_use]
+    pub fn new(steamcmd: Option<PathBuf>) -> Self {
+        Self {
+            open: false,
+            missing: Vec::new(),
+            steamcmd,
+            download_rx: None,
+            download_status: None,
+        }
+    }
+
+    pub fn set_missing(&mut self, missing: Vec<MissingDependency>) {
+        self.missing = missing;
+    }
+
+    #[must_use]
+    pub fn missing(&self) -> &[MissingDependency] {
+        &self.missing
+    }
+
+    /// The workshop ids of the missing dependencies that say what theirs is.
+    fn workshop_ids(&self) -> Vec<String> {
+        self.missing
+            .iter()
+            .filter_map(|m| m.details.steam_workshop_id())
+            .map(String::from)
+            .collect()
+    }
+
+    /// Starts downloading the missing dependencies on another thread.
+    fn download(&mut self, folder: PathBuf) {
+        let (tx, rx) = channel();
+        let workshop_ids = self.workshop_ids();
+        thread::spawn(move || {
+            tx.send(SteamCmd::new(folder).download(&workshop_ids))
+                .log_if_err();
+        });
+        self.download_rx = Some(rx);
+        self.download_status = Some(String::from("Downloading..."));
+    }
+
+    fn check_download(&mut self) {
+        let Some(rx) = self.download_rx.as_ref() else {
+            return;
+        };
+        let status = match rx.try_recv() {
+            Ok(Ok(report)) => {
+                let mut status = format!("Downloaded {} mods", report.downloaded.len());
+                if let Some(folder) = self.steamcmd.clone() {
+                    let dir = SteamCmd::new(folder).download_dir();
+                    let _ = write!(status, " to {}", dir.display());
+                }
+                if !report.failed.is_empty() {
+                    let _ = write!(status, "\nCouldn't download {}", report.failed.join(", "));
+                }
+                status
+            }
+            Ok(Err(err)) => err.to_string(),
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => String::from("Download stopped unexpectedly"),
+        };
+        self.download_status = Some(status);
+        self.download_rx = None;
+    }
+}
+
+impl Widget for &mut MissingDependenciesWindow {
+    fn ui(self, ui: &mut Ui) -> Response {
+        self.check_download();
+
+        let mut open = self.open;
+        let mut download = None;
+
+        if open {
+            egui::Window::new("Missing dependencies")
+                .collapsible(false)
+                .open(&mut open)
+                .show(ui.ctx(), |ui| {
+                    ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for m in &self.missing {
+                            ui.horizontal(|ui| {
+                                ui.label(format!(
+                                    "{}, required by {}",
+                                    m.name(),
+                                    m.required_by.join(", ")
+                                ));
+                                for (text, url) in [
+                                    ("Workshop", m.details.steamWorkshopUrl.as_ref()),
+                                    ("Download", m.details.downloadUrl.as_ref()),
+                                ] {
+                                    if let Some(url) = url {
+                                        if ui.link(text).on_hover_text(url).clicked() {
+                                            open::that(url).log_if_err();
+                                        }
+                                    }
+                                }
+                            });
+                        }
+                    });
+                    ui.separator();
+
+                    let can_download = self.steamcmd.is_some()
+                        && self.download_rx.is_none()
+                        && !self.workshop_ids().is_empty();
+                    let btn = ui
+                        .add_enabled(
+                            can_download,
+                            egui::Button::new("Download missing via SteamCMD"),
+                        )
+                        .on_disabled_hover_text(
+                            "Needs the SteamCMD folder to be set, and workshop links for the mods",
+                        );
+                    if btn.clicked() {
+                        download.clone_from(&self.steamcmd);
+                    }
+                    if let Some(status) = self.download_status.as_ref() {
+                        ui.label(status);
+                    }
+                });
+        }
+
+        self.open = open;
+        if let Some(folder) = download {
+            self.download(folder);
+        }
+
+        ui.scope(|_| {}).response
+    }
+}
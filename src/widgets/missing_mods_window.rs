@@ -0,0 +1,83 @@
+use crate::{serialization::import::ImportResult, traits::LogIfErr};
+use eframe::egui::{self, Response, ScrollArea, Ui, Widget};
+use std::{cell::RefCell, rc::Rc, sync::mpsc::Sender};
+
+/// Window listing the mods from an imported list that aren't installed,
+/// letting the user keep or drop them before the list replaces the active mods.
+#[derive(Debug)]
+pub struct MissingModsWindow {
+    pending: Rc<RefCell<Option<ImportResult>>>,
+    change_mod_list_tx: Sender<Vec<String>>,
+}
+
+impl MissingModsWindow {
+    #[must_use]
+    pub fn new(
+        pending: Rc<RefCell<Option<ImportResult>>>,
+        change_mod_list_tx: Sender<Vec<String>>,
+    ) -> Self {
+        Self {
+            pending,
+            change_mod_list_tx,
+        }
+    }
+}
+
+impl Widget for &mut MissingModsWindow {
+    fn ui(self, ui: &mut Ui) -> Response {
+        // `Some(keep_missing)` once the user picks
+        let mut choice: Option<bool> = None;
+        let mut cancel = false;
+
+        if let Some(result) = self.pending.borrow().as_ref() {
+            egui::Window::new("Missing mods")
+                .collapsible(false)
+                .show(ui.ctx(), |ui| {
+                    ui.label(format!(
+                        "{} of the {} mods in the list aren't installed:",
+                        result.missing.len(),
+                        result.mods.len()
+                    ));
+                    ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for m in &result.missing {
+                            match m.steam_workshop_url() {
+                                Some(url) => ui.hyperlink_to(m.to_string(), url),
+                                None => ui.label(m.to_string()),
+                            };
+                        }
+                    });
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button("Keep them")
+                            .on_hover_text(
+                                "Import the whole list, so they get loaded once installed",
+                            )
+                            .clicked()
+                        {
+                            choice = Some(true);
+                        }
+                        if ui
+                            .button("Drop them")
+                            .on_hover_text("Only import the installed mods")
+                            .clicked()
+                        {
+                            choice = Some(false);
+                        }
+                        cancel = ui.button("Cancel").clicked();
+                    });
+                });
+        }
+
+        if choice.is_some() || cancel {
+            let result = self.pending.borrow_mut().take();
+            if let (Some(result), Some(keep_missing)) = (result, choice) {
+                self.change_mod_list_tx
+                    .send(result.into_mod_list(keep_missing))
+                    .log_if_err();
+            }
+        }
+
+        ui.scope(|_| {}).response
+    }
+}
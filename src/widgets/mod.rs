@@ -7,6 +7,9 @@ pub use mod_listing_item::ModListingItem;
 mod mod_info;
 pub use mod_info::ModInfo;
 
+mod user_rules_editor;
+pub use user_rules_editor::{SharedUserRules, UserRulesEditor};
+
 mod button;
 pub use button::{Button, ButtonBuilder};
 mod button_generators;
@@ -17,5 +20,29 @@ pub use buttons_container::ButtonsContainer;
 mod path_label;
 pub use path_label::PathLabel;
 
+mod auto_fix_preview;
+pub use auto_fix_preview::AutoFixPreview;
+
+mod export_window;
+pub use export_window::ExportWindow;
+
+mod missing_dependencies_window;
+pub use missing_dependencies_window::MissingDependenciesWindow;
+
+mod missing_mods_window;
+pub use missing_mods_window::MissingModsWindow;
+
+mod mods_config_changed_window;
+pub use mods_config_changed_window::{DiskChoice, ModsConfigChangedWindow};
+
+mod notice_window;
+pub use notice_window::{Notice, NoticeWindow};
+
+mod restore_backup_window;
+pub use restore_backup_window::RestoreBackupWindow;
+
+mod save_status;
+pub use save_status::{SaveState, SaveStatus};
+
 mod status;
 pub use status::{Status, STATUS_HEIGHT};
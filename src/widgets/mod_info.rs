@@ -1,4 +1,8 @@
-use crate::{helpers::fetch_inc_id, widgets::PathLabel, ModMetaData};
+use crate::{
+    helpers::fetch_inc_id,
+    widgets::{PathLabel, UserRulesEditor},
+    ModMetaData, RuleSource,
+};
 use eframe::egui::{widgets::Label, Response, ScrollArea, Ui, Widget};
 use egui_extras::{Column, TableBuilder};
 use std::{
@@ -18,6 +22,7 @@ pub struct ModInfo {
     selected: Rc<RefCell<Option<String>>>,
     last_selected: Option<String>,
     path_lab: Option<PathLabel>,
+    user_rules: UserRulesEditor,
     id: AtomicUsize,
 }
 
@@ -26,24 +31,36 @@ impl ModInfo {
     pub fn new(
         mmd: Arc<Mutex<HashMap<String, ModMetaData>>>,
         selected: Rc<RefCell<Option<String>>>,
+        user_rules: UserRulesEditor,
     ) -> Self {
         Self {
             mmd,
             selected,
             last_selected: None,
             path_lab: None,
+            user_rules,
             id: AtomicUsize::new(fetch_inc_id()),
         }
     }
 
+    /// Checks if the user's load order rules were edited since the last time this was called.
+    pub fn take_rules_changed(&mut self) -> bool {
+        self.user_rules.take_changed()
+    }
+
     fn render(
         ui: &mut Ui,
         selected: &str,
-        mmd: &ModMetaData,
+        mmd: &mut ModMetaData,
         last_selected: &mut Option<String>,
         path_lab: &mut Option<PathLabel>,
+        user_rules: &mut UserRulesEditor,
         id: &AtomicUsize,
     ) -> Response {
+        if last_selected.as_deref() != Some(selected) {
+            user_rules.clear_inputs();
+        }
+
         // get data
         *last_selected = Some(String::from(selected));
         *path_lab = Some(PathLabel::new(mmd.path.clone().unwrap_or_default()));
@@ -64,6 +81,7 @@ impl ModInfo {
             authors.append(&mut auths.clone());
         }
         let authors = authors.join(", ");
+        let rules = load_order_rules(mmd);
 
         // make widgets
         let name_widget = Label::new(format!("Name: {name}"));
@@ -94,6 +112,18 @@ impl ModInfo {
             // path
             ui.add(path_widget);
 
+            // rules
+            ui.collapsing("Load order rules", |ui| {
+                for rule in rules {
+                    ui.label(rule);
+                }
+                ui.separator();
+                ui.label("Your rules:");
+                if let Some(new_rules) = user_rules.show(ui, selected) {
+                    mmd.user_rules = Some(new_rules).filter(|r| !r.is_empty());
+                }
+            });
+
             // desc
             if let Some(description_widget) = description_widget {
                 ui.group(|ui| {
@@ -107,12 +137,55 @@ impl ModInfo {
     }
 }
 
+/// Describes each of the mod's load order rules, and where it came from.
+fn load_order_rules(mmd: &ModMetaData) -> Vec<String> {
+    let mut rules: Vec<String> = mmd
+        .load_after_rules()
+        .map(|(pid, source)| format!("Loads after {pid} ({source})"))
+        .chain(
+            mmd.forceLoadAfter
+                .iter()
+                .flatten()
+                .map(|pid| format!("Loads after {pid} ({}, forced)", RuleSource::About)),
+        )
+        .chain(
+            mmd.load_before_rules()
+                .map(|(pid, source)| format!("Loads before {pid} ({source})")),
+        )
+        .chain(
+            mmd.forceLoadBefore
+                .iter()
+                .flatten()
+                .map(|pid| format!("Loads before {pid} ({}, forced)", RuleSource::About)),
+        )
+        .chain(
+            mmd.incompatible_rules()
+                .map(|(pid, source)| format!("Incompatible with {pid} ({source})")),
+        )
+        .collect();
+    rules.sort();
+
+    let user_rules = mmd.user_rules.as_ref();
+    if mmd.load_top() {
+        rules.push(format!("Loads first ({})", RuleSource::User));
+    }
+    if mmd.load_bottom() {
+        let source = if user_rules.is_some_and(|r| r.load_bottom) {
+            RuleSource::User
+        } else {
+            RuleSource::Community
+        };
+        rules.push(format!("Loads last ({source})"));
+    }
+    rules
+}
+
 impl Widget for &mut ModInfo {
     fn ui(self, ui: &mut Ui) -> Response {
         let sel = self.selected.borrow_mut();
         if let Some(sel) = sel.as_deref() {
-            let map = self.mmd.try_lock();
-            match map.as_ref().map(|map| map.get(sel)) {
+            let mut map = self.mmd.try_lock();
+            match map.as_mut().map(|map| map.get_mut(sel)) {
                 Ok(Some(mmd)) => {
                     return ModInfo::render(
                         ui,
@@ -120,6 +193,7 @@ impl Widget for &mut ModInfo {
                         mmd,
                         &mut self.last_selected,
                         &mut self.path_lab,
+                        &mut self.user_rules,
                         &self.id,
                     )
                 }
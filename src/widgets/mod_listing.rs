@@ -28,12 +28,21 @@ impl<'a> ModListing<'a> {
         mod_meta_data: &Arc<Mutex<HashMap<String, ModMetaData>>>,
         selected: &Rc<RefCell<Option<String>>>,
         title: Option<String>,
+        version: Option<&str>,
         tx: Sender<MultiVecOp<'a, ModListingItem<'a>>>,
     ) -> Self {
         let id = format!("modlisting{}", fetch_inc_id());
         let items = mods
             .into_iter()
-            .map(|m| ModListingItem::new(m, mod_meta_data.clone(), selected.clone(), tx.clone()))
+            .map(|m| {
+                ModListingItem::new(
+                    m,
+                    mod_meta_data.clone(),
+                    selected.clone(),
+                    version,
+                    tx.clone(),
+                )
+            })
             .collect();
 
         Self { id, items, title }
@@ -44,6 +53,7 @@ impl<'a> ModListing<'a> {
         active_pids: Vec<String>,
         mod_list: &ModList,
         selected: &Rc<RefCell<Option<String>>>,
+        version: Option<&str>,
         direct_vecop_tx: &Sender<MultiVecOp<'a, ModListingItem<'a>>>,
     ) -> (Self, Self) {
         let inactive_pids = mod_list
@@ -60,6 +70,7 @@ impl<'a> ModListing<'a> {
             &mod_list.mods,
             selected,
             Some(String::from("Active")),
+            version,
             direct_vecop_tx.clone(),
         );
         let inactive = Self::new(
@@ -67,6 +78,7 @@ impl<'a> ModListing<'a> {
             &mod_list.mods,
             selected,
             Some(String::from("Inactive")),
+            version,
             direct_vecop_tx.clone(),
         );
 
@@ -91,11 +103,20 @@ impl<'a> ModListing<'a> {
         package_ids: Vec<String>,
         mod_meta_data: &Arc<Mutex<HashMap<String, ModMetaData>>>,
         selected: &Rc<RefCell<Option<String>>>,
+        version: Option<&str>,
         tx: &Sender<MultiVecOp<'a, ModListingItem<'a>>>,
     ) -> Self {
         let items = package_ids
             .into_iter()
-            .map(|m| ModListingItem::new(m, mod_meta_data.clone(), selected.clone(), tx.clone()))
+            .map(|m| {
+                ModListingItem::new(
+                    m,
+                    mod_meta_data.clone(),
+                    selected.clone(),
+                    version,
+                    tx.clone(),
+                )
+            })
             .collect();
         self.with_items(items)
     }
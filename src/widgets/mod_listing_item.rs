@@ -1,10 +1,11 @@
 use crate::{
     glyphs,
     helpers::vec_ops::MultiVecOp,
+    major_minor_version,
     traits::{LockIgnorePoisoned, LogIfErr, TableRower},
     ModMetaData,
 };
-use eframe::egui::{Response, SelectableLabel, Ui, Widget};
+use eframe::egui::{Response, RichText, SelectableLabel, Ui, Widget};
 use egui_extras::TableRow;
 use std::{
     cell::RefCell,
@@ -21,21 +22,40 @@ pub struct ModListingItem<'a> {
     pub package_id: String,
     pub mod_meta_data: Option<Arc<Mutex<HashMap<String, ModMetaData>>>>,
     pub selected: Rc<RefCell<Option<String>>>,
+    /// Set if the mod doesn't support the current game version.
+    pub outdated: Option<String>,
     tx: Sender<MultiVecOp<'a, ModListingItem<'a>>>,
 }
 
 impl<'a> ModListingItem<'a> {
+    /// If `version` is given, marks the mod if it doesn't support that game version.
     #[must_use]
     pub fn new(
         package_id: String,
         mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
         selected: Rc<RefCell<Option<String>>>,
+        version: Option<&str>,
         tx: Sender<MultiVecOp<'a, ModListingItem<'a>>>,
     ) -> Self {
+        let outdated = version.and_then(|version| {
+            let mmd = mod_meta_data.lock_ignore_poisoned();
+            let m = mmd.get(&package_id)?;
+            (m.supports_version(version) == Some(false)).then(|| {
+                let mut supported: Vec<_> = m.supportedVersions.iter().flatten().cloned().collect();
+                supported.sort();
+                format!(
+                    "Doesn't support {} (supports {})",
+                    major_minor_version(version),
+                    supported.join(", ")
+                )
+            })
+        });
+
         Self {
             package_id,
             mod_meta_data: Some(mod_meta_data),
             selected,
+            outdated,
             tx,
         }
     }
@@ -82,7 +102,7 @@ impl<'a> ModListingItem<'a> {
     }
 }
 
-impl<'a> Widget for &ModListingItem<'a> {
+impl Widget for &ModListingItem<'_> {
     fn ui(self, ui: &mut Ui) -> Response {
         const BUTTON_WIDTH: f32 = 16.0;
         const ROW_HEIGHT: f32 = 16.0;
@@ -116,8 +136,15 @@ impl TableRower for &ModListingItem<'_> {
 
         row.col(|ui| {
             let mut sel = self.selected.borrow_mut();
-            let is_selected = sel.clone().map_or(false, |pid| self.package_id == pid);
-            let lab = ui.add(SelectableLabel::new(is_selected, self.get_display_name()));
+            let is_selected = sel.clone().is_some_and(|pid| self.package_id == pid);
+            let mut text = RichText::new(self.get_display_name());
+            if self.outdated.is_some() {
+                text = text.color(ui.visuals().warn_fg_color);
+            }
+            let mut lab = ui.add(SelectableLabel::new(is_selected, text));
+            if let Some(outdated) = self.outdated.as_ref() {
+                lab = lab.on_hover_text(outdated);
+            }
 
             if lab.clicked() {
                 *sel = Some(self.package_id.clone().to_lowercase());
@@ -0,0 +1,106 @@
+use crate::{
+    helpers::ui::changes_list, traits::LockIgnorePoisoned, widgets::ModListing, ModMetaData,
+};
+use eframe::egui::{self, Response, Ui, Widget};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// What the user picked in the [`ModsConfigChangedWindow`], with the active mods now on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiskChoice {
+    /// Replace the active mods with the ones on disk.
+    Load(Vec<String>),
+    /// Keep the active mods as they are, to be saved over the ones on disk.
+    Keep(Vec<String>),
+}
+
+/// Window asking what to do when `ModsConfig.xml` was changed by something else (e.g. the game),
+/// so its active mods no longer match the ones in rimrs.
+#[derive(Debug)]
+pub struct ModsConfigChangedWindow<'a> {
+    /// The active mods on disk, while the window is open.
+    on_disk: Option<Vec<String>>,
+    choice: Option<DiskChoice>,
+    active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+    mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+}
+
+impl<'a> ModsConfigChangedWindow<'a> {
+    #[must_use]
+    pub fn new(
+        active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+        mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+    ) -> Self {
+        Self {
+            on_disk: None,
+            choice: None,
+            active_mod_listing_ref,
+            mod_meta_data,
+        }
+    }
+
+    /// Opens the window for the given active mods from disk,
+    /// replacing the ones it was already showing if it was open.
+    pub fn show(&mut self, on_disk: Vec<String>) {
+        self.on_disk = Some(on_disk);
+    }
+
+    /// What the user picked, once they have.
+    pub fn take_choice(&mut self) -> Option<DiskChoice> {
+        self.choice.take()
+    }
+
+    fn display_name(&self, package_id: &str) -> String {
+        self.mod_meta_data
+            .lock_ignore_poisoned()
+            .get(&package_id.to_lowercase())
+            .and_then(|mmd| mmd.name.clone())
+            .unwrap_or_else(|| String::from(package_id))
+    }
+}
+
+impl Widget for &mut ModsConfigChangedWindow<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        if let Some(on_disk) = self.on_disk.take() {
+            let active = Vec::from(&*self.active_mod_listing_ref.borrow());
+            let mut choice = None;
+
+            egui::Window::new("ModsConfig.xml changed")
+                .collapsible(false)
+                .show(ui.ctx(), |ui| {
+                    ui.label(
+                        "ModsConfig.xml was changed outside of rimrs, e.g. by the game. \
+                        Loading it would make these changes to the active mods:",
+                    );
+                    changes_list(ui, "mods_config_diff", &active, &on_disk, |pid| {
+                        self.display_name(pid)
+                    });
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Load from disk").clicked() {
+                            choice = Some(DiskChoice::Load(on_disk.clone()));
+                        }
+                        if ui
+                            .button("Keep current")
+                            .on_hover_text("Keep rimrs's active mods (save to overwrite the file)")
+                            .clicked()
+                        {
+                            choice = Some(DiskChoice::Keep(on_disk.clone()));
+                        }
+                    });
+                });
+
+            if choice.is_some() {
+                self.choice = choice;
+            } else {
+                self.on_disk = Some(on_disk);
+            }
+        }
+
+        ui.scope(|_| {}).response
+    }
+}
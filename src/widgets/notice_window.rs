@@ -0,0 +1,58 @@
+use eframe::egui::{self, Response, ScrollArea, Ui, Widget};
+use std::{cell::RefCell, rc::Rc};
+
+/// Something to tell the user about that needs more room than a hint, e.g. why sorting failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notice {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+impl Notice {
+    #[must_use]
+    pub fn new<S: Into<String>>(title: S, lines: Vec<String>) -> Self {
+        Self {
+            title: title.into(),
+            lines,
+        }
+    }
+}
+
+/// Window showing the [`Notice`] in `pending`, until it's closed.
+#[derive(Debug)]
+pub struct NoticeWindow {
+    pending: Rc<RefCell<Option<Notice>>>,
+}
+
+impl NoticeWindow {
+    #[must_use]
+    pub fn new(pending: Rc<RefCell<Option<Notice>>>) -> Self {
+        Self { pending }
+    }
+}
+
+impl Widget for &mut NoticeWindow {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let mut close = false;
+
+        if let Some(notice) = self.pending.borrow().as_ref() {
+            egui::Window::new(notice.title.as_str())
+                .collapsible(false)
+                .show(ui.ctx(), |ui| {
+                    ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for line in &notice.lines {
+                            ui.label(line);
+                        }
+                    });
+                    ui.separator();
+                    close = ui.button("Close").clicked();
+                });
+        }
+
+        if close {
+            *self.pending.borrow_mut() = None;
+        }
+
+        ui.scope(|_| {}).response
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    backups::{Backup, Backups},
+    helpers::{format_timestamp, ui::changes_list},
+    traits::{LockIgnorePoisoned, LogIfErr},
+    widgets::ModListing,
+    ModMetaData,
+};
+use eframe::egui::{self, Response, ScrollArea, Ui, Widget};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    sync::{mpsc::Sender, Arc, Mutex},
+};
+
+/// A backup, along with its active mods or why they couldn't be read.
+type Entry = (Backup, Result<Vec<String>, String>);
+
+/// Window listing the backups of `ModsConfig.xml`, opened by the "Restore backup" button.
+/// Shows how each differs from the current active mods, and can replace them with it.
+#[derive(Debug)]
+pub struct RestoreBackupWindow<'a> {
+    open: Rc<Cell<bool>>,
+    backups: Option<Backups>,
+    /// Read when the window is opened.
+    entries: Option<Vec<Entry>>,
+    selected: Option<usize>,
+    active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+    mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+    change_mod_list_tx: Sender<Vec<String>>,
+}
+
+impl<'a> RestoreBackupWindow<'a> {
+    #[must_use]
+    pub fn new(
+        open: Rc<Cell<bool>>,
+        backups: Option<Backups>,
+        active_mod_listing_ref: Rc<RefCell<ModListing<'a>>>,
+        mod_meta_data: Arc<Mutex<HashMap<String, ModMetaData>>>,
+        change_mod_list_tx: Sender<Vec<String>>,
+    ) -> Self {
+        Self {
+            open,
+            backups,
+            entries: None,
+            selected: None,
+            active_mod_listing_ref,
+            mod_meta_data,
+            change_mod_list_tx,
+        }
+    }
+
+    fn read_entries(&self) -> Vec<Entry> {
+        let Some(backups) = self.backups.as_ref() else {
+            return Vec::new();
+        };
+        backups
+            .list()
+            .log_if_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|backup| {
+                let mods = backup
+                    .read()
+                    .map(|mods_config| mods_config.activeMods)
+                    .map_err(|err| err.to_string());
+                (backup, mods)
+            })
+            .collect()
+    }
+
+    fn display_name(&self, package_id: &str) -> String {
+        self.mod_meta_data
+            .lock_ignore_poisoned()
+            .get(&package_id.to_lowercase())
+            .and_then(|mmd| mmd.name.clone())
+            .unwrap_or_else(|| String::from(package_id))
+    }
+
+    fn active_mods(&self) -> Vec<String> {
+        self.active_mod_listing_ref
+            .borrow()
+            .items
+            .iter()
+            .map(|item| item.package_id.clone())
+            .collect()
+    }
+
+    /// Shows the window's contents, returning the mods to restore if the user picked a backup.
+    fn contents(&mut self, ui: &mut Ui, entries: &[Entry]) -> Option<Vec<String>> {
+        let mut restore = None;
+
+        if entries.is_empty() {
+            ui.label("There aren't any backups yet. One is made each time the mod list is saved.");
+            return None;
+        }
+
+        ScrollArea::vertical()
+            .id_source("backups")
+            .max_height(150.0)
+            .show(ui, |ui| {
+                for (i, (backup, mods)) in entries.iter().enumerate() {
+                    let text = match mods {
+                        Ok(mods) => format!(
+                            "{} ({} mods)",
+                            format_timestamp(backup.timestamp / 1000),
+                            mods.len()
+                        ),
+                        Err(_) => {
+                            format!("{} (unreadable)", format_timestamp(backup.timestamp / 1000))
+                        }
+                    };
+                    if ui
+                        .selectable_label(self.selected == Some(i), text)
+                        .clicked()
+                    {
+                        self.selected = Some(i);
+                    }
+                }
+            });
+
+        if let Some((_, mods)) = self.selected.and_then(|i| entries.get(i)) {
+            ui.separator();
+            match mods {
+                Ok(mods) => {
+                    ui.heading("Changes to the active mods");
+                    changes_list(ui, "backup_diff", &self.active_mods(), mods, |pid| {
+                        self.display_name(pid)
+                    });
+                    if ui
+                        .button("Restore")
+                        .on_hover_text("Replace the active mods with these (save to apply)")
+                        .clicked()
+                    {
+                        restore = Some(mods.clone());
+                    }
+                }
+                Err(err) => {
+                    ui.label(format!("Couldn't read this backup: {err}"));
+                }
+            }
+        }
+
+        restore
+    }
+}
+
+impl Widget for &mut RestoreBackupWindow<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        if self.open.get() {
+            let entries = self.entries.take().unwrap_or_else(|| self.read_entries());
+            let mut open = true;
+            let mut restore = None;
+
+            egui::Window::new("Restore backup")
+                .collapsible(false)
+                .open(&mut open)
+                .show(ui.ctx(), |ui| restore = self.contents(ui, &entries));
+
+            if let Some(mods) = restore {
+                self.change_mod_list_tx.send(mods).log_if_err();
+                open = false;
+            }
+            if open {
+                self.entries = Some(entries);
+            } else {
+                self.open.set(false);
+                self.selected = None;
+            }
+        }
+
+        ui.scope(|_| {}).response
+    }
+}
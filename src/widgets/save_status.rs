@@ -0,0 +1,95 @@
+use crate::{
+    writer_thread::{self, Message, PendingWrite},
+    CHANGED_ACTIVE_MODS,
+};
+use std::sync::mpsc::SyncSender;
+
+/// How the last save went, shown on the Save button.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SaveState {
+    #[default]
+    Idle,
+    Pending,
+    Saved,
+    Failed(String),
+}
+
+/// Keeps track of the save request sent to the writer thread by the Save button.
+#[derive(Debug, Default)]
+pub struct SaveStatus {
+    state: SaveState,
+    /// The request, and the active mods it's saving.
+    pending: Option<(PendingWrite, Vec<String>)>,
+    /// The active mods from the last save that went through, until they're taken.
+    saved: Option<Vec<String>>,
+}
+
+impl SaveStatus {
+    #[must_use]
+    pub fn state(&self) -> &SaveState {
+        &self.state
+    }
+
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Asks the writer thread to save the given active mods.
+    pub fn save(&mut self, writer_thread_tx: &SyncSender<Message>, active_mods: Vec<String>) {
+        let sent = writer_thread_tx
+            .try_send(Message::SetActiveMods(active_mods.clone()))
+            .and_then(|()| writer_thread::request(writer_thread_tx, Message::Save));
+        match sent {
+            Ok(pending) => {
+                self.state = SaveState::Pending;
+                self.pending = Some((pending, active_mods));
+            }
+            Err(err) => {
+                log::error!("{err}");
+                self.state = SaveState::Failed(err.to_string());
+            }
+        }
+    }
+
+    /// Checks whether the writer thread has replied to the save.
+    /// Once it's confirmed, [`CHANGED_ACTIVE_MODS`] is cleared,
+    /// unless the active mods have changed again since they were sent.
+    pub fn poll(&mut self, active_mods: &[String]) {
+        let Some((pending, saved)) = self.pending.as_ref() else {
+            return;
+        };
+        let Some(res) = pending.poll() else {
+            return;
+        };
+
+        self.state = match res {
+            Ok(_) => {
+                if saved == active_mods {
+                    CHANGED_ACTIVE_MODS.reset();
+                }
+                self.saved = Some(saved.clone());
+                SaveState::Saved
+            }
+            Err(err) => SaveState::Failed(err.to_string()),
+        };
+        self.pending = None;
+    }
+
+    /// The active mods that were last saved, if they haven't been taken since.
+    pub fn take_saved(&mut self) -> Option<Vec<String>> {
+        self.saved.take()
+    }
+
+    /// The text for the Save button.
+    #[must_use]
+    pub fn label(&self) -> String {
+        let label = match &self.state {
+            SaveState::Pending => "Saving...",
+            SaveState::Failed(_) => "Save failed, retry",
+            SaveState::Saved if !CHANGED_ACTIVE_MODS.check() => "Saved",
+            SaveState::Idle | SaveState::Saved => "Save",
+        };
+        String::from(label)
+    }
+}
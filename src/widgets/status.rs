@@ -1,9 +1,16 @@
-use crate::{mods::ModListValidationResult, validate, widgets::ModListing, ModMetaData};
-use eframe::egui::{Response, Ui, Widget};
+use crate::{
+    mods::{Diagnostic, ModListValidationResult},
+    traits::LockIgnorePoisoned,
+    validate_for_version,
+    widgets::{MissingDependenciesWindow, ModListing},
+    ModMetaData,
+};
+use eframe::egui::{Label, Response, Sense, Ui, Widget};
 use egui_extras::{Column, TableBuilder};
 use std::{
     cell::RefCell,
     collections::HashMap,
+    path::PathBuf,
     rc::Rc,
     sync::{Arc, Mutex},
 };
@@ -14,7 +21,9 @@ pub const STATUS_HEIGHT: f32 = 16.0;
 pub struct Status<'a> {
     active_mods: Rc<RefCell<ModListing<'a>>>,
     mmd: Arc<Mutex<HashMap<String, ModMetaData>>>,
+    version: Option<String>,
     latest: Option<ModListValidationResult>,
+    missing_dependencies: MissingDependenciesWindow,
     should_update: *mut bool,
 }
 
@@ -23,12 +32,16 @@ impl<'a> Status<'a> {
     pub fn new(
         active_mods: Rc<RefCell<ModListing<'a>>>,
         mmd: Arc<Mutex<HashMap<String, ModMetaData>>>,
+        version: Option<String>,
+        steamcmd: Option<PathBuf>,
         should_update: *mut bool,
     ) -> Self {
         Self {
             active_mods,
             mmd,
+            version,
             latest: None,
+            missing_dependencies: MissingDependenciesWindow::new(steamcmd),
             should_update,
         }
     }
@@ -41,24 +54,56 @@ impl<'a> Status<'a> {
             .iter()
             .map(|item| item.package_id.clone())
             .collect();
-        self.latest = Some(validate(&self.mmd, &mods));
+        let latest = validate_for_version(&self.mmd, &mods, self.version.as_deref());
+        self.missing_dependencies
+            .set_missing(latest.missing_dependencies(&self.mmd.lock_ignore_poisoned()));
+        self.latest = Some(latest);
     }
 
-    fn display(ui: &mut Ui, res: &ModListValidationResult) {
-        let w = ui.available_width() / 2.0;
+    /// Shows the counts of each kind of diagnostic.
+    /// Returns whether the count of missing dependencies was clicked.
+    fn display(ui: &mut Ui, res: &ModListValidationResult, missing_dependencies: usize) -> bool {
+        fn label(ui: &mut Ui, name: &str, diagnostics: &[&Diagnostic]) {
+            let lab = ui.label(format!("{name}: {}", diagnostics.len()));
+            if !diagnostics.is_empty() {
+                let messages: Vec<_> = diagnostics.iter().map(ToString::to_string).collect();
+                lab.on_hover_text(messages.join("\n"));
+            }
+        }
+
+        let warnings: Vec<_> = res.warnings().into_iter().flatten().collect();
+        let errors: Vec<_> = res.errors().into_iter().flatten().collect();
+        let outdated: Vec<_> = res
+            .diagnostics()
+            .filter(|d| matches!(d, Diagnostic::UnsupportedVersion { .. }))
+            .collect();
+
+        let mut clicked = false;
+        let w = ui.available_width() / 4.0;
         TableBuilder::new(ui)
+            .column(Column::exact(w))
+            .column(Column::exact(w))
             .column(Column::exact(w))
             .column(Column::remainder())
             .body(|mut body| {
                 body.row(STATUS_HEIGHT, |mut row| {
+                    row.col(|ui| label(ui, "Warnings", &warnings));
+                    row.col(|ui| label(ui, "Errors", &errors));
+                    row.col(|ui| label(ui, "Outdated", &outdated));
                     row.col(|ui| {
-                        ui.label(format!("Warnings: {}", res.warnings().map_or(0, Vec::len)));
-                    });
-                    row.col(|ui| {
-                        ui.label(format!("Errors: {}", res.errors().map_or(0, Vec::len)));
+                        let text = format!("Not installed: {missing_dependencies}");
+                        if missing_dependencies == 0 {
+                            ui.label(text);
+                        } else {
+                            clicked = ui
+                                .add(Label::new(text).sense(Sense::click()))
+                                .on_hover_text("Dependencies of active mods that aren't installed, click for links")
+                                .clicked();
+                        }
                     });
                 });
             });
+        clicked
     }
 }
 
@@ -74,8 +119,12 @@ impl Widget for &mut Status<'_> {
 
         ui.scope(|ui| {
             if let Some(res) = self.latest.as_ref() {
-                Status::display(ui, res);
+                let missing = self.missing_dependencies.missing().len();
+                if Status::display(ui, res, missing) {
+                    self.missing_dependencies.open = true;
+                }
             }
+            ui.add(&mut self.missing_dependencies);
         })
         .response
     }
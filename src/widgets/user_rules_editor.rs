@@ -0,0 +1,200 @@
+use crate::{
+    serialization::user_rules::UserRules,
+    traits::{LockIgnorePoisoned, LogIfErr},
+    LoadRules, ModMetaData,
+};
+use eframe::egui::{Key, Ui};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    rc::Rc,
+    sync::Mutex,
+};
+
+/// The user's load order rules, shared between the [`UserRulesEditor`] and the "Import list" button.
+/// Saved to `path` whenever they change.
+#[derive(Debug, Clone, Default)]
+pub struct SharedUserRules {
+    rules: Rc<RefCell<UserRules>>,
+    path: Option<PathBuf>,
+    changed: Rc<Cell<bool>>,
+}
+
+impl SharedUserRules {
+    #[must_use]
+    pub fn new(rules: UserRules, path: Option<PathBuf>) -> Self {
+        Self {
+            rules: Rc::new(RefCell::new(rules)),
+            path,
+            changed: Rc::default(),
+        }
+    }
+
+    /// Gets a copy of the rules for the given mod.
+    #[must_use]
+    pub fn get(&self, package_id: &str) -> Option<LoadRules> {
+        self.rules.borrow().get(package_id).cloned()
+    }
+
+    /// Replaces the rules for the given mod, then saves them.
+    pub fn set(&self, package_id: &str, rules: LoadRules) {
+        self.rules.borrow_mut().set(package_id, rules);
+        self.save();
+    }
+
+    /// Adds the imported rules to the user's rules (see [`UserRules::merge`]), saves them,
+    /// and updates the affected mods in `mod_meta_data`.
+    pub fn merge(&self, imported: UserRules, mod_meta_data: &Mutex<HashMap<String, ModMetaData>>) {
+        let package_ids: Vec<String> = imported.rules.keys().cloned().collect();
+        let mut rules = self.rules.borrow_mut();
+        rules.merge(imported);
+
+        let mut mmd = mod_meta_data.lock_ignore_poisoned();
+        for package_id in package_ids {
+            if let Some(mmd) = mmd.get_mut(&package_id) {
+                mmd.user_rules = rules.get(&package_id).cloned();
+            }
+        }
+        drop(rules);
+        self.save();
+    }
+
+    /// Checks if the rules changed since the last time this was called.
+    #[must_use]
+    pub fn take_changed(&self) -> bool {
+        self.changed.take()
+    }
+
+    fn save(&self) {
+        if let Some(path) = self.path.as_ref() {
+            self.rules.borrow().save_to(path).log_if_err();
+        }
+        self.changed.set(true);
+    }
+}
+
+/// Edits the user's own load order rules for a mod, saving them whenever they change.
+/// Shown in [`crate::widgets::ModInfo`].
+#[derive(Debug)]
+pub struct UserRulesEditor {
+    rules: SharedUserRules,
+    new_load_after: String,
+    new_load_before: String,
+    new_incompatible: String,
+}
+
+impl UserRulesEditor {
+    #[must_use]
+    pub fn new(rules: SharedUserRules) -> Self {
+        Self {
+            rules,
+            new_load_after: String::new(),
+            new_load_before: String::new(),
+            new_incompatible: String::new(),
+        }
+    }
+
+    /// Clears the text fields, for when a different mod gets selected.
+    pub fn clear_inputs(&mut self) {
+        self.new_load_after.clear();
+        self.new_load_before.clear();
+        self.new_incompatible.clear();
+    }
+
+    /// Checks if the rules changed since the last time this was called.
+    #[must_use]
+    pub fn take_changed(&self) -> bool {
+        self.rules.take_changed()
+    }
+
+    /// Shows the rules for the given mod.
+    /// Returns the new rules if they were changed, after saving them.
+    pub fn show(&mut self, ui: &mut Ui, package_id: &str) -> Option<LoadRules> {
+        let mut rules = self.rules.get(package_id).unwrap_or_default();
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(&mut rules.load_top, "Always load first")
+                .changed()
+            {
+                rules.load_bottom &= !rules.load_top;
+                changed = true;
+            }
+            if ui
+                .checkbox(&mut rules.load_bottom, "Always load last")
+                .changed()
+            {
+                rules.load_top &= !rules.load_bottom;
+                changed = true;
+            }
+        });
+
+        for (label, package_ids, input) in [
+            (
+                "Load after",
+                &mut rules.load_after,
+                &mut self.new_load_after,
+            ),
+            (
+                "Load before",
+                &mut rules.load_before,
+                &mut self.new_load_before,
+            ),
+            (
+                "Incompatible with",
+                &mut rules.incompatible_with,
+                &mut self.new_incompatible,
+            ),
+        ] {
+            changed |= package_id_list(ui, label, package_id, package_ids, input);
+        }
+
+        if !changed {
+            return None;
+        }
+
+        self.rules.set(package_id, rules.clone());
+        Some(rules)
+    }
+}
+
+/// Shows a list of package ids with buttons to remove them, and a text field to add more.
+/// Returns whether the list was changed.
+fn package_id_list(
+    ui: &mut Ui,
+    label: &str,
+    package_id: &str,
+    package_ids: &mut HashSet<String>,
+    input: &mut String,
+) -> bool {
+    let mut changed = false;
+
+    ui.label(label);
+    let mut sorted: Vec<String> = package_ids.iter().cloned().collect();
+    sorted.sort();
+    for pid in sorted {
+        ui.horizontal(|ui| {
+            if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                package_ids.remove(&pid);
+                changed = true;
+            }
+            ui.label(&pid);
+        });
+    }
+
+    ui.horizontal(|ui| {
+        let text_edit = ui.text_edit_singleline(input);
+        let entered = text_edit.lost_focus() && ui.input().key_pressed(Key::Enter);
+        if ui.button("Add").clicked() || entered {
+            let new = input.trim().to_lowercase();
+            if !new.is_empty() && new != package_id {
+                changed |= package_ids.insert(new);
+            }
+            input.clear();
+        }
+    });
+
+    changed
+}
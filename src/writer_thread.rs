@@ -1,24 +1,86 @@
-use crate::{traits::LogIfErr, ModsConfig, CHANGED_ACTIVE_MODS};
+use crate::{backups::Backups, traits::LogIfErr, ModsConfig, ModsConfigFile};
 use std::{
-    fs::OpenOptions,
-    io::Write,
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, Write},
     path::{Path, PathBuf},
     sync::{
-        mpsc::{Receiver, SyncSender},
+        mpsc::{channel, Receiver, Sender, SyncSender, TryRecvError, TrySendError},
         Arc,
     },
     thread::{self, JoinHandle},
 };
+use thiserror::Error;
+
+/// What the writer thread replies to a write request with: the file it wrote to, or why it couldn't.
+pub type WriteResult = Result<PathBuf, WriteError>;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Stop,
-    Save,
+    /// Writes the mods config to the destination, replying with how it went.
+    Save(Sender<WriteResult>),
     SetDestination(PathBuf),
-    SetModsConfig(Arc<ModsConfig>),
+    /// The mods config to save, along with the file it was read from so the rest of it is kept.
+    SetModsConfig(Arc<ModsConfigFile>),
+    /// Where to back up `ModsConfig.xml` to before each [`Message::Save`].
+    SetBackups(Backups),
     SetActiveMods(Vec<String>),
     SetHintTx(SyncSender<String>),
-    WriteTo(PathBuf, Vec<String>),
+    WriteTo(PathBuf, Vec<String>, Sender<WriteResult>),
+    /// Writes already serialized contents to the file, e.g. from [`crate::serialization::export`].
+    WriteFile(PathBuf, String, Sender<WriteResult>),
+}
+
+#[derive(Debug, Error)]
+pub enum WriteError {
+    #[error("nowhere to save ModsConfig.xml to")]
+    NoDestination,
+    #[error("ModsConfig.xml hasn't been loaded")]
+    NoModsConfig,
+    #[error("couldn't write to {}: {1}", .0.display())]
+    IOError(PathBuf, #[source] io::Error),
+    #[error("the writer thread isn't running")]
+    Disconnected,
+}
+
+/// A write request that's been sent to the writer thread, see [`request`].
+#[derive(Debug)]
+pub struct PendingWrite(Receiver<WriteResult>);
+
+impl PendingWrite {
+    /// Checks for the writer thread's reply without blocking.
+    /// Returns [`None`] if it hasn't replied yet.
+    #[must_use]
+    pub fn poll(&self) -> Option<WriteResult> {
+        match self.0.try_recv() {
+            Ok(res) => Some(res),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(WriteError::Disconnected)),
+        }
+    }
+
+    /// Blocks until the writer thread replies.
+    ///
+    /// # Errors
+    /// If the write failed, or the writer thread stopped before replying.
+    pub fn wait(self) -> WriteResult {
+        self.0.recv().unwrap_or(Err(WriteError::Disconnected))
+    }
+}
+
+/// Sends a write request made by `message` (e.g. `Message::Save`) to the writer thread,
+/// giving it a channel to reply on.
+///
+/// # Errors
+/// If the channel to the writer thread is full or disconnected.
+pub fn request(
+    writer_thread_tx: &SyncSender<Message>,
+    message: impl FnOnce(Sender<WriteResult>) -> Message,
+) -> Result<PendingWrite, TrySendError<Message>> {
+    let (reply_tx, reply_rx) = channel();
+    writer_thread_tx.try_send(message(reply_tx))?;
+    Ok(PendingWrite(reply_rx))
 }
 
 #[must_use]
@@ -29,71 +91,138 @@ pub fn spawn(rx: Receiver<Message>) -> JoinHandle<()> {
 #[allow(clippy::needless_pass_by_value)] // i want this thread to have full control of the receiver
 fn writer_thread_fn(rx: Receiver<Message>) {
     let mut destination: Option<PathBuf> = None;
-    let mut mods_config: Option<ModsConfig> = None;
+    let mut mods_config: Option<ModsConfigFile> = None;
     let mut hint_tx: Option<SyncSender<String>> = None;
+    let mut backups: Option<Backups> = None;
 
-    loop {
-        match rx.recv() {
-            Ok(Message::Save) => write_to(
-                destination.as_ref().unwrap(),
-                mods_config.as_ref().unwrap(),
-                &hint_tx,
-                true,
-            ),
-            Ok(Message::SetDestination(new_dest)) => destination = Some(new_dest),
-            Ok(Message::SetModsConfig(new_mods_config)) => {
+    // stops once every sender has been dropped, as nothing else could be sent
+    while let Ok(msg) = rx.recv() {
+        match msg {
+            Message::Save(reply_tx) => {
+                let res = save(
+                    destination.as_deref(),
+                    mods_config.as_ref(),
+                    backups.as_ref(),
+                    hint_tx.as_ref(),
+                );
+                reply(&reply_tx, res);
+            }
+            Message::SetDestination(new_dest) => destination = Some(new_dest),
+            Message::SetModsConfig(new_mods_config) => {
                 mods_config = Some((*new_mods_config).clone());
             }
-            Ok(Message::SetActiveMods(new_mods)) => set_active_mods(&mut mods_config, new_mods),
-            Ok(Message::SetHintTx(new_hint_tx)) => hint_tx = Some(new_hint_tx),
-            Ok(Message::WriteTo(path, mods)) => {
+            Message::SetBackups(new_backups) => backups = Some(new_backups),
+            Message::SetActiveMods(new_mods) => set_active_mods(&mut mods_config, new_mods),
+            Message::SetHintTx(new_hint_tx) => hint_tx = Some(new_hint_tx),
+            Message::WriteTo(path, mods, reply_tx) => {
                 let mut mods_config = mods_config.clone();
                 set_active_mods(&mut mods_config, mods);
-                write_to(path, mods_config.as_ref().unwrap(), &hint_tx, false);
+                let res = match mods_config.as_ref() {
+                    Some(mods_config) => write_to(path, &Vec::from(mods_config), hint_tx.as_ref()),
+                    None => Err(WriteError::NoModsConfig),
+                };
+                reply(&reply_tx, res);
             }
-            Ok(Message::Stop) => break,
-            Err(err) => panic!("{err}"),
+            Message::WriteFile(path, contents, reply_tx) => {
+                let res = write_to(path, contents.as_bytes(), hint_tx.as_ref());
+                reply(&reply_tx, res);
+            }
+            Message::Stop => break,
         }
     }
 }
 
-fn set_active_mods(mods_config: &mut Option<ModsConfig>, new_mods: Vec<String>) {
-    if let Some(mc) = mods_config.as_mut() {
-        mc.activeMods = new_mods;
+fn save(
+    destination: Option<&Path>,
+    mods_config: Option<&ModsConfigFile>,
+    backups: Option<&Backups>,
+    hint_tx: Option<&SyncSender<String>>,
+) -> WriteResult {
+    let destination = destination.ok_or(WriteError::NoDestination)?;
+    let mods_config = mods_config.ok_or(WriteError::NoModsConfig)?;
+    if let Some(backups) = backups {
+        backups.back_up(destination).log_if_err();
+    }
+    write_to(destination, &Vec::from(mods_config), hint_tx)
+}
+
+/// Sends the result of a request back, logging it if it failed.
+fn reply(reply_tx: &Sender<WriteResult>, res: WriteResult) {
+    if let Err(err) = res.as_ref() {
+        log::error!("{err}");
+    }
+    // the requester doesn't have to wait for the reply
+    reply_tx.send(res).ok();
+}
+
+fn set_active_mods(mods_config: &mut Option<ModsConfigFile>, new_mods: Vec<String>) {
+    if let Some(file) = mods_config.as_mut() {
+        file.mods_config.activeMods = new_mods;
     }
 }
 
 fn write_to<P: AsRef<Path>>(
     destination: P,
-    mods_config: &ModsConfig,
-    hint_tx: &Option<SyncSender<String>>,
-    reset_flag: bool,
-) {
-    let file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(destination.as_ref());
-
-    match file.and_then(|mut f| f.write_all(&Vec::from(mods_config))) {
-        Ok(_) => {
-            if reset_flag {
-                CHANGED_ACTIVE_MODS.reset();
-            }
-            log::info!("Wrote to {:?}", destination.as_ref());
-            if let Some(hint_tx) = hint_tx.as_ref() {
-                hint_tx
-                    .try_send(format!("Wrote to {:?}", destination.as_ref()))
-                    .log_if_err();
-            }
-        }
-        Err(err) => {
-            log::error!("{err}");
-            if let Some(hint_tx) = hint_tx.as_ref() {
-                hint_tx
-                    .try_send(format!("Couldn't write to {:?}", destination.as_ref()))
-                    .log_if_err();
-            }
+    contents: &[u8],
+    hint_tx: Option<&SyncSender<String>>,
+) -> WriteResult {
+    let destination = destination.as_ref();
+    let res = write_file(destination, contents);
+
+    if let Some(hint_tx) = hint_tx {
+        let hint = match res {
+            Ok(()) => format!("Wrote to {}", destination.display()),
+            Err(_) => format!("Couldn't write to {}", destination.display()),
+        };
+        hint_tx.try_send(hint).log_if_err();
+    }
+
+    match res {
+        Ok(()) => {
+            log::info!("Wrote to {}", destination.display());
+            Ok(destination.to_path_buf())
         }
+        Err(err) => Err(WriteError::IOError(destination.to_path_buf(), err)),
+    }
+}
+
+/// Serializes `mods_config` and writes it to `destination`.
+///
+/// # Errors
+/// If it can't open or write to the file.
+pub fn write_mods_config<P: AsRef<Path>>(
+    destination: P,
+    mods_config: &ModsConfig,
+) -> Result<(), std::io::Error> {
+    write_file(destination, &Vec::from(mods_config))
+}
+
+/// Like [`write_mods_config`], but keeps anything else that was in the file it was read from.
+///
+/// # Errors
+/// If it can't open or write to the file.
+pub fn write_mods_config_file<P: AsRef<Path>>(
+    destination: P,
+    file: &ModsConfigFile,
+) -> Result<(), std::io::Error> {
+    write_file(destination, &Vec::from(file))
+}
+
+/// Writes to a temporary file next to `destination`, then renames it over `destination`,
+/// so a crash or full disk can't leave it half-written.
+fn write_file<P: AsRef<Path>>(destination: P, contents: &[u8]) -> Result<(), std::io::Error> {
+    let destination = destination.as_ref();
+    let mut temp_name = destination
+        .file_name()
+        .map_or_else(OsString::new, ToOwned::to_owned);
+    temp_name.push(".rimrs-tmp");
+    let temp_path = destination.with_file_name(temp_name);
+
+    let res = File::create(&temp_path)
+        .and_then(|mut f| f.write_all(contents).and_then(|()| f.sync_all()))
+        .and_then(|()| fs::rename(&temp_path, destination));
+    if res.is_err() {
+        fs::remove_file(&temp_path).ok();
     }
+    res
 }
@@ -0,0 +1,160 @@
+use rimrs::{auto_fix, helpers::diff::Change, validate, Dependency, ModMetaData, SuggestedFix};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+fn ids(mods: &[&str]) -> Vec<String> {
+    mods.iter().map(|m| String::from(*m)).collect()
+}
+
+/// `a` depends on `b`, `c` loads after `d`, `e` is incompatible with `f`. `g` is installed but unrelated.
+fn generate_mod_meta_data() -> Arc<Mutex<HashMap<String, ModMetaData>>> {
+    let mut mmd = HashMap::new();
+
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            modDependencies: Some(HashSet::from([Dependency {
+                packageId: Some(String::from("b")),
+                ..Default::default()
+            }])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(String::from("b"), ModMetaData::default());
+    mmd.insert(
+        String::from("c"),
+        ModMetaData {
+            loadAfter: Some(HashSet::from([String::from("d")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(String::from("d"), ModMetaData::default());
+    mmd.insert(
+        String::from("e"),
+        ModMetaData {
+            incompatibleWith: Some(HashSet::from([String::from("f")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(String::from("f"), ModMetaData::default());
+    mmd.insert(String::from("g"), ModMetaData::default());
+
+    Arc::new(Mutex::new(mmd))
+}
+
+#[test]
+fn nothing_to_fix() {
+    let mmd = generate_mod_meta_data();
+    let fix = auto_fix(&mmd, &ids(&["b", "a", "g"]), None);
+
+    assert!(fix.is_empty());
+    assert!(fix.applied.is_empty());
+    assert!(fix.remaining.is_empty());
+
+    // ids are compared lowercased, so a different case isn't a change
+    let fix = auto_fix(&mmd, &ids(&["B", "a", "G"]), None);
+    assert!(fix.is_empty());
+    assert!(fix
+        .diff()
+        .iter()
+        .all(|change| matches!(change, Change::Kept(_))));
+}
+
+#[test]
+fn activates_missing_dependency() {
+    let mmd = generate_mod_meta_data();
+    let fix = auto_fix(&mmd, &ids(&["g", "a"]), None);
+
+    assert_eq!(fix.after, ids(&["g", "b", "a"]));
+    assert_eq!(
+        fix.applied[0].1,
+        SuggestedFix::Activate {
+            package_id: String::from("b"),
+            before: String::from("a"),
+        }
+    );
+    assert_eq!(
+        fix.diff()
+            .into_iter()
+            .filter(Change::is_change)
+            .collect::<Vec<_>>(),
+        vec![Change::Added(String::from("b"))]
+    );
+    assert!(validate(&mmd, &fix.after).is_ok());
+}
+
+#[test]
+fn doesnt_activate_uninstalled_dependency() {
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            modDependencies: Some(HashSet::from([Dependency {
+                packageId: Some(String::from("z")),
+                ..Default::default()
+            }])),
+            ..Default::default()
+        },
+    );
+    let mmd = Arc::new(Mutex::new(mmd));
+    let fix = auto_fix(&mmd, &ids(&["a"]), None);
+
+    assert!(fix.is_empty());
+    assert_eq!(fix.remaining.len(), 1);
+    assert_eq!(fix.remaining[0].to_string(), "a requires z");
+}
+
+#[test]
+fn fixes_load_order() {
+    let mmd = generate_mod_meta_data();
+    let fix = auto_fix(&mmd, &ids(&["c", "g", "d", "b", "a"]), None);
+
+    assert_eq!(fix.after, ids(&["g", "d", "c", "b", "a"]));
+    assert!(fix.remaining.is_empty());
+}
+
+#[test]
+fn deactivates_incompatible() {
+    let mmd = generate_mod_meta_data();
+    let fix = auto_fix(&mmd, &ids(&["e", "f", "g"]), None);
+
+    assert_eq!(fix.after, ids(&["f", "g"]));
+    assert_eq!(
+        fix.applied[0].1,
+        SuggestedFix::Deactivate(String::from("e"))
+    );
+}
+
+#[test]
+fn removes_duplicates() {
+    let mmd = generate_mod_meta_data();
+    let fix = auto_fix(&mmd, &ids(&["g", "b", "g"]), None);
+
+    assert_eq!(fix.after, ids(&["g", "b"]));
+    assert_eq!(fix.before, ids(&["g", "b", "g"]));
+}
+
+#[test]
+fn conflicting_rules_terminate() {
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            loadAfter: Some(HashSet::from([String::from("b")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("b"),
+        ModMetaData {
+            loadAfter: Some(HashSet::from([String::from("a")])),
+            ..Default::default()
+        },
+    );
+    let mmd = Arc::new(Mutex::new(mmd));
+    let fix = auto_fix(&mmd, &ids(&["a", "b"]), None);
+
+    assert!(!fix.remaining.is_empty());
+}
@@ -0,0 +1,120 @@
+use rimrs::{
+    backups::Backups,
+    writer_thread::{self, write_mods_config, Message},
+    ModsConfig, ModsConfigFile,
+};
+use std::{fs, sync::mpsc::sync_channel, sync::Arc};
+
+const MODS_CONFIG: &str = "<ModsConfigData>
+  <activeMods>
+    <li>ludeon.rimworld</li>
+    <li>brrainz.harmony</li>
+  </activeMods>
+</ModsConfigData>";
+
+#[test]
+fn back_up_and_prune() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("ModsConfig.xml");
+    let backups = Backups::new(dir.path().join("backups"), 3);
+
+    // nothing to back up yet
+    assert_eq!(backups.back_up(&file).unwrap(), None);
+    assert!(backups.list().unwrap().is_empty());
+
+    for i in 0..5 {
+        fs::write(
+            &file,
+            format!("<ModsConfigData><version>{i}</version></ModsConfigData>"),
+        )
+        .unwrap();
+        backups.back_up(&file).unwrap().unwrap();
+    }
+
+    let list = backups.list().unwrap();
+    assert_eq!(list.len(), 3);
+    assert!(list.windows(2).all(|w| w[0].timestamp > w[1].timestamp));
+    let versions: Vec<_> = list
+        .iter()
+        .map(|backup| backup.read().unwrap().version.unwrap())
+        .collect();
+    assert_eq!(versions, ["4", "3", "2"]);
+
+    // saving again without changes doesn't push out older versions
+    assert_eq!(backups.back_up(&file).unwrap(), None);
+    assert_eq!(backups.list().unwrap(), list);
+    fs::write(&file, MODS_CONFIG).unwrap();
+    let newest = backups.back_up(&file).unwrap().unwrap();
+    assert_eq!(backups.list().unwrap()[0], newest);
+
+    // other files in the folder are left alone
+    fs::write(backups.dir().join("notes.txt"), "").unwrap();
+    assert_eq!(backups.list().unwrap().len(), 3);
+}
+
+#[test]
+fn no_backups_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("ModsConfig.xml");
+    fs::write(&file, MODS_CONFIG).unwrap();
+
+    let backups = Backups::new(dir.path().join("backups"), 0);
+    assert_eq!(backups.back_up(&file).unwrap(), None);
+    assert!(!backups.dir().exists());
+}
+
+#[test]
+fn atomic_write() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("ModsConfig.xml");
+    fs::write(
+        &file,
+        "old contents that are longer than the new ones, and then some",
+    )
+    .unwrap();
+
+    let mut mods_config = ModsConfig::try_from(MODS_CONFIG.as_bytes()).unwrap();
+    mods_config.activeMods.pop();
+    write_mods_config(&file, &mods_config).unwrap();
+
+    let written = ModsConfig::try_from(file.as_path()).unwrap();
+    assert_eq!(written.activeMods, ["ludeon.rimworld"]);
+    // only the file itself, no temp file left behind
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    let missing_dir = dir.path().join("missing/ModsConfig.xml");
+    assert!(write_mods_config(&missing_dir, &mods_config).is_err());
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn writer_thread_backs_up_before_saving() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("ModsConfig.xml");
+    fs::write(&file, MODS_CONFIG).unwrap();
+    let backups = Backups::new(dir.path().join("backups"), 5);
+
+    let (tx, rx) = sync_channel(8);
+    let handle = writer_thread::spawn(rx);
+    let mods_config = ModsConfigFile::try_from(file.as_path()).unwrap();
+    for msg in [
+        Message::SetDestination(file.clone()),
+        Message::SetModsConfig(Arc::new(mods_config)),
+        Message::SetBackups(backups.clone()),
+        Message::SetActiveMods(vec![String::from("ludeon.rimworld")]),
+    ] {
+        tx.send(msg).unwrap();
+    }
+    let saved = writer_thread::request(&tx, Message::Save).unwrap().wait();
+    assert_eq!(saved.unwrap(), file);
+    tx.send(Message::Stop).unwrap();
+    handle.join().unwrap();
+
+    let list = backups.list().unwrap();
+    assert_eq!(list.len(), 1);
+    assert_eq!(fs::read_to_string(&list[0].path).unwrap(), MODS_CONFIG);
+    assert_eq!(
+        ModsConfig::try_from(file.as_path()).unwrap().activeMods,
+        ["ludeon.rimworld"]
+    );
+}
@@ -0,0 +1,285 @@
+use rimrs::{
+    backups::{Backups, DEFAULT_BACKUP_COUNT},
+    cli::{run_with_backups, Args, Command, EXIT_ERROR, EXIT_INVALID, EXIT_OK},
+    ModsConfig, SortMode,
+};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tempfile::TempDir;
+
+fn write_mod(dir: &Path, folder: &str, package_id: &str, extra: &str) {
+    let about = dir.join(folder).join("About");
+    fs::create_dir_all(&about).unwrap();
+    fs::write(
+        about.join("About.xml"),
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<ModMetaData>
+    <name>{folder}</name>
+    <packageId>{package_id}</packageId>
+    {extra}
+</ModMetaData>"#
+        ),
+    )
+    .unwrap();
+}
+
+fn write_mods_config(path: &Path, active: &[&str]) {
+    let mods_config = ModsConfig {
+        version: Some(String::from("1.4.3613 rev641")),
+        activeMods: active.iter().map(|s| String::from(*s)).collect(),
+        knownExpansions: vec![String::from("ludeon.rimworld")],
+    };
+    fs::write(path, String::from(&mods_config)).unwrap();
+}
+
+/// Makes a fake install with Core, `test.a` (which depends on `test.b`) and `test.b`,
+/// with Core and `test.a` active.
+fn fake_install() -> TempDir {
+    let root = tempfile::tempdir().unwrap();
+    let data = root.path().join("game/Data");
+    let mods = root.path().join("game/Mods");
+    let config = root.path().join("config");
+    fs::create_dir_all(&config).unwrap();
+
+    write_mod(&data, "Core", "ludeon.rimworld", "");
+    write_mod(
+        &mods,
+        "A",
+        "test.a",
+        "<modDependencies><li><packageId>test.b</packageId></li></modDependencies>",
+    );
+    write_mod(&mods, "B", "test.b", "");
+    write_mods_config(
+        &config.join("ModsConfig.xml"),
+        &["ludeon.rimworld", "test.a"],
+    );
+
+    fs::write(
+        root.path().join("settings.ini"),
+        format!(
+            "[Folders]\nConfig folder = {}\nExpansions = {}\nLocal mods = {}\n",
+            config.display(),
+            data.display(),
+            mods.display()
+        ),
+    )
+    .unwrap();
+
+    root
+}
+
+fn backups(root: &TempDir) -> Backups {
+    Backups::new(root.path().join("backups"), DEFAULT_BACKUP_COUNT)
+}
+
+/// Runs the cli with `--settings` pointing at the fake install, and backups kept in it.
+fn run_in(root: &TempDir, args: &[&str]) -> (i32, Value, String) {
+    let settings = root.path().join("settings.ini");
+    let mut full_args = vec!["--settings", settings.to_str().unwrap()];
+    full_args.extend_from_slice(args);
+
+    let mut out = Vec::new();
+    let mut err = Vec::new();
+    let code = run_with_backups(full_args, Some(backups(root)), &mut out, &mut err);
+    let out = serde_json::from_slice(&out).unwrap_or(Value::Null);
+    (code, out, String::from_utf8(err).unwrap())
+}
+
+fn active_on_disk(root: &TempDir) -> Vec<String> {
+    ModsConfig::try_from(root.path().join("config/ModsConfig.xml").as_path())
+        .unwrap()
+        .activeMods
+}
+
+#[test]
+fn parse_args() {
+    let args = Args::parse(["--dry-run", "activate", "Some.Mod"]).unwrap();
+    assert!(args.dry_run);
+    assert_eq!(args.command, Command::Activate(String::from("some.mod")));
+
+    assert!(Args::parse(Vec::<String>::new()).is_err());
+    assert!(Args::parse(["activate"]).is_err());
+    assert!(Args::parse(["list", "sort"]).is_err());
+    assert!(Args::parse(["frobnicate"]).is_err());
+
+    let args = Args::parse(["sort", "--stable"]).unwrap();
+    assert_eq!(args.command, Command::Sort(SortMode::Stable));
+    assert!(Args::parse(["--stable", "list"]).is_err());
+
+    let args = Args::parse(["import", "list.xml", "--keep-missing"]).unwrap();
+    assert_eq!(
+        args.command,
+        Command::Import {
+            path: PathBuf::from("list.xml"),
+            keep_missing: true
+        }
+    );
+    assert!(Args::parse(["--keep-missing", "sort"]).is_err());
+}
+
+#[test]
+fn list() {
+    let root = fake_install();
+    let (code, out, _) = run_in(&root, &["list"]);
+
+    assert_eq!(code, EXIT_OK);
+    assert_eq!(out["version"], "1.4.3613 rev641");
+    assert_eq!(out["active"][0]["packageId"], "ludeon.rimworld");
+    assert_eq!(out["active"][1]["packageId"], "test.a");
+    assert_eq!(out["active"][1]["name"], "A");
+    assert_eq!(out["inactive"][0]["packageId"], "test.b");
+}
+
+#[test]
+fn validate_errors() {
+    let root = fake_install();
+    let (code, out, _) = run_in(&root, &["validate"]);
+
+    assert_eq!(code, EXIT_INVALID);
+    assert_eq!(out["valid"], false);
+    assert_eq!(out["errors"][0], "test.a requires test.b");
+
+    let diagnostic = &out["diagnostics"][0];
+    assert_eq!(diagnostic["kind"], "missingDependency");
+    assert_eq!(diagnostic["severity"], "error");
+    assert_eq!(diagnostic["packageIds"], json!(["test.a", "test.b"]));
+    assert_eq!(diagnostic["fix"], "activate test.b before test.a");
+}
+
+#[test]
+fn activate_sort_validate() {
+    let root = fake_install();
+
+    let (code, out, _) = run_in(&root, &["activate", "test.b"]);
+    assert_eq!(code, EXIT_OK);
+    assert_eq!(out["written"], true);
+    assert_eq!(
+        active_on_disk(&root),
+        ["ludeon.rimworld", "test.a", "test.b"]
+    );
+
+    let (code, _, _) = run_in(&root, &["sort"]);
+    assert_eq!(code, EXIT_OK);
+    assert_eq!(
+        active_on_disk(&root),
+        ["ludeon.rimworld", "test.b", "test.a"]
+    );
+
+    let (code, out, _) = run_in(&root, &["validate"]);
+    assert_eq!(code, EXIT_OK);
+    assert_eq!(out["valid"], true);
+}
+
+#[test]
+fn deactivate() {
+    let root = fake_install();
+    let (code, _, _) = run_in(&root, &["deactivate", "test.a"]);
+
+    assert_eq!(code, EXIT_OK);
+    assert_eq!(active_on_disk(&root), ["ludeon.rimworld"]);
+}
+
+#[test]
+fn dry_run() {
+    let root = fake_install();
+    let (code, out, _) = run_in(&root, &["--dry-run", "deactivate", "test.a"]);
+
+    assert_eq!(code, EXIT_OK);
+    assert_eq!(out["written"], false);
+    assert_eq!(out["active"][0], "ludeon.rimworld");
+    assert_eq!(active_on_disk(&root), ["ludeon.rimworld", "test.a"]);
+    assert!(backups(&root).list().unwrap().is_empty());
+}
+
+#[test]
+fn backs_up_before_writing() {
+    let root = fake_install();
+    let mods_config = root.path().join("config/ModsConfig.xml");
+    let original = fs::read_to_string(&mods_config).unwrap();
+
+    run_in(&root, &["deactivate", "test.a"]);
+    let list = backups(&root).list().unwrap();
+    assert_eq!(list.len(), 1);
+    assert_eq!(fs::read_to_string(&list[0].path).unwrap(), original);
+
+    // reading doesn't back it up again
+    run_in(&root, &["list"]);
+    run_in(&root, &["validate"]);
+    assert_eq!(backups(&root).list().unwrap(), list);
+
+    run_in(&root, &["activate", "test.b"]);
+    assert_eq!(backups(&root).list().unwrap().len(), 2);
+}
+
+#[test]
+fn activate_unknown() {
+    let root = fake_install();
+    let (code, _, err) = run_in(&root, &["activate", "not.installed"]);
+
+    assert_eq!(code, EXIT_ERROR);
+    let err: Value = serde_json::from_str(&err).unwrap();
+    assert_eq!(err["error"], "mod `not.installed` isn't installed");
+}
+
+#[test]
+fn export_import() {
+    let root = fake_install();
+    let list = root.path().join("list.xml");
+
+    let (code, _, _) = run_in(&root, &["export", list.to_str().unwrap()]);
+    assert_eq!(code, EXIT_OK);
+    run_in(&root, &["deactivate", "test.a"]);
+    assert_eq!(active_on_disk(&root), ["ludeon.rimworld"]);
+
+    let (code, _, _) = run_in(&root, &["import", list.to_str().unwrap()]);
+    assert_eq!(code, EXIT_OK);
+    assert_eq!(active_on_disk(&root), ["ludeon.rimworld", "test.a"]);
+}
+
+#[test]
+fn import_other_formats() {
+    let root = fake_install();
+    let list = root.path().join("Test list.xml");
+    fs::write(
+        &list,
+        "<ModList>
+            <modIds><li>Ludeon.RimWorld</li><li>brrainz.harmony</li><li>Test.B</li></modIds>
+            <modNames><li>Core</li><li>Harmony</li><li>B</li></modNames>
+        </ModList>",
+    )
+    .unwrap();
+
+    let (code, out, _) = run_in(&root, &["import", list.to_str().unwrap()]);
+    assert_eq!(code, EXIT_INVALID);
+    assert_eq!(
+        out["missing"],
+        json!([{ "packageId": "brrainz.harmony", "name": "Harmony" }])
+    );
+    assert_eq!(active_on_disk(&root), ["ludeon.rimworld", "test.b"]);
+
+    let (code, _, _) = run_in(&root, &["import", list.to_str().unwrap(), "--keep-missing"]);
+    assert_eq!(code, EXIT_OK);
+    assert_eq!(
+        active_on_disk(&root),
+        ["ludeon.rimworld", "brrainz.harmony", "test.b"]
+    );
+
+    let rules = root.path().join("rules.json");
+    fs::write(&rules, r#"{"rules": {}}"#).unwrap();
+    let (code, _, err) = run_in(&root, &["import", rules.to_str().unwrap()]);
+    assert_eq!(code, EXIT_ERROR);
+    assert!(err.contains("not a mod list"), "{err}");
+}
+
+#[test]
+fn missing_settings() {
+    let root = tempfile::tempdir().unwrap();
+    let (code, _, err) = run_in(&root, &["list"]);
+
+    assert_eq!(code, EXIT_ERROR);
+    assert!(err.contains("couldn't read settings"));
+}
@@ -0,0 +1,132 @@
+use rimrs::{
+    serialization::community_rules::CommunityRules, sort, LoadRules, ModList, ModMetaData,
+    RuleSource, Settings,
+};
+use std::{collections::HashSet, fs};
+
+const COMMUNITY_RULES_JSON: &str = r#"{
+    "timestamp": 1672531200,
+    "rules": {
+        "Test.A": {
+            "loadAfter": {
+                "test.b": { "name": ["B"], "comment": ["B patches A's defs"] }
+            },
+            "loadBefore": {}
+        },
+        "test.c": {
+            "loadBottom": { "value": true }
+        },
+        "test.d": {
+            "loadBefore": ["test.c"]
+        }
+    }
+}"#;
+
+fn mod_meta_data(pid: &str) -> ModMetaData {
+    ModMetaData {
+        packageId: Some(String::from(pid)),
+        ..Default::default()
+    }
+}
+
+fn ids(mods: &[&str]) -> Vec<String> {
+    mods.iter().map(|m| String::from(*m)).collect()
+}
+
+#[test]
+fn parse() {
+    let rules = CommunityRules::try_from(COMMUNITY_RULES_JSON.as_bytes()).unwrap();
+
+    assert_eq!(rules.timestamp, Some(1_672_531_200));
+    assert_eq!(
+        rules.rules.get("test.a"),
+        Some(&LoadRules {
+            load_after: HashSet::from([String::from("test.b")]),
+            ..Default::default()
+        })
+    );
+    assert!(rules.rules["test.c"].load_bottom);
+    assert!(rules.rules["test.d"].load_before.contains("test.c"));
+}
+
+#[test]
+fn invalid() {
+    assert!(CommunityRules::try_from(&b"{}"[..]).is_err());
+    assert!(CommunityRules::try_from(&b"not json"[..]).is_err());
+    assert!(CommunityRules::try_from(&br#"{"rules": {"a": 1}}"#[..]).is_err());
+}
+
+#[test]
+fn sort_with_community_rules() {
+    let mod_list =
+        ModList::from(["test.a", "test.b", "test.c", "test.d", "test.e"].map(mod_meta_data));
+    let mods = ids(&["test.a", "test.b", "test.c", "test.d", "test.e"]);
+
+    // without the rules it's just alphabetical
+    assert_eq!(sort(&mods, &mod_list.mods).unwrap(), mods);
+
+    let rules = CommunityRules::try_from(COMMUNITY_RULES_JSON.as_bytes()).unwrap();
+    rules.apply(&mod_list);
+    assert_eq!(
+        sort(&mods, &mod_list.mods).unwrap(),
+        ids(&["test.b", "test.a", "test.d", "test.e", "test.c"])
+    );
+}
+
+#[test]
+fn rule_sources() {
+    let mut mmd = ModMetaData {
+        loadAfter: Some(HashSet::from([String::from("test.x")])),
+        ..mod_meta_data("test.a")
+    };
+    mmd.community_rules = Some(LoadRules {
+        load_after: HashSet::from([String::from("test.b")]),
+        ..Default::default()
+    });
+
+    let mut load_after: Vec<_> = mmd.load_after_rules().collect();
+    load_after.sort_by_key(|(pid, _)| *pid);
+    assert_eq!(
+        load_after,
+        vec![
+            (&String::from("test.b"), RuleSource::Community),
+            (&String::from("test.x"), RuleSource::About),
+        ]
+    );
+    assert_eq!(mmd.load_before_rules().count(), 0);
+    assert!(!mmd.load_bottom());
+}
+
+#[test]
+fn from_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    let mods = dir.path().join("mods");
+    let about = mods.join("A").join("About");
+    fs::create_dir_all(&about).unwrap();
+    fs::write(
+        about.join("About.xml"),
+        "<ModMetaData><packageId>test.a</packageId></ModMetaData>",
+    )
+    .unwrap();
+    let rules_path = dir.path().join("rules.json");
+    fs::write(&rules_path, COMMUNITY_RULES_JSON).unwrap();
+
+    let mut settings = Settings::default();
+    settings.folders.local_mods = Some(mods);
+    settings.community_rules = Some(rules_path.clone());
+
+    let settings_path = dir.path().join("settings.ini");
+    settings.save_to(&settings_path).unwrap();
+    let settings = Settings::from_path(&settings_path).unwrap();
+    assert_eq!(settings.community_rules, Some(rules_path));
+
+    let mod_list = ModList::try_from(&settings).unwrap();
+    let mmd = mod_list.mods.lock().unwrap();
+    assert_eq!(
+        mmd["test.a"]
+            .community_rules
+            .as_ref()
+            .map(|r| &r.load_after),
+        Some(&HashSet::from([String::from("test.b")]))
+    );
+}
@@ -0,0 +1,141 @@
+#![cfg(target_os = "linux")]
+
+use rimrs::{
+    helpers::config::{
+        find_game_dir, find_workshop_dir, get_config_dir_from_env, get_game_config_dir_from_env,
+    },
+    serialization::rimpy_config::RimPyConfigFolders,
+};
+use std::{collections::HashMap, fs, path::Path};
+use tempfile::TempDir;
+
+const GAME_CONFIG: &str = ".config/unity3d/Ludeon Studios/RimWorld by Ludeon Studios/Config";
+const STEAM: &str = ".local/share/Steam";
+
+fn env_with_home(home: &Path) -> HashMap<String, String> {
+    HashMap::from([(String::from("HOME"), home.to_string_lossy().into_owned())])
+}
+
+/// Makes a fake home directory with RimWorld installed through steam.
+fn fake_home() -> TempDir {
+    let home = tempfile::tempdir().unwrap();
+    fs::create_dir_all(home.path().join(GAME_CONFIG)).unwrap();
+    fs::create_dir_all(
+        home.path()
+            .join(STEAM)
+            .join("steamapps/workshop/content/294100"),
+    )
+    .unwrap();
+    fs::create_dir_all(
+        home.path()
+            .join(STEAM)
+            .join("steamapps/common/RimWorld/Mods"),
+    )
+    .unwrap();
+    fs::create_dir_all(
+        home.path()
+            .join(STEAM)
+            .join("steamapps/common/RimWorld/Data"),
+    )
+    .unwrap();
+    home
+}
+
+#[test]
+fn config_dirs_from_home() {
+    let env = env_with_home(Path::new("/home/someone"));
+
+    assert_eq!(
+        get_game_config_dir_from_env(&env).unwrap(),
+        Path::new("/home/someone").join(GAME_CONFIG)
+    );
+    assert_eq!(
+        get_config_dir_from_env(&env).unwrap(),
+        Path::new("/home/someone/.config/unity3d/RimPy Mod Manager")
+    );
+}
+
+#[test]
+fn config_dirs_prefer_xdg_config_home() {
+    let mut env = env_with_home(Path::new("/home/someone"));
+    env.insert(String::from("XDG_CONFIG_HOME"), String::from("/xdg"));
+
+    assert_eq!(
+        get_game_config_dir_from_env(&env).unwrap(),
+        Path::new("/xdg/unity3d/Ludeon Studios/RimWorld by Ludeon Studios/Config")
+    );
+}
+
+#[test]
+fn config_dirs_without_env() {
+    let env = HashMap::new();
+    assert!(get_game_config_dir_from_env(&env).is_err());
+    assert!(get_config_dir_from_env(&env).is_err());
+}
+
+#[test]
+fn finds_steam_dirs() {
+    let home = fake_home();
+    let env = env_with_home(home.path());
+
+    assert_eq!(
+        find_workshop_dir(&env).unwrap(),
+        home.path()
+            .join(STEAM)
+            .join("steamapps/workshop/content/294100")
+    );
+    assert_eq!(
+        find_game_dir(&env).unwrap(),
+        home.path().join(STEAM).join("steamapps/common/RimWorld")
+    );
+}
+
+#[test]
+fn finds_steam_dirs_in_xdg_data_home() {
+    let home = tempfile::tempdir().unwrap();
+    let data_home = home.path().join("data");
+    fs::create_dir_all(data_home.join("Steam/steamapps/workshop/content/294100")).unwrap();
+
+    let mut env = env_with_home(home.path());
+    env.insert(
+        String::from("XDG_DATA_HOME"),
+        data_home.to_string_lossy().into_owned(),
+    );
+
+    assert_eq!(
+        find_workshop_dir(&env).unwrap(),
+        data_home.join("Steam/steamapps/workshop/content/294100")
+    );
+    assert!(find_game_dir(&env).is_none());
+}
+
+#[test]
+fn discover_folders() {
+    let home = fake_home();
+    let env = env_with_home(home.path());
+    let game = home.path().join(STEAM).join("steamapps/common/RimWorld");
+
+    let folders = RimPyConfigFolders::discover(&env);
+    assert_eq!(folders.config_folder, Some(home.path().join(GAME_CONFIG)));
+    assert_eq!(folders.game_folder, Some(game.clone()));
+    assert_eq!(folders.local_mods, Some(game.join("Mods")));
+    assert_eq!(folders.expansions, Some(game.join("Data")));
+    assert_eq!(
+        folders.steam_mods,
+        Some(
+            home.path()
+                .join(STEAM)
+                .join("steamapps/workshop/content/294100")
+        )
+    );
+}
+
+#[test]
+fn discover_nothing_installed() {
+    let home = tempfile::tempdir().unwrap();
+    let folders = RimPyConfigFolders::discover(&env_with_home(home.path()));
+
+    assert!(folders.config_folder.is_none());
+    assert!(folders.game_folder.is_none());
+    assert!(folders.steam_mods.is_none());
+}
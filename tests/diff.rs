@@ -0,0 +1,83 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rimrs::helpers::diff::{diff, Change};
+
+#[test]
+fn diff_lists() {
+    assert_eq!(diff::<&str>(&[], &[]), vec![]);
+    assert_eq!(
+        diff(&["a", "b", "c"], &["b", "c", "a"]),
+        vec![
+            Change::Removed("a"),
+            Change::Kept("b"),
+            Change::Kept("c"),
+            Change::Added("a"),
+        ]
+    );
+    assert_eq!(
+        diff(&["a", "b"], &["c"]),
+        vec![
+            Change::Removed("a"),
+            Change::Removed("b"),
+            Change::Added("c")
+        ]
+    );
+}
+
+/// Length of the longest common subsequence, the slow way.
+fn lcs_len(a: &[u8], b: &[u8]) -> usize {
+    let mut row = vec![0; b.len() + 1];
+    for x in a {
+        let mut diagonal = 0;
+        for (j, y) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if x == y {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Checks that the diff turns `before` into `after`, keeping as many items as it can.
+fn check_diff(before: &[u8], after: &[u8]) {
+    let changes = diff(before, after);
+
+    let rebuilt = |keep: fn(&Change<u8>) -> bool| -> Vec<u8> {
+        changes
+            .iter()
+            .filter(|c| keep(c))
+            .map(|c| *c.item())
+            .collect()
+    };
+    assert_eq!(rebuilt(|c| !matches!(c, Change::Added(_))), before);
+    assert_eq!(rebuilt(|c| !matches!(c, Change::Removed(_))), after);
+    assert_eq!(
+        changes.iter().filter(|c| !c.is_change()).count(),
+        lcs_len(before, after),
+        "{before:?} {after:?}"
+    );
+}
+
+#[test]
+fn diff_is_minimal() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..500 {
+        // with duplicates
+        let mut list = || -> Vec<u8> {
+            let len = rng.gen_range(0..30);
+            (0..len).map(|_| rng.gen_range(b'a'..b'g')).collect()
+        };
+        check_diff(&list(), &list());
+
+        // without, like a mod list
+        let mut list = || -> Vec<u8> {
+            let mut items: Vec<u8> = (b'a'..=b'z').filter(|_| rng.gen_bool(0.7)).collect();
+            items.shuffle(&mut rng);
+            items
+        };
+        check_diff(&list(), &list());
+    }
+}
@@ -0,0 +1,144 @@
+use rimrs::{
+    serialization::{
+        export::{export, ExportFormat, ExportedMod},
+        import::{import, Imported, ImportedMod},
+    },
+    ModMetaData,
+};
+use std::{collections::HashMap, path::PathBuf};
+
+fn mods() -> Vec<ExportedMod> {
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("brrainz.harmony"),
+        ModMetaData {
+            name: Some(String::from("Harmony")),
+            packageId: Some(String::from("brrainz.harmony")),
+            path: Some(PathBuf::from("workshop/2009463077/About/About.xml")),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("test.a"),
+        ModMetaData {
+            name: Some(String::from("A & <B> | C")),
+            packageId: Some(String::from("test.a")),
+            url: Some(String::from("https://example.com/a")),
+            path: Some(PathBuf::from("local/A/About/About.xml")),
+            ..Default::default()
+        },
+    );
+
+    ExportedMod::collect(
+        &[
+            String::from("Brrainz.Harmony"),
+            String::from("test.a"),
+            String::from("test.missing"),
+        ],
+        &mmd,
+    )
+}
+
+#[test]
+fn exported_mod() {
+    let mods = mods();
+    assert_eq!(mods[0].name, "Harmony");
+    assert_eq!(mods[0].steam_workshop_id.as_deref(), Some("2009463077"));
+    assert_eq!(
+        mods[0].link().as_deref(),
+        Some("https://steamcommunity.com/sharedfiles/filedetails/?id=2009463077")
+    );
+    assert_eq!(mods[1].steam_workshop_id, None);
+    assert_eq!(mods[1].link().as_deref(), Some("https://example.com/a"));
+    assert_eq!(mods[2].name, "test.missing");
+    assert_eq!(mods[2].link(), None);
+}
+
+#[test]
+fn rimpy() {
+    let out = export(ExportFormat::RimPy, &mods(), Some("1.4.3613 rev641"));
+    assert!(out.contains("<gameVersion>1.4.3613 rev641</gameVersion>"));
+    assert!(out.contains("<li>2009463077</li>"));
+    assert!(out.contains("<li>A &amp; &lt;B&gt; | C</li>"));
+
+    // can be imported again, keeping the names and workshop ids
+    let Imported::ModList(imported) = import(out.as_bytes()).unwrap() else {
+        panic!("expected a mod list");
+    };
+    assert_eq!(
+        imported,
+        vec![
+            ImportedMod {
+                package_id: String::from("Brrainz.Harmony"),
+                name: Some(String::from("Harmony")),
+                steam_workshop_id: Some(String::from("2009463077")),
+            },
+            ImportedMod {
+                package_id: String::from("test.a"),
+                name: Some(String::from("A & <B> | C")),
+                steam_workshop_id: None,
+            },
+            ImportedMod {
+                package_id: String::from("test.missing"),
+                name: Some(String::from("test.missing")),
+                steam_workshop_id: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn text() {
+    assert_eq!(
+        export(ExportFormat::Text, &mods(), None),
+        "Harmony (Brrainz.Harmony) <https://steamcommunity.com/sharedfiles/filedetails/?id=2009463077>\n\
+         A & <B> | C (test.a) <https://example.com/a>\n\
+         test.missing (test.missing)\n"
+    );
+}
+
+#[test]
+fn markdown() {
+    let out = export(ExportFormat::Markdown, &mods(), None);
+    let lines: Vec<_> = out.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0], "| # | Name | Package id | Workshop id |");
+    assert_eq!(
+        lines[2],
+        "| 1 | [Harmony](https://steamcommunity.com/sharedfiles/filedetails/?id=2009463077) | Brrainz.Harmony | [2009463077](https://steamcommunity.com/sharedfiles/filedetails/?id=2009463077) |"
+    );
+    assert_eq!(
+        lines[3],
+        "| 2 | [A \\& \\<B\\> \\| C](https://example.com/a) | test.a |  |"
+    );
+    assert_eq!(lines[4], "| 3 | test.missing | test.missing |  |");
+
+    let special = ExportedMod {
+        name: String::from("[1.4] *Better* `mod_name` \\ ~beta~"),
+        package_id: String::from("some_author.mod"),
+        steam_workshop_id: None,
+        url: None,
+    };
+    let out = export(ExportFormat::Markdown, &[special], None);
+    assert_eq!(
+        out.lines().nth(2).unwrap(),
+        "| 1 | \\[1.4\\] \\*Better\\* \\`mod\\_name\\` \\\\ \\~beta\\~ | some\\_author.mod |  |"
+    );
+}
+
+#[test]
+fn json() {
+    let out: serde_json::Value =
+        serde_json::from_str(&export(ExportFormat::Json, &mods(), None)).unwrap();
+    assert_eq!(
+        out[0],
+        serde_json::json!({
+            "name": "Harmony",
+            "packageId": "Brrainz.Harmony",
+            "steamWorkshopId": "2009463077",
+            "url": null,
+        })
+    );
+    assert_eq!(out[1]["url"], "https://example.com/a");
+    assert_eq!(out.as_array().unwrap().len(), 3);
+}
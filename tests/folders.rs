@@ -0,0 +1,93 @@
+use rimrs::{
+    helpers::{
+        folders::{Folder, FolderError},
+        paths::push_game_exe_path,
+    },
+    serialization::rimpy_config::RimPyConfigFolders,
+};
+use std::fs;
+
+#[test]
+fn not_set() {
+    let folders = RimPyConfigFolders::default();
+    for folder in Folder::ALL {
+        assert_eq!(folder.check(&folders), Err(FolderError::NotSet));
+    }
+}
+
+#[test]
+fn not_a_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing");
+    for folder in Folder::ALL {
+        assert_eq!(folder.check_path(&missing), Err(FolderError::NotADirectory));
+    }
+}
+
+#[test]
+fn config_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(
+        Folder::Config.check_path(dir.path()),
+        Err(FolderError::NoModsConfig)
+    );
+
+    fs::write(dir.path().join("ModsConfig.xml"), "<ModsConfigData />").unwrap();
+    assert_eq!(Folder::Config.check_path(dir.path()), Ok(()));
+}
+
+#[test]
+fn game_folder() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(
+        Folder::Game.check_path(dir.path()),
+        Err(FolderError::NoExecutable)
+    );
+
+    let exe = push_game_exe_path(dir.path().to_path_buf());
+    fs::create_dir_all(exe.parent().unwrap()).unwrap();
+    fs::write(exe, "").unwrap();
+    assert_eq!(Folder::Game.check_path(dir.path()), Ok(()));
+}
+
+#[test]
+fn mod_folders() {
+    let dir = tempfile::tempdir().unwrap();
+    // nothing subscribed to yet
+    assert_eq!(Folder::SteamMods.check_path(dir.path()), Ok(()));
+
+    fs::create_dir_all(dir.path().join("notamod")).unwrap();
+
+    assert_eq!(
+        Folder::Expansions.check_path(dir.path()),
+        Err(FolderError::NoMods)
+    );
+    // can be empty on a fresh install
+    assert_eq!(Folder::LocalMods.check_path(dir.path()), Ok(()));
+    assert_eq!(Folder::SteamMods.check_path(dir.path()), Ok(()));
+
+    fs::create_dir_all(dir.path().join("Core/About")).unwrap();
+    fs::write(dir.path().join("Core/About/About.xml"), "<ModMetaData />").unwrap();
+    assert_eq!(Folder::Expansions.check_path(dir.path()), Ok(()));
+    assert_eq!(Folder::SteamMods.check_path(dir.path()), Ok(()));
+}
+
+#[test]
+fn set_and_get() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut folders = RimPyConfigFolders::default();
+
+    Folder::LocalMods.set(&mut folders, Some(dir.path().to_path_buf()));
+    assert_eq!(folders.local_mods.as_deref(), Some(dir.path()));
+    assert_eq!(Folder::LocalMods.get(&folders), folders.local_mods.as_ref());
+    assert_eq!(Folder::LocalMods.check(&folders), Ok(()));
+}
+
+#[test]
+fn only_steam_mods_optional() {
+    let optional: Vec<_> = Folder::ALL
+        .into_iter()
+        .filter(|folder| !folder.is_required())
+        .collect();
+    assert_eq!(optional, vec![Folder::SteamMods]);
+}
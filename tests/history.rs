@@ -0,0 +1,54 @@
+use rimrs::helpers::history::{History, HistoryStep};
+
+#[test]
+fn undo_and_redo() {
+    let mut history = History::new(10);
+    assert!(!history.can_undo());
+    assert_eq!(history.undo(0), None);
+
+    // 0 -> 1 -> 2
+    history.record(0);
+    history.record(1);
+    assert!(history.can_undo());
+    assert!(!history.can_redo());
+
+    assert_eq!(history.undo(2), Some(1));
+    assert_eq!(history.step(HistoryStep::Undo, 1), Some(0));
+    assert!(!history.can_undo());
+    assert_eq!(history.undo(0), None);
+
+    assert_eq!(history.redo(0), Some(1));
+    assert_eq!(history.step(HistoryStep::Redo, 1), Some(2));
+    assert_eq!(history.redo(2), None);
+    assert_eq!(history.undo(2), Some(1));
+
+    // a new change forgets what could be redone
+    history.record(1);
+    assert!(!history.can_redo());
+    assert_eq!(history.undo(3), Some(1));
+    assert_eq!(history.undo(1), Some(0));
+
+    history.clear();
+    assert!(!history.can_undo());
+    assert!(!history.can_redo());
+}
+
+#[test]
+fn bounded() {
+    let mut history = History::new(3);
+    for state in 0..10 {
+        history.record(state);
+    }
+
+    let mut undone = Vec::new();
+    let mut current = 10;
+    while let Some(state) = history.undo(current) {
+        undone.push(state);
+        current = state;
+    }
+    assert_eq!(undone, [9, 8, 7]);
+
+    let mut nothing = History::new(0);
+    nothing.record(0);
+    assert_eq!(nothing.undo(1), None);
+}
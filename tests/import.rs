@@ -0,0 +1,256 @@
+use rimrs::{
+    serialization::{
+        import::{
+            import, import_file, ImportError, ImportFormat, ImportResult, Imported, ImportedMod,
+        },
+        mod_manager::{ModManagerList, ModManagerManifest},
+        user_rules::UserRules,
+    },
+    LoadRules, ModMetaData,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+const MOD_MANAGER_LIST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<ModList>
+    <Name>Test list</Name>
+    <modIds>
+        <li>ludeon.rimworld</li>
+        <li>brrainz.harmony</li>
+        <li>test.a</li>
+    </modIds>
+    <modNames>
+        <li>Core</li>
+        <li>Harmony</li>
+        <li>A</li>
+    </modNames>
+</ModList>"#;
+
+const MOD_MANAGER_MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Manifest>
+    <identifier>Test.A</identifier>
+    <version>1.0.0</version>
+    <loadAfter>
+        <li>brrainz.harmony</li>
+    </loadAfter>
+    <loadBefore />
+    <incompatibleWith>
+        <li>test.b >= 1.2</li>
+    </incompatibleWith>
+</Manifest>"#;
+
+const MODS_CONFIG: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<ModsConfigData>
+    <version>1.4.3613 rev641</version>
+    <activeMods>
+        <li>ludeon.rimworld</li>
+        <li>test.a</li>
+    </activeMods>
+</ModsConfigData>"#;
+
+fn ids(mods: &[&str]) -> Vec<String> {
+    mods.iter().map(|m| String::from(*m)).collect()
+}
+
+fn imported_ids(imported: Imported) -> Vec<String> {
+    match imported {
+        Imported::ModList(mods) => mods.into_iter().map(|m| m.package_id).collect(),
+        Imported::Rules(_) => panic!("expected a mod list"),
+    }
+}
+
+#[test]
+fn detect() {
+    let detect = |s: &str| ImportFormat::detect(s.as_bytes());
+    assert_eq!(detect(MODS_CONFIG), Some(ImportFormat::ModsConfig));
+    assert_eq!(detect(MOD_MANAGER_LIST), Some(ImportFormat::ModManagerList));
+    assert_eq!(
+        detect(MOD_MANAGER_MANIFEST),
+        Some(ImportFormat::ModManagerManifest)
+    );
+    assert_eq!(detect("\n  {\"rules\": {}}"), Some(ImportFormat::RulesFile));
+    assert_eq!(detect("<ModMetaData></ModMetaData>"), None);
+    assert_eq!(detect("not a mod list"), None);
+    assert_eq!(detect(""), None);
+}
+
+#[test]
+fn mod_manager_list() {
+    let list = ModManagerList::try_from(MOD_MANAGER_LIST.as_bytes()).unwrap();
+    assert_eq!(list.name.as_deref(), Some("Test list"));
+    assert_eq!(
+        list.mod_ids,
+        ids(&["ludeon.rimworld", "brrainz.harmony", "test.a"])
+    );
+    assert_eq!(list.mod_names, ids(&["Core", "Harmony", "A"]));
+}
+
+#[test]
+fn mod_manager_manifest() {
+    let manifest = ModManagerManifest::try_from(MOD_MANAGER_MANIFEST.as_bytes()).unwrap();
+    assert_eq!(manifest.identifier.as_deref(), Some("test.a"));
+    assert_eq!(
+        manifest.rules,
+        LoadRules {
+            load_after: HashSet::from([String::from("brrainz.harmony")]),
+            incompatible_with: HashSet::from([String::from("test.b")]),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn import_formats() {
+    assert_eq!(
+        imported_ids(import(MODS_CONFIG.as_bytes()).unwrap()),
+        ids(&["ludeon.rimworld", "test.a"])
+    );
+    assert_eq!(
+        imported_ids(import(MOD_MANAGER_LIST.as_bytes()).unwrap()),
+        ids(&["ludeon.rimworld", "brrainz.harmony", "test.a"])
+    );
+
+    let Imported::Rules(rules) = import(MOD_MANAGER_MANIFEST.as_bytes()).unwrap() else {
+        panic!("expected rules");
+    };
+    assert!(rules.rules["test.a"].load_after.contains("brrainz.harmony"));
+
+    let Imported::Rules(rules) = import(br#"{"rules": {"test.c": {"loadTop": true}}}"#).unwrap()
+    else {
+        panic!("expected rules");
+    };
+    assert!(rules.rules["test.c"].load_top);
+
+    assert!(matches!(
+        import(b"<Manifest><version>1</version></Manifest>"),
+        Err(ImportError::InvalidData(_))
+    ));
+    assert!(matches!(
+        import(b"<Defs></Defs>"),
+        Err(ImportError::UnknownFormat)
+    ));
+}
+
+#[test]
+fn import_from_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("list.xml");
+    fs::write(&path, MOD_MANAGER_LIST).unwrap();
+    assert!(matches!(import_file(&path), Ok(Imported::ModList(_))));
+    assert!(matches!(
+        import_file(dir.path().join("missing.xml")),
+        Err(ImportError::IOError(_))
+    ));
+}
+
+#[test]
+fn import_save_file() {
+    // only the meta block is read, so the (huge, here broken) rest of the save doesn't matter
+    let save = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<savegame>
+    <meta>
+        <modIds>
+            <li>ludeon.rimworld</li>
+            <li>brrainz.harmony</li>
+        </modIds>
+    </meta>
+    <game>{}<<< not xml"#,
+        "<thing />".repeat(100_000)
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("colony.rws");
+    fs::write(&path, save).unwrap();
+
+    assert_eq!(
+        imported_ids(import_file(&path).unwrap()),
+        ids(&["ludeon.rimworld", "brrainz.harmony"])
+    );
+}
+
+#[test]
+fn merge_rules() {
+    let mut rules = UserRules::default();
+    rules.set(
+        "test.a",
+        LoadRules {
+            load_before: HashSet::from([String::from("test.x")]),
+            load_bottom: true,
+            ..Default::default()
+        },
+    );
+
+    let Imported::Rules(imported) = import(MOD_MANAGER_MANIFEST.as_bytes()).unwrap() else {
+        panic!("expected rules");
+    };
+    rules.merge(imported);
+    assert_eq!(
+        rules.rules["test.a"],
+        LoadRules {
+            load_after: HashSet::from([String::from("brrainz.harmony")]),
+            load_before: HashSet::from([String::from("test.x")]),
+            incompatible_with: HashSet::from([String::from("test.b")]),
+            load_top: false,
+            load_bottom: true,
+        }
+    );
+}
+
+#[test]
+fn found_and_missing() {
+    let Imported::ModList(mods) = import(MOD_MANAGER_LIST.as_bytes()).unwrap() else {
+        panic!("expected a mod list");
+    };
+    let installed: HashMap<String, ModMetaData> = ["ludeon.rimworld", "test.a"]
+        .into_iter()
+        .map(|pid| (String::from(pid), ModMetaData::default()))
+        .collect();
+
+    let result = ImportResult::new(mods, &installed);
+    assert_eq!(result.found, ids(&["ludeon.rimworld", "test.a"]));
+    assert_eq!(result.missing.len(), 1);
+    assert_eq!(result.missing[0].to_string(), "Harmony (brrainz.harmony)");
+    assert_eq!(result.missing[0].steam_workshop_url(), None);
+
+    assert_eq!(
+        result.clone().into_mod_list(true),
+        ids(&["ludeon.rimworld", "brrainz.harmony", "test.a"])
+    );
+    assert_eq!(
+        result.into_mod_list(false),
+        ids(&["ludeon.rimworld", "test.a"])
+    );
+}
+
+#[test]
+fn found_and_missing_mixed_case() {
+    let mods = [
+        "Ludeon.RimWorld",
+        "Ludeon.RimWorld.Royalty",
+        "BrRainz.Harmony",
+    ]
+    .into_iter()
+    .map(|pid| ImportedMod::new(String::from(pid)))
+    .collect();
+    let installed: HashMap<String, ModMetaData> = ["ludeon.rimworld", "ludeon.rimworld.royalty"]
+        .into_iter()
+        .map(|pid| (String::from(pid), ModMetaData::default()))
+        .collect();
+
+    let result = ImportResult::new(mods, &installed);
+    assert_eq!(
+        result.found,
+        ids(&["ludeon.rimworld", "ludeon.rimworld.royalty"])
+    );
+    assert_eq!(result.missing[0].package_id, "brrainz.harmony");
+    assert_eq!(
+        result.into_mod_list(true),
+        ids(&[
+            "ludeon.rimworld",
+            "ludeon.rimworld.royalty",
+            "brrainz.harmony"
+        ])
+    );
+}
@@ -21,6 +21,18 @@ fn min_nonempty() {
     assert!(reader.next().is_none());
 }
 
+#[test]
+fn blank_lines_between_sections() {
+    let text = "[a]\nb = c\n\n[d]\ne = f\n".as_bytes();
+    let reader = INIReader::from(Box::new(text) as Box<dyn Read>);
+    let lines: Vec<_> = reader.map(Result::unwrap).collect();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1].section.as_deref(), Some("d"));
+    assert_eq!(lines[1].key, "e");
+    assert_eq!(lines[1].value, "f");
+}
+
 /// Assumes the file exists lol
 /// `cargo test from_file --test ini -- --nocapture`
 #[test]
@@ -0,0 +1,201 @@
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rimrs::{
+    sort, sort_lenient, validate, Dependency, ModEdge, ModGraph, ModMetaData, SortMode, SortRule,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+const SYNTHETIC_MODS: usize = 5000;
+
+fn synthetic_pid(i: usize) -> String {
+    format!("synthetic.mod{i:05}")
+}
+
+/// Makes `n` mods where every rule says a mod loads after one with a lower number,
+/// so sorting them by number is always valid.
+fn synthetic_mod_meta_data(n: usize) -> HashMap<String, ModMetaData> {
+    (0..n)
+        .map(|i| {
+            let dependencies = (i > 0).then(|| {
+                HashSet::from([Dependency {
+                    packageId: Some(synthetic_pid(i / 2)),
+                    ..Default::default()
+                }])
+            });
+            let load_after = (i >= 3).then(|| HashSet::from([synthetic_pid(i - 3)]));
+            let load_before = (i + 7 < n).then(|| HashSet::from([synthetic_pid(i + 7)]));
+            let mmd = ModMetaData {
+                packageId: Some(synthetic_pid(i)),
+                modDependencies: dependencies,
+                loadAfter: load_after,
+                loadBefore: load_before,
+                ..Default::default()
+            };
+            (synthetic_pid(i), mmd)
+        })
+        .collect()
+}
+
+/// Every mod, in a scrambled (but repeatable) order.
+fn scrambled(n: usize) -> Vec<String> {
+    (0..n).map(|i| synthetic_pid((i * 7919) % n)).collect()
+}
+
+#[test]
+fn edges() {
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            modDependencies: Some(HashSet::from([Dependency {
+                packageId: Some(String::from("b")),
+                ..Default::default()
+            }])),
+            loadAfter: Some(HashSet::from([String::from("b"), String::from("z")])),
+            incompatibleWith: Some(HashSet::from([String::from("c")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("c"),
+        ModMetaData {
+            loadBefore: Some(HashSet::from([String::from("a")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(String::from("b"), ModMetaData::default());
+    let mods = vec![
+        String::from("c"),
+        String::from("a"),
+        String::from("b"),
+        String::from("a"),
+    ];
+
+    let graph = ModGraph::new(&mods, &mmd);
+    assert_eq!(graph.len(), 3);
+    assert_eq!(graph.index_of("b"), Some(2));
+    assert_eq!(
+        graph.edges(1),
+        &[
+            ModEdge {
+                to: 0,
+                rule: SortRule::LoadBefore
+            },
+            ModEdge {
+                to: 2,
+                rule: SortRule::ModDependency
+            },
+            ModEdge {
+                to: 2,
+                rule: SortRule::LoadAfter
+            },
+        ]
+    );
+    assert_eq!(
+        graph.edge_between(1, 2).map(|e| e.rule),
+        Some(SortRule::ModDependency)
+    );
+    assert_eq!(graph.incompatible(1), &[0]);
+    assert_eq!(graph.toposort().unwrap(), vec![0, 2, 1]);
+}
+
+#[test]
+fn missing_metadata() {
+    let mmd = HashMap::from([(String::from("a"), ModMetaData::default())]);
+    let mods = vec![String::from("a"), String::from("b")];
+
+    let graph = ModGraph::new(&mods, &mmd);
+    assert_eq!(graph.missing_metadata().collect::<Vec<_>>(), vec![1]);
+}
+
+#[test]
+fn thousands_of_mods() {
+    let mmd = synthetic_mod_meta_data(SYNTHETIC_MODS);
+    let mods = scrambled(SYNTHETIC_MODS);
+
+    let graph = ModGraph::new(&mods, &mmd);
+    assert_eq!(graph.len(), SYNTHETIC_MODS);
+
+    let mmd = Arc::new(Mutex::new(mmd));
+    let sorted = sort(&mods, &mmd).unwrap();
+    assert_eq!(sorted.len(), SYNTHETIC_MODS);
+    let res = validate(&mmd, &sorted);
+    assert!(res.is_ok(), "{:?}", res.diagnostics().next());
+    assert!(validate(&mmd, &mods).is_err());
+
+    // already sorted, so nothing moves
+    let report = sort_lenient(&sorted, &mmd, None, SortMode::Stable).unwrap();
+    assert_eq!(report.sorted, sorted);
+    assert!(report.moved.is_empty());
+}
+
+#[test]
+fn thousands_of_shuffled_mods() {
+    let mmd = Arc::new(Mutex::new(synthetic_mod_meta_data(SYNTHETIC_MODS)));
+    let mut mods = scrambled(SYNTHETIC_MODS);
+    mods.shuffle(&mut StdRng::seed_from_u64(0));
+
+    let report = sort_lenient(&mods, &mmd, None, SortMode::Stable).unwrap();
+    assert_eq!(report.sorted.len(), SYNTHETIC_MODS);
+    assert!(report.ignored.is_empty());
+    let res = validate(&mmd, &report.sorted);
+    assert!(res.is_ok(), "{:?}", res.diagnostics().next());
+
+    assert!(!report.moved.is_empty());
+    for m in &report.moved {
+        assert_eq!(mods[m.from], m.package_id);
+        assert_eq!(report.sorted[m.to], m.package_id);
+    }
+    // only the moved mods change places relative to each other
+    let moved: HashSet<_> = report.moved.iter().map(|m| &m.package_id).collect();
+    let kept = |list: &[String]| -> Vec<String> {
+        list.iter()
+            .filter(|pid| !moved.contains(pid))
+            .cloned()
+            .collect()
+    };
+    assert_eq!(kept(&mods), kept(&report.sorted));
+}
+
+/// Prints how long each step takes for [`SYNTHETIC_MODS`] mods.
+///
+/// `cargo test --release timings --test mod_graph -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn timings() {
+    let mmd = synthetic_mod_meta_data(SYNTHETIC_MODS);
+    let mods = scrambled(SYNTHETIC_MODS);
+
+    let start = Instant::now();
+    let graph = ModGraph::new(&mods, &mmd);
+    let built = start.elapsed();
+    drop(graph);
+
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    let start = Instant::now();
+    let sorted = sort(&mods, &mmd).unwrap();
+    let sorted_in = start.elapsed();
+
+    let start = Instant::now();
+    let _ = validate(&mmd, &sorted);
+    let validated_in = start.elapsed();
+
+    let start = Instant::now();
+    let _ = sort_lenient(&sorted, &mmd, None, SortMode::Stable).unwrap();
+    let stable_in = start.elapsed();
+
+    let mut shuffled = mods.clone();
+    shuffled.shuffle(&mut StdRng::seed_from_u64(0));
+    let start = Instant::now();
+    let _ = sort_lenient(&shuffled, &mmd, None, SortMode::Stable).unwrap();
+    let shuffled_in = start.elapsed();
+
+    println!(
+        "{SYNTHETIC_MODS} mods: graph {built:?}, sort {sorted_in:?}, validate {validated_in:?}, \
+        stable re-sort {stable_in:?}, stable sort of shuffled {shuffled_in:?}"
+    );
+}
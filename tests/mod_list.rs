@@ -27,3 +27,62 @@ fn load_steam_mods() {
     assert!(la.contains("mlie.lordoftherimsthethirdage"));
     println!("{la:?}");
 }
+
+fn write_about(mod_dir: &std::path::Path, package_id: &str, name: &str) {
+    std::fs::create_dir_all(mod_dir.join("About")).unwrap();
+    std::fs::write(
+        mod_dir.join("About/About.xml"),
+        format!(
+            "<ModMetaData><name>{name}</name><packageId>{package_id}</packageId></ModMetaData>"
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn rescan_mod() {
+    let dir = tempfile::tempdir().unwrap();
+    let harmony = dir.path().join("harmony");
+    write_about(&harmony, "brrainz.harmony", "Harmony");
+    let mod_list = ModList::from_dirs([dir.path().to_path_buf()]).unwrap();
+    let no_rules = |_: &mut rimrs::ModMetaData| {};
+
+    // new mod, given its rules
+    let hugslib = dir.path().join("hugslib");
+    write_about(&hugslib, "UnlimitedHugs.HugsLib", "HugsLib");
+    let changed = mod_list.rescan_mod(&hugslib, |mmd| {
+        mmd.user_rules = Some(rimrs::LoadRules::default());
+    });
+    assert_eq!(changed, ["unlimitedhugs.hugslib"]);
+    assert!(mod_list.mods.lock().unwrap()["unlimitedhugs.hugslib"]
+        .user_rules
+        .is_some());
+
+    // updated mod, keeping its rules
+    write_about(&hugslib, "UnlimitedHugs.HugsLib", "HugsLib 2");
+    assert_eq!(
+        mod_list.rescan_mod(&hugslib, no_rules),
+        ["unlimitedhugs.hugslib"]
+    );
+    let mods = mod_list.mods.lock().unwrap();
+    assert_eq!(
+        mods["unlimitedhugs.hugslib"].name.as_deref(),
+        Some("HugsLib 2")
+    );
+    assert!(mods["unlimitedhugs.hugslib"].user_rules.is_some());
+    drop(mods);
+
+    // half-written About.xml is ignored until it's done
+    std::fs::write(harmony.join("About/About.xml"), "<ModMetaData><name>Harm").unwrap();
+    assert!(mod_list.rescan_mod(&harmony, no_rules).is_empty());
+    assert!(mod_list
+        .mods
+        .lock()
+        .unwrap()
+        .contains_key("brrainz.harmony"));
+
+    // deleted mod
+    std::fs::remove_dir_all(&harmony).unwrap();
+    assert_eq!(mod_list.rescan_mod(&harmony, no_rules), ["brrainz.harmony"]);
+    assert_eq!(mod_list.mods.lock().unwrap().len(), 1);
+}
@@ -1,4 +1,7 @@
-use rimrs::{validate, Dependency, ModList, ModMetaData, ModsConfig, RimPyConfig};
+use rimrs::{
+    validate, validate_for_version, Dependency, Diagnostic, LoadOrderRule, ModList, ModMetaData,
+    ModsConfig, RimPyConfig, Severity, SuggestedFix,
+};
 use std::{
     collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
@@ -62,7 +65,13 @@ fn no_mod_metadata() {
     assert!(res.is_warn());
     assert_eq!(
         res.warnings().unwrap(),
-        &vec![String::from("Couldn't find metadata for z")]
+        &vec![Diagnostic::MissingMetadata {
+            package_id: String::from("z")
+        }]
+    );
+    assert_eq!(
+        res.warnings().unwrap()[0].to_string(),
+        "Couldn't find metadata for z"
     );
 }
 
@@ -81,16 +90,48 @@ fn all_in_order() {
 fn hard_dependency_missing() {
     let res = validate!(&[String::from("b"), String::from("c")]);
     assert!(res.is_err());
-    assert_eq!(res.errors().unwrap(), &vec![String::from("b requires a")]);
+
+    let err = &res.errors().unwrap()[0];
+    assert_eq!(
+        err,
+        &Diagnostic::MissingDependency {
+            package_id: String::from("b"),
+            dependency: String::from("a"),
+            is_active: false,
+        }
+    );
+    assert_eq!(err.to_string(), "b requires a");
+    assert_eq!(err.severity(), Severity::Error);
+    assert_eq!(err.package_ids(), vec!["b", "a"]);
+    assert_eq!(
+        err.suggested_fix(),
+        Some(SuggestedFix::Activate {
+            package_id: String::from("a"),
+            before: String::from("b"),
+        })
+    );
 }
 
 #[test]
 fn load_after_wrong_order() {
     let res = validate!(&[String::from("d"), String::from("c")]);
     assert!(res.is_warn());
+    let warning = &res.warnings().unwrap()[0];
     assert_eq!(
-        res.warnings().unwrap(),
-        &vec![String::from("d should be loaded after c")]
+        warning,
+        &Diagnostic::LoadOrderViolation {
+            package_id: String::from("d"),
+            other: String::from("c"),
+            rule: LoadOrderRule::LoadAfter,
+        }
+    );
+    assert_eq!(warning.to_string(), "d should be loaded after c");
+    assert_eq!(
+        warning.suggested_fix(),
+        Some(SuggestedFix::MoveAfter {
+            package_id: String::from("d"),
+            after: String::from("c"),
+        })
     );
 }
 
@@ -98,10 +139,16 @@ fn load_after_wrong_order() {
 fn load_before_wrong_order() {
     let res = validate!(&[String::from("c"), String::from("a")]);
     assert!(res.is_warn());
+    let warning = &res.warnings().unwrap()[0];
     assert_eq!(
-        res.warnings().unwrap(),
-        &vec![String::from("a should be loaded before c")]
+        warning,
+        &Diagnostic::LoadOrderViolation {
+            package_id: String::from("a"),
+            other: String::from("c"),
+            rule: LoadOrderRule::LoadBefore,
+        }
     );
+    assert_eq!(warning.to_string(), "a should be loaded before c");
 }
 
 #[test]
@@ -109,12 +156,47 @@ fn incompatible() {
     let res = validate!(&[String::from("e"), String::from("b")]);
     assert!(res.is_err());
     assert_eq!(
-        res.errors().unwrap(),
-        &vec![
+        res.errors()
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec![
             String::from("b requires a"),
             String::from("e is incompatible with b")
         ]
     );
+    assert_eq!(
+        res.errors().unwrap()[1].suggested_fix(),
+        Some(SuggestedFix::Deactivate(String::from("e")))
+    );
+}
+
+#[test]
+fn dependency_by_version() {
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            modDependenciesByVersion: Some(HashMap::from([(
+                String::from("1.4"),
+                HashSet::from([Dependency {
+                    packageId: Some(String::from("b")),
+                    ..Default::default()
+                }]),
+            )])),
+            ..Default::default()
+        },
+    );
+    let mmd = Arc::new(Mutex::new(mmd));
+    let mods = [String::from("a")];
+
+    assert!(validate(&mmd, &mods).is_ok());
+    assert!(validate_for_version(&mmd, &mods, Some("1.3.3389 rev22")).is_ok());
+
+    let res = validate_for_version(&mmd, &mods, Some("1.4.3613 rev641"));
+    assert!(res.is_err());
+    assert_eq!(res.errors().unwrap()[0].to_string(), "a requires b");
 }
 
 /// Reads mod list from disk then validates it.
@@ -136,3 +218,69 @@ fn full_test() {
 
     println!("res: {res:?}");
 }
+
+#[test]
+fn unsupported_version() {
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            supportedVersions: Some(HashSet::from([String::from("1.3")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("b"),
+        ModMetaData {
+            supportedVersions: Some(HashSet::from([String::from("1.3"), String::from("1.4")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(String::from("c"), ModMetaData::default());
+    let mmd = Arc::new(Mutex::new(mmd));
+    let mods = [String::from("a"), String::from("b"), String::from("c")];
+
+    assert!(validate(&mmd, &mods).is_ok());
+
+    let res = validate_for_version(&mmd, &mods, Some("1.4.3613 rev641"));
+    assert!(res.is_warn());
+    assert_eq!(
+        res.warnings().unwrap(),
+        &vec![Diagnostic::UnsupportedVersion {
+            package_id: String::from("a"),
+            version: String::from("1.4"),
+        }]
+    );
+    assert_eq!(
+        res.warnings().unwrap()[0].to_string(),
+        "a doesn't support version 1.4"
+    );
+}
+
+#[test]
+fn dependency_loaded_too_late() {
+    let res = validate!(&[String::from("b"), String::from("a")]);
+    assert_eq!(
+        res.errors().unwrap()[0].suggested_fix(),
+        Some(SuggestedFix::MoveBefore {
+            package_id: String::from("a"),
+            before: String::from("b"),
+        })
+    );
+}
+
+#[test]
+fn duplicate() {
+    let res = validate!(&[String::from("c"), String::from("c"), String::from("c")]);
+    assert!(res.is_warn());
+    assert_eq!(
+        res.warnings().unwrap(),
+        &vec![Diagnostic::Duplicate {
+            package_id: String::from("c")
+        }]
+    );
+    assert_eq!(
+        res.warnings().unwrap()[0].to_string(),
+        "c is active more than once"
+    );
+}
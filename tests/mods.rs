@@ -1,5 +1,8 @@
-use rimrs::ModMetaData;
-use std::path::PathBuf;
+use rimrs::{major_minor_version, ModMetaData};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 #[test]
 fn parse_mod_meta_data() {
@@ -15,3 +18,58 @@ fn parse_mod_meta_data() {
         .unwrap()
         .contains(&String::from("1.3")));
 }
+
+#[test]
+fn major_minor() {
+    assert_eq!(major_minor_version("1.4.3613 rev641"), "1.4");
+    assert_eq!(major_minor_version("1.4"), "1.4");
+    assert_eq!(major_minor_version(" 1.5.4104 rev435 "), "1.5");
+    assert_eq!(major_minor_version(""), "");
+}
+
+#[test]
+fn effective_for() {
+    let mmd = ModMetaData {
+        description: Some(String::from("Generic")),
+        descriptionsByVersion: Some(HashMap::from([(String::from("1.3"), String::from("Old"))])),
+        loadAfter: Some(HashSet::from([String::from("a")])),
+        loadAfterByVersion: Some(HashMap::from([(
+            String::from("1.4"),
+            HashSet::from([String::from("b")]),
+        )])),
+        incompatibleWithByVersion: Some(HashMap::from([(
+            String::from("1.3"),
+            HashSet::from([String::from("c")]),
+        )])),
+        ..Default::default()
+    };
+
+    let old = mmd.effective_for("1.3.3389 rev22");
+    assert_eq!(old.description.unwrap(), "Old");
+    assert_eq!(old.loadAfter.unwrap(), HashSet::from([String::from("a")]));
+    assert_eq!(
+        old.incompatibleWith.unwrap(),
+        HashSet::from([String::from("c")])
+    );
+
+    let new = mmd.effective_for("1.4.3613 rev641");
+    assert_eq!(new.description.unwrap(), "Generic");
+    assert_eq!(
+        new.loadAfter.unwrap(),
+        HashSet::from([String::from("a"), String::from("b")])
+    );
+    assert!(new.incompatibleWith.is_none());
+}
+
+#[test]
+fn supports_version() {
+    let mmd = ModMetaData {
+        supportedVersions: Some(HashSet::from([String::from("1.3"), String::from("1.4")])),
+        ..Default::default()
+    };
+
+    assert_eq!(mmd.supports_version("1.4.3613 rev641"), Some(true));
+    assert_eq!(mmd.supports_version("1.3"), Some(true));
+    assert_eq!(mmd.supports_version("1.5.4104 rev435"), Some(false));
+    assert_eq!(ModMetaData::default().supports_version("1.4"), None);
+}
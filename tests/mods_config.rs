@@ -0,0 +1,218 @@
+use proptest::prelude::*;
+use rimrs::{ModsConfig, ModsConfigFile};
+
+const RIMWORLD_FILE: &str = "\u{feff}<?xml version=\"1.0\" encoding=\"utf-8\"?>\r
+<ModsConfigData>\r
+  <version>1.4.3613 rev641</version>\r
+  <activeMods>\r
+    <li>brrainz.harmony</li>\r
+    <li>ludeon.rimworld</li>\r
+  </activeMods>\r
+  <knownExpansions>\r
+    <li>ludeon.rimworld.royalty</li>\r
+  </knownExpansions>\r
+  <someNewSetting attr=\"x\">true</someNewSetting>\r
+</ModsConfigData>";
+
+fn ids(mods: &[&str]) -> Vec<String> {
+    mods.iter().map(|m| String::from(*m)).collect()
+}
+
+fn parse(text: &str) -> ModsConfigFile {
+    ModsConfigFile::try_from(text.as_bytes()).unwrap()
+}
+
+#[test]
+fn keeps_unknown_elements() {
+    let mut file = parse(RIMWORLD_FILE);
+    assert_eq!(String::from(&file), RIMWORLD_FILE);
+
+    file.mods_config.activeMods = ids(&["ludeon.rimworld", "a&b", "unlimitedhugs.hugslib"]);
+    assert_eq!(
+        String::from(&file),
+        "\u{feff}<?xml version=\"1.0\" encoding=\"utf-8\"?>\r
+<ModsConfigData>\r
+  <version>1.4.3613 rev641</version>\r
+  <activeMods>\r
+    <li>ludeon.rimworld</li>\r
+    <li>a&amp;b</li>\r
+    <li>unlimitedhugs.hugslib</li>\r
+  </activeMods>\r
+  <knownExpansions>\r
+    <li>ludeon.rimworld.royalty</li>\r
+  </knownExpansions>\r
+  <someNewSetting attr=\"x\">true</someNewSetting>\r
+</ModsConfigData>"
+    );
+}
+
+#[test]
+fn adds_and_removes_sections() {
+    let mut file = parse(
+        "<ModsConfigData>\n  <version>1</version>\n  <other><activeMods><li>not.this</li></activeMods></other>\n</ModsConfigData>",
+    );
+    assert!(file.mods_config.activeMods.is_empty());
+
+    file.mods_config.version = None;
+    file.mods_config.activeMods = ids(&["a"]);
+    assert_eq!(
+        String::from(&file),
+        "<ModsConfigData>\n  <other><activeMods><li>not.this</li></activeMods></other>\n  <activeMods>\n    <li>a</li>\n  </activeMods>\n</ModsConfigData>"
+    );
+
+    let mut file = parse("<ModsConfigData><activeMods><li>a</li></activeMods></ModsConfigData>");
+    file.mods_config.activeMods.clear();
+    assert_eq!(
+        String::from(&file),
+        "<ModsConfigData><activeMods /></ModsConfigData>"
+    );
+}
+
+#[test]
+fn whitespace_and_escaping() {
+    let file = parse(
+        "<ModsConfigData>\n<version>\n  1.4 \n</version><!-- <activeMods><li>no</li></activeMods> -->\n<activeMods>\n<li>  a  </li><li></li>\n<li><![CDATA[b<c]]></li><li>d &amp; e</li></activeMods></ModsConfigData>",
+    );
+    assert_eq!(file.mods_config.version.as_deref(), Some("1.4"));
+    assert_eq!(file.mods_config.activeMods, ids(&["a", "b<c", "d & e"]));
+}
+
+#[test]
+fn doesnt_panic_on_stray_text() {
+    let file = parse("<ModsConfigData>stray<unknown>text</unknown></ModsConfigData>");
+    assert!(file.mods_config.version.is_none());
+    assert!(ModsConfig::try_from(&b""[..]).is_err());
+}
+
+/// A package id or version, possibly with characters that need escaping.
+fn value() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9._<>&'\" -]{1,16}"
+        .prop_map(|s| String::from(s.trim()))
+        .prop_filter("must not be blank", |s| !s.is_empty())
+}
+
+fn whitespace() -> impl Strategy<Value = String> {
+    "[ \t\n]{0,4}"
+}
+
+/// Something `ModsConfig` doesn't know about.
+fn unknown() -> impl Strategy<Value = String> {
+    prop_oneof![
+        value().prop_map(|v| format!(
+            "<unknown a=\"1\">{}</unknown>",
+            xml::escape::escape_str_pcdata(&v)
+        )),
+        Just(String::from("<!-- <version>not this</version> -->")),
+        Just(String::from("<empty />")),
+        Just(String::from(
+            "<nested><activeMods><li>not.this</li></activeMods></nested>"
+        )),
+    ]
+}
+
+/// A `ModsConfig.xml` with its known sections and unknown elements in any order and with any spacing,
+/// along with the active mods in it.
+fn mods_config_file() -> impl Strategy<Value = (String, Vec<String>)> {
+    (
+        proptest::option::of(value()),
+        prop::collection::vec(value(), 0..6),
+        prop::collection::vec(value(), 0..3),
+        prop::collection::vec(unknown(), 0..3),
+        prop::collection::vec(whitespace(), 8),
+        any::<prop::sample::Index>(),
+    )
+        .prop_map(|(version, active, known, unknowns, ws, index)| {
+            let list = |tag: &str, items: &[String]| {
+                let mut out = format!("<{tag}>");
+                for item in items {
+                    out.push_str(&format!(
+                        "{}<li>{}</li>",
+                        ws[2],
+                        xml::escape::escape_str_pcdata(item)
+                    ));
+                }
+                out.push_str(&format!("{}</{tag}>", ws[3]));
+                out
+            };
+
+            let mut children = unknowns;
+            let mut sections = Vec::new();
+            if let Some(version) = version.as_ref() {
+                sections.push(format!(
+                    "<version>{}{}{}</version>",
+                    ws[4],
+                    xml::escape::escape_str_pcdata(version),
+                    ws[5]
+                ));
+            }
+            sections.push(list("activeMods", &active));
+            sections.push(list("knownExpansions", &known));
+            let at = index.index(children.len() + 1);
+            children.splice(at..at, sections);
+
+            let mut text = format!(
+                "<?xml version=\"1.0\" encoding=\"utf-8\"?>{}<ModsConfigData>",
+                ws[0]
+            );
+            for child in children {
+                text.push_str(&format!("{}{child}", ws[1]));
+            }
+            text.push_str(&format!("{}</ModsConfigData>{}", ws[6], ws[7]));
+            (text, active)
+        })
+}
+
+proptest! {
+    #[test]
+    fn unchanged_round_trips((text, active) in mods_config_file()) {
+        let file = parse(&text);
+        prop_assert_eq!(&file.mods_config.activeMods, &active);
+        prop_assert_eq!(String::from(&file), text);
+    }
+
+    #[test]
+    fn changes_only_touch_their_section(
+        (text, _) in mods_config_file(),
+        new_active in prop::collection::vec(value(), 0..6),
+    ) {
+        let original = parse(&text);
+        let mut file = original.clone();
+        file.mods_config.activeMods = new_active.clone();
+
+        let written = String::from(&file);
+        let reparsed = parse(&written);
+        prop_assert_eq!(&reparsed.mods_config.activeMods, &new_active);
+        prop_assert_eq!(&reparsed.mods_config.version, &original.mods_config.version);
+        prop_assert_eq!(&reparsed.mods_config.knownExpansions, &original.mods_config.knownExpansions);
+
+        // the rest of the file is kept
+        let unknowns = ["<unknown", "<!--", "<empty />", "<nested>"];
+        for unknown in unknowns {
+            prop_assert_eq!(written.matches(unknown).count(), text.matches(unknown).count());
+        }
+
+        // and changing it back gives the original file
+        file.mods_config.activeMods = original.mods_config.activeMods.clone();
+        prop_assert_eq!(String::from(&file), text);
+    }
+
+    #[test]
+    fn new_files_round_trip(
+        version in proptest::option::of(value()),
+        active in prop::collection::vec(value(), 0..6),
+        known in prop::collection::vec(value(), 0..3),
+    ) {
+        let mods_config = ModsConfig {
+            version: version.clone(),
+            activeMods: active.clone(),
+            knownExpansions: known.clone(),
+        };
+
+        let written = String::from(&mods_config);
+        let reparsed = parse(&written);
+        prop_assert_eq!(reparsed.mods_config.version, version);
+        prop_assert_eq!(reparsed.mods_config.activeMods, active);
+        prop_assert_eq!(reparsed.mods_config.knownExpansions, known);
+        prop_assert_eq!(String::from(&parse(&written)), written);
+    }
+}
@@ -0,0 +1,92 @@
+use rimrs::profiles::{ProfileError, Profiles};
+use std::fs;
+
+fn ids(mods: &[&str]) -> Vec<String> {
+    mods.iter().map(|m| String::from(*m)).collect()
+}
+
+#[test]
+fn create_rename_duplicate_delete() {
+    let dir = tempfile::tempdir().unwrap();
+    let profiles = Profiles::new(dir.path().join("profiles"));
+    assert!(profiles.list().unwrap().is_empty());
+    assert_eq!(profiles.current(), None);
+
+    let vanilla = ids(&["ludeon.rimworld", "brrainz.harmony"]);
+    profiles
+        .create("Vanilla plus", &vanilla, Some("1.4.3613 rev641"))
+        .unwrap();
+    assert_eq!(profiles.load("Vanilla plus").unwrap(), vanilla);
+    assert!(matches!(
+        profiles.create("Vanilla plus", &[], None),
+        Err(ProfileError::AlreadyExists(_))
+    ));
+
+    profiles.set_current(Some("Vanilla plus")).unwrap();
+    profiles.rename("Vanilla plus", "vanilla").unwrap();
+    assert_eq!(profiles.current().as_deref(), Some("vanilla"));
+    assert!(!profiles.exists("Vanilla plus"));
+
+    profiles.duplicate("vanilla", "Testing").unwrap();
+    assert_eq!(profiles.load("Testing").unwrap(), vanilla);
+    assert_eq!(profiles.list().unwrap(), ["Testing", "vanilla"]);
+
+    profiles
+        .save("Testing", &ids(&["ludeon.rimworld"]), None)
+        .unwrap();
+    assert_eq!(profiles.load("Testing").unwrap(), ["ludeon.rimworld"]);
+    assert_eq!(profiles.load("vanilla").unwrap(), vanilla);
+
+    profiles.delete("vanilla").unwrap();
+    assert_eq!(profiles.current(), None);
+    assert_eq!(profiles.list().unwrap(), ["Testing"]);
+    assert!(matches!(
+        profiles.load("vanilla"),
+        Err(ProfileError::NotFound(_))
+    ));
+}
+
+#[test]
+fn names() {
+    assert_eq!(
+        Profiles::validate_name("  Colony sim ").unwrap(),
+        "Colony sim"
+    );
+    for name in ["CONSOLE", "com10", "v1.4", "my nul"] {
+        assert_eq!(Profiles::validate_name(name).unwrap(), name);
+    }
+    for name in [
+        "",
+        "   ",
+        ".hidden",
+        "a/b",
+        "a\\b",
+        "what?",
+        "a\nb",
+        "CON",
+        "nul",
+        "Com1",
+        "lpt9.txt",
+        "aux .old",
+        "trailing.",
+        "trailing. ",
+    ] {
+        assert!(
+            matches!(
+                Profiles::validate_name(name),
+                Err(ProfileError::InvalidName(_))
+            ),
+            "{name:?}"
+        );
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let profiles = Profiles::new(dir.path().to_path_buf());
+    assert!(profiles.create("../escape", &[], None).is_err());
+
+    // the current profile and anything else in the folder aren't listed
+    profiles.create("one", &[], None).unwrap();
+    profiles.set_current(Some("one")).unwrap();
+    fs::write(dir.path().join("notes.txt"), "").unwrap();
+    assert_eq!(profiles.list().unwrap(), ["one"]);
+}
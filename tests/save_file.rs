@@ -0,0 +1,89 @@
+use rimrs::{
+    serialization::{
+        import::{ImportResult, ImportedMod},
+        save_file::SaveMeta,
+    },
+    ModMetaData,
+};
+use std::{collections::HashMap, fs};
+
+const SAVE_META: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<savegame>
+    <meta>
+        <gameVersion>1.4.3613 rev641</gameVersion>
+        <modIds>
+            <li>ludeon.rimworld</li>
+            <li>brrainz.harmony</li>
+            <li>test.missing</li>
+        </modIds>
+        <modSteamIds>
+            <li>0</li>
+            <li>2009463077</li>
+            <li>123</li>
+        </modSteamIds>
+        <modNames>
+            <li>Core</li>
+            <li>Harmony</li>
+            <li>Missing Mod</li>
+        </modNames>
+    </meta>
+    <game>
+        <modIds><li>not.this</li></modIds>
+"#;
+
+fn ids(mods: &[&str]) -> Vec<String> {
+    mods.iter().map(|m| String::from(*m)).collect()
+}
+
+#[test]
+fn parse_meta() {
+    // the rest of the save isn't read, so it doesn't matter that it's cut off
+    let meta = SaveMeta::from_reader(SAVE_META.as_bytes()).unwrap();
+
+    assert_eq!(meta.game_version.as_deref(), Some("1.4.3613 rev641"));
+    assert_eq!(
+        meta.mod_ids,
+        ids(&["ludeon.rimworld", "brrainz.harmony", "test.missing"])
+    );
+    assert_eq!(meta.mod_steam_ids, ids(&["0", "2009463077", "123"]));
+    assert_eq!(meta.mod_names, ids(&["Core", "Harmony", "Missing Mod"]));
+}
+
+#[test]
+fn missing() {
+    let meta = SaveMeta::from_reader(SAVE_META.as_bytes()).unwrap();
+    let installed: HashMap<String, ModMetaData> = ["ludeon.rimworld", "brrainz.harmony"]
+        .into_iter()
+        .map(|pid| (String::from(pid), ModMetaData::default()))
+        .collect();
+
+    let result = ImportResult::new(meta.mods(), &installed);
+    assert_eq!(result.found, ids(&["ludeon.rimworld", "brrainz.harmony"]));
+    assert_eq!(
+        result.missing,
+        vec![ImportedMod {
+            package_id: String::from("test.missing"),
+            name: Some(String::from("Missing Mod")),
+            steam_workshop_id: Some(String::from("123")),
+        }]
+    );
+    assert_eq!(result.missing[0].to_string(), "Missing Mod (test.missing)");
+    assert_eq!(
+        result.missing[0].steam_workshop_url().as_deref(),
+        Some("https://steamcommunity.com/sharedfiles/filedetails/?id=123")
+    );
+
+    // core isn't from the workshop
+    assert_eq!(meta.mods()[0].steam_workshop_id, None);
+}
+
+#[test]
+fn from_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("test.rws");
+    fs::write(&path, SAVE_META).unwrap();
+    assert_eq!(SaveMeta::from_path(&path).unwrap().mod_ids.len(), 3);
+
+    assert!(SaveMeta::from_path(dir.path().join("missing.rws")).is_err());
+    assert!(SaveMeta::from_reader(&b"<savegame><meta></savegame>"[..]).is_err());
+}
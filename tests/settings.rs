@@ -0,0 +1,108 @@
+use rimrs::{serialization::rimpy_config::RimPyConfigFolders, Settings};
+use std::{fs, path::PathBuf};
+
+const RIMPY_CONFIG_INI: &str = "[Folders]
+Config folder = /rw/config
+Game folder = /rw/game
+Local mods = /rw/game/Mods
+Expansions = /rw/game/Data
+Steam mods = /steam/workshop/content/294100
+
+[StartupParams]
+Params = -popupwindow
+
+[Colors]
+Background = #000000
+";
+
+fn settings_path(dir: &tempfile::TempDir) -> PathBuf {
+    dir.path().join("rimrs").join("settings.ini")
+}
+
+#[test]
+fn round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = settings_path(&dir);
+
+    let mut settings = Settings {
+        folders: RimPyConfigFolders {
+            config_folder: Some(PathBuf::from("/rw/config")),
+            game_folder: Some(PathBuf::from("/rw/game")),
+            steamcmd: Some(PathBuf::from("/steamcmd")),
+            ..Default::default()
+        },
+        startup_params: Some(String::from("-popupwindow -logfile a=b.log")),
+        ..Default::default()
+    };
+    settings.ui.dark_mode = false;
+    settings.save_to(&path).unwrap();
+
+    let read = Settings::from_path(&path).unwrap();
+    assert_eq!(read.folders.config_folder, settings.folders.config_folder);
+    assert_eq!(read.folders.game_folder, settings.folders.game_folder);
+    assert_eq!(read.folders.steamcmd, settings.folders.steamcmd);
+    assert!(read.folders.local_mods.is_none());
+    assert_eq!(read.startup_params, settings.startup_params);
+    assert!(!read.ui.dark_mode);
+}
+
+#[test]
+fn invalid_dark_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("settings.ini");
+    fs::write(&path, "[UI]\nDark mode = maybe\n").unwrap();
+
+    assert!(Settings::from_path(&path).is_err());
+}
+
+#[test]
+fn migrates_from_rimpy() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings_path = settings_path(&dir);
+    let rimpy_path = dir.path().join("config.ini");
+    fs::write(&rimpy_path, RIMPY_CONFIG_INI).unwrap();
+
+    let settings = Settings::load_or_migrate(&settings_path, Some(&rimpy_path)).unwrap();
+    assert_eq!(
+        settings.folders.steam_mods,
+        Some(PathBuf::from("/steam/workshop/content/294100"))
+    );
+    assert_eq!(settings.startup_params.as_deref(), Some("-popupwindow"));
+
+    // should have been saved as native settings
+    let saved = Settings::from_path(&settings_path).unwrap();
+    assert_eq!(saved.folders.game_folder, Some(PathBuf::from("/rw/game")));
+    assert_eq!(
+        saved.folders.expansions,
+        Some(PathBuf::from("/rw/game/Data"))
+    );
+}
+
+#[test]
+fn prefers_native_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings_path = settings_path(&dir);
+    let rimpy_path = dir.path().join("config.ini");
+    fs::write(&rimpy_path, RIMPY_CONFIG_INI).unwrap();
+    fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+    fs::write(&settings_path, "[Folders]\nGame folder = /native/game\n").unwrap();
+
+    let settings = Settings::load_or_migrate(&settings_path, Some(&rimpy_path)).unwrap();
+    assert_eq!(
+        settings.folders.game_folder,
+        Some(PathBuf::from("/native/game"))
+    );
+    assert!(settings.folders.config_folder.is_none());
+    assert!(settings.startup_params.is_none());
+}
+
+#[test]
+fn defaults_without_any_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings_path = settings_path(&dir);
+
+    let settings = Settings::load_or_migrate(&settings_path, None).unwrap();
+    assert!(settings.folders.game_folder.is_none());
+    assert!(settings.ui.dark_mode);
+    assert!(!settings_path.exists());
+}
@@ -1,7 +1,10 @@
 use rand::{prelude::SliceRandom, thread_rng};
-use rimrs::{sort, Dependency, ModList, ModMetaData, ModsConfig, RimPyConfig, SortError};
+use rimrs::{
+    sort, sort_for_version, sort_lenient, Cycle, Dependency, ModList, ModMetaData, ModsConfig,
+    RimPyConfig, SortEdge, SortError, SortMode, SortRule,
+};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
 
@@ -140,7 +143,87 @@ fn cyclic() {
     let mmd = Arc::new(Mutex::new(mmd));
 
     let sorted = sort(&mods, &mmd);
-    assert_eq!(sorted, Err(SortError::CyclicError));
+    let expected = Cycle(vec![
+        SortEdge {
+            package_id: String::from("a"),
+            after: String::from("b"),
+            rule: SortRule::LoadAfter,
+        },
+        SortEdge {
+            package_id: String::from("b"),
+            after: String::from("a"),
+            rule: SortRule::ModDependency,
+        },
+    ]);
+    assert_eq!(sorted, Err(SortError::CyclicError(expected.clone())));
+    assert_eq!(expected.package_ids(), vec!["a", "b"]);
+    assert_eq!(
+        expected.to_string(),
+        "a loads after b (loadAfter in a), b depends on a"
+    );
+}
+
+#[test]
+fn cyclic_load_before() {
+    let mods = vec![String::from("a"), String::from("b"), String::from("c")];
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            forceLoadBefore: Some(HashSet::from([String::from("c")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("b"),
+        ModMetaData {
+            loadAfter: Some(HashSet::from([String::from("c")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("c"),
+        ModMetaData {
+            loadAfter: Some(HashSet::from([String::from("b")])),
+            ..Default::default()
+        },
+    );
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    let Err(SortError::CyclicError(cycle)) = sort(&mods, &mmd) else {
+        panic!("expected a cycle");
+    };
+    assert_eq!(cycle.package_ids(), vec!["b", "c"]);
+}
+
+#[test]
+fn load_before_later_mod() {
+    // `a` comes first alphabetically, so this makes sure `loadBefore` isn't only checked one way round
+    let mods = vec![String::from("b"), String::from("a")];
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            loadBefore: Some(HashSet::from([String::from("b")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("b"),
+        ModMetaData {
+            loadAfter: Some(HashSet::from([String::from("z")])),
+            ..Default::default()
+        },
+    );
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    assert_eq!(sort(&mods, &mmd).unwrap(), vec!["a", "b"]);
+
+    let mut mmd = mmd.lock().unwrap().clone();
+    mmd.get_mut("a").unwrap().loadBefore = None;
+    mmd.get_mut("b").unwrap().loadBefore = Some(HashSet::from([String::from("a")]));
+    let mmd = Arc::new(Mutex::new(mmd));
+    assert_eq!(sort(&mods, &mmd).unwrap(), vec!["b", "a"]);
 }
 
 /// `cargo test from_active --test sorting -- --nocapture`
@@ -162,3 +245,274 @@ fn from_active() {
 
     println!("{:?}", sorted.unwrap());
 }
+
+#[test]
+fn by_version() {
+    let mods = vec![String::from("a"), String::from("b")];
+
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            packageId: Some(String::from("a")),
+            loadAfterByVersion: Some(HashMap::from([(
+                String::from("1.4"),
+                HashSet::from([String::from("b")]),
+            )])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("b"),
+        ModMetaData {
+            packageId: Some(String::from("b")),
+            ..Default::default()
+        },
+    );
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    assert_eq!(sort(&mods, &mmd).unwrap(), vec!["a", "b"]);
+    assert_eq!(
+        sort_for_version(&mods, &mmd, Some("1.3.3389 rev22")).unwrap(),
+        vec!["a", "b"]
+    );
+    assert_eq!(
+        sort_for_version(&mods, &mmd, Some("1.4.3613 rev641")).unwrap(),
+        vec!["b", "a"]
+    );
+}
+
+#[test]
+fn lenient_ignores_soft_rule() {
+    // same as `cyclic`, but the loadAfter can be dropped
+    let mods = vec![String::from("a"), String::from("b")];
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            loadAfter: Some(HashSet::from([String::from("b")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("b"),
+        ModMetaData {
+            modDependencies: Some(HashSet::from([Dependency {
+                packageId: Some(String::from("a")),
+                ..Default::default()
+            }])),
+            ..Default::default()
+        },
+    );
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    let report = sort_lenient(&mods, &mmd, None, SortMode::Alphabetical).unwrap();
+    assert_eq!(report.sorted, vec!["a", "b"]);
+    assert_eq!(
+        report.ignored,
+        vec![SortEdge {
+            package_id: String::from("a"),
+            after: String::from("b"),
+            rule: SortRule::LoadAfter,
+        }]
+    );
+}
+
+#[test]
+fn lenient_keeps_unrelated_rules() {
+    let mods = vec![
+        String::from("a"),
+        String::from("b"),
+        String::from("c"),
+        String::from("d"),
+    ];
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            loadAfter: Some(HashSet::from([String::from("b")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("b"),
+        ModMetaData {
+            loadAfter: Some(HashSet::from([String::from("a")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("c"),
+        ModMetaData {
+            loadBefore: Some(HashSet::from([String::from("a")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("d"),
+        ModMetaData {
+            loadBefore: Some(HashSet::from([String::from("c")])),
+            ..Default::default()
+        },
+    );
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    let report = sort_lenient(&mods, &mmd, None, SortMode::Alphabetical).unwrap();
+    assert_eq!(report.ignored.len(), 1);
+
+    let pos = |pid: &str| report.sorted.iter().position(|m| m == pid).unwrap();
+    assert!(pos("d") < pos("c"));
+    assert!(pos("c") < pos("a"));
+}
+
+#[test]
+fn lenient_ignores_only_needed_rules() {
+    // a <-> b is found first, and its loadBefore gets dropped,
+    // but then b's loadAfter has to be dropped for a -> c -> b -> a, which breaks both cycles
+    let mods = vec![String::from("a"), String::from("b"), String::from("c")];
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            modDependencies: Some(HashSet::from([Dependency {
+                packageId: Some(String::from("c")),
+                ..Default::default()
+            }])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("b"),
+        ModMetaData {
+            loadBefore: Some(HashSet::from([String::from("a")])),
+            loadAfter: Some(HashSet::from([String::from("a")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("c"),
+        ModMetaData {
+            forceLoadAfter: Some(HashSet::from([String::from("b")])),
+            ..Default::default()
+        },
+    );
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    let report = sort_lenient(&mods, &mmd, None, SortMode::Alphabetical).unwrap();
+    assert_eq!(report.sorted, vec!["b", "c", "a"]);
+    assert_eq!(
+        report.ignored,
+        vec![SortEdge {
+            package_id: String::from("b"),
+            after: String::from("a"),
+            rule: SortRule::LoadAfter,
+        }]
+    );
+}
+
+#[test]
+fn lenient_fails_on_hard_cycle() {
+    let mods = vec![String::from("a"), String::from("b")];
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            forceLoadAfter: Some(HashSet::from([String::from("b")])),
+            loadBefore: Some(HashSet::from([String::from("b")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(
+        String::from("b"),
+        ModMetaData {
+            forceLoadAfter: Some(HashSet::from([String::from("a")])),
+            ..Default::default()
+        },
+    );
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    let Err(SortError::CyclicError(cycle)) =
+        sort_lenient(&mods, &mmd, None, SortMode::Alphabetical)
+    else {
+        panic!("expected a cycle");
+    };
+    assert!(cycle.0.iter().all(|edge| !edge.rule.is_soft()));
+}
+
+#[test]
+fn stable_keeps_order() {
+    let mods = vec![
+        String::from("z"),
+        String::from("a"),
+        String::from("y"),
+        String::from("b"),
+    ];
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            modDependencies: Some(HashSet::from([Dependency {
+                packageId: Some(String::from("b")),
+                ..Default::default()
+            }])),
+            ..Default::default()
+        },
+    );
+    for pid in ["b", "y", "z"] {
+        mmd.insert(String::from(pid), ModMetaData::default());
+    }
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    let report = sort_lenient(&mods, &mmd, None, SortMode::Stable).unwrap();
+    assert_eq!(report.sorted, vec!["z", "b", "a", "y"]);
+    assert_eq!(report.moved.len(), 1);
+
+    let moved = &report.moved[0];
+    assert_eq!(
+        (moved.package_id.as_str(), moved.from, moved.to),
+        ("b", 3, 1)
+    );
+    assert_eq!(
+        moved.because,
+        vec![SortEdge {
+            package_id: String::from("a"),
+            after: String::from("b"),
+            rule: SortRule::ModDependency,
+        }]
+    );
+    assert_eq!(moved.to_string(), "b moved from 4 to 2 (a depends on b)");
+
+    // already sorted, so nothing moves
+    let report = sort_lenient(&report.sorted, &mmd, None, SortMode::Stable).unwrap();
+    assert_eq!(report.sorted, vec!["z", "b", "a", "y"]);
+    assert!(report.moved.is_empty());
+
+    // the alphabetical sort doesn't report moves
+    let report = sort_lenient(&mods, &mmd, None, SortMode::Alphabetical).unwrap();
+    assert_eq!(report.sorted, vec!["b", "a", "y", "z"]);
+    assert!(report.moved.is_empty());
+}
+
+#[test]
+fn stable_load_before() {
+    let mods = vec![String::from("c"), String::from("b"), String::from("a")];
+    let mut mmd = HashMap::new();
+    mmd.insert(
+        String::from("a"),
+        ModMetaData {
+            loadBefore: Some(HashSet::from([String::from("c")])),
+            ..Default::default()
+        },
+    );
+    mmd.insert(String::from("b"), ModMetaData::default());
+    mmd.insert(String::from("c"), ModMetaData::default());
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    let report = sort_lenient(&mods, &mmd, None, SortMode::Stable).unwrap();
+    assert_eq!(report.sorted, vec!["a", "c", "b"]);
+    assert_eq!(report.moved.len(), 1);
+    assert_eq!(
+        report.moved[0].to_string(),
+        "a moved from 3 to 1 (c loads after a (loadBefore in a))"
+    );
+}
@@ -0,0 +1,193 @@
+use rimrs::{
+    steamcmd::{CommandRunner, DownloadReport, SteamCmd, SteamCmdError},
+    validate, Dependency, ModMetaData,
+};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Output},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+#[cfg(target_os = "windows")]
+const EXECUTABLE_NAME: &str = "steamcmd.exe";
+#[cfg(not(target_os = "windows"))]
+const EXECUTABLE_NAME: &str = "steamcmd.sh";
+
+type Calls = Rc<RefCell<Vec<(PathBuf, Vec<OsString>)>>>;
+
+/// Records what it was asked to run, and "downloads" item 123.
+#[derive(Default)]
+struct FakeRunner {
+    calls: Calls,
+}
+
+impl CommandRunner for FakeRunner {
+    fn run(&self, program: &Path, args: &[OsString]) -> io::Result<Output> {
+        self.calls
+            .borrow_mut()
+            .push((program.to_path_buf(), args.to_vec()));
+        Ok(Output {
+            status: ExitStatus::default(),
+            stdout: b"Success. Downloaded item 123 to \"somewhere\" (10 bytes)\n".to_vec(),
+            stderr: Vec::new(),
+        })
+    }
+}
+
+fn ids(mods: &[&str]) -> Vec<String> {
+    mods.iter().map(|m| String::from(*m)).collect()
+}
+
+#[test]
+fn script() {
+    let steamcmd = SteamCmd::new(PathBuf::from("steamcmd"));
+    let script = steamcmd.script(&ids(&["123", "456"]));
+
+    assert!(script.contains("force_install_dir \"steamcmd\"\n"));
+    assert!(script.contains("login anonymous\n"));
+    assert!(
+        script.contains("workshop_download_item 294100 123\nworkshop_download_item 294100 456\n")
+    );
+    assert!(script.ends_with("quit\n"));
+
+    assert_eq!(
+        steamcmd.download_dir(),
+        PathBuf::from("steamcmd/steamapps/workshop/content/294100")
+    );
+}
+
+#[test]
+fn report_from_output() {
+    let stdout = "Loading Steam API...OK\n\
+                  Success. Downloaded item 123 to \"/steamapps/workshop/content/294100/123\" (100 bytes)\n\
+                  ERROR! Download item 456 failed (Failure).\n";
+    assert_eq!(
+        DownloadReport::from_output(stdout, &ids(&["123", "456"])),
+        DownloadReport {
+            downloaded: ids(&["123"]),
+            failed: ids(&["456"]),
+        }
+    );
+}
+
+#[test]
+fn download_with_fake_runner() {
+    let dir = tempfile::tempdir().unwrap();
+    let executable = dir.path().join(EXECUTABLE_NAME);
+    fs::write(&executable, "").unwrap();
+
+    let runner = FakeRunner::default();
+    let calls = Rc::clone(&runner.calls);
+    let steamcmd = SteamCmd::with_runner(dir.path().to_path_buf(), runner);
+
+    let report = steamcmd.download(&ids(&["123", "456"])).unwrap();
+    assert_eq!(report.downloaded, ids(&["123"]));
+    assert_eq!(report.failed, ids(&["456"]));
+
+    let script_path = dir.path().join("rimrs_download.txt");
+    assert_eq!(
+        fs::read_to_string(&script_path).unwrap(),
+        steamcmd.script(&ids(&["123", "456"]))
+    );
+    assert_eq!(
+        *calls.borrow(),
+        vec![(
+            executable,
+            vec![OsString::from("+runscript"), script_path.into_os_string()]
+        )]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn download_with_fake_executable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let executable = dir.path().join(EXECUTABLE_NAME);
+    fs::write(
+        &executable,
+        "#!/bin/sh\n[ \"$1\" = \"+runscript\" ] && [ -f \"$2\" ] || exit 1\necho 'Success. Downloaded item 456 to \"somewhere\" (10 bytes)'\n",
+    )
+    .unwrap();
+    fs::set_permissions(&executable, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let report = SteamCmd::new(dir.path().to_path_buf())
+        .download(&ids(&["123", "456"]))
+        .unwrap();
+    assert_eq!(report.downloaded, ids(&["456"]));
+    assert_eq!(report.failed, ids(&["123"]));
+}
+
+#[test]
+fn steamcmd_not_found() {
+    let dir = tempfile::tempdir().unwrap();
+    let runner = FakeRunner::default();
+    let calls = Rc::clone(&runner.calls);
+
+    let res = SteamCmd::with_runner(dir.path().to_path_buf(), runner).download(&ids(&["123"]));
+    assert!(
+        matches!(res, Err(SteamCmdError::NotFound(path)) if path == dir.path().join(EXECUTABLE_NAME))
+    );
+    assert!(calls.borrow().is_empty());
+}
+
+#[test]
+fn dependency_workshop_id() {
+    let dep = Dependency {
+        steamWorkshopUrl: Some(String::from("steam://url/CommunityFilePage/2009463077")),
+        ..Default::default()
+    };
+    assert_eq!(dep.steam_workshop_id(), Some("2009463077"));
+
+    let dep = Dependency {
+        steamWorkshopUrl: Some(String::from(
+            "https://steamcommunity.com/sharedfiles/filedetails/?id=818773962",
+        )),
+        ..Default::default()
+    };
+    assert_eq!(dep.steam_workshop_id(), Some("818773962"));
+
+    assert_eq!(Dependency::default().steam_workshop_id(), None);
+}
+
+#[test]
+fn missing_dependencies() {
+    let harmony = Dependency {
+        packageId: Some(String::from("brrainz.harmony")),
+        displayName: Some(String::from("Harmony")),
+        steamWorkshopUrl: Some(String::from("steam://url/CommunityFilePage/2009463077")),
+        ..Default::default()
+    };
+    let installed = Dependency {
+        packageId: Some(String::from("c")),
+        ..Default::default()
+    };
+
+    let mut mmd = HashMap::new();
+    for pid in ["a", "b"] {
+        mmd.insert(
+            String::from(pid),
+            ModMetaData {
+                modDependencies: Some(HashSet::from([harmony.clone(), installed.clone()])),
+                ..Default::default()
+            },
+        );
+    }
+    mmd.insert(String::from("c"), ModMetaData::default());
+    let mmd = Arc::new(Mutex::new(mmd));
+
+    let res = validate(&mmd, &ids(&["c", "a", "b"]));
+    let missing = res.missing_dependencies(&mmd.lock().unwrap());
+
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].package_id, "brrainz.harmony");
+    assert_eq!(missing[0].name(), "Harmony");
+    assert_eq!(missing[0].details.steam_workshop_id(), Some("2009463077"));
+    assert_eq!(missing[0].required_by, ids(&["a", "b"]));
+}
@@ -0,0 +1,175 @@
+use rimrs::{
+    serialization::{rules_file, user_rules::UserRules},
+    sort, validate, LoadRules, ModList, ModMetaData, Settings,
+};
+use std::{collections::HashSet, fs};
+
+fn mod_meta_data(pid: &str) -> ModMetaData {
+    ModMetaData {
+        packageId: Some(String::from(pid)),
+        ..Default::default()
+    }
+}
+
+fn ids(mods: &[&str]) -> Vec<String> {
+    mods.iter().map(|m| String::from(*m)).collect()
+}
+
+fn pids(mods: &[&str]) -> HashSet<String> {
+    mods.iter().map(|m| String::from(*m)).collect()
+}
+
+#[test]
+fn round_trip() {
+    let mut rules = UserRules::default();
+    rules.set(
+        "test.a",
+        LoadRules {
+            load_after: pids(&["test.b"]),
+            incompatible_with: pids(&["test.c", "test.d"]),
+            load_top: true,
+            ..Default::default()
+        },
+    );
+    rules.set("test.b", LoadRules::default());
+    assert_eq!(rules.rules.len(), 1);
+
+    let json = rules_file::to_json(Some(1), &rules.rules).to_string();
+    let (timestamp, parsed) = rules_file::parse(json.as_bytes()).unwrap();
+    assert_eq!(timestamp, Some(1));
+    assert_eq!(parsed, rules.rules);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nested").join("userRules.json");
+    rules.save_to(&path).unwrap();
+    assert_eq!(UserRules::from_path(&path).unwrap(), rules);
+}
+
+#[test]
+fn mixed_case() {
+    let mut rules = UserRules::default();
+    let load_top = LoadRules {
+        load_top: true,
+        ..Default::default()
+    };
+    rules.set("Test.A", load_top.clone());
+    assert_eq!(rules.get("test.a"), Some(&load_top));
+    assert_eq!(rules.get("TEST.A"), Some(&load_top));
+
+    rules.set("TEST.a", LoadRules::default());
+    assert_eq!(rules.get("test.a"), None);
+    assert!(rules.rules.is_empty());
+}
+
+#[test]
+fn sort_with_user_rules() {
+    let mod_list = ModList::from(["test.a", "test.b", "test.c", "test.d"].map(mod_meta_data));
+    let mods = ids(&["test.a", "test.b", "test.c", "test.d"]);
+
+    let mut rules = UserRules::default();
+    rules.set(
+        "test.a",
+        LoadRules {
+            load_bottom: true,
+            ..Default::default()
+        },
+    );
+    rules.set(
+        "test.d",
+        LoadRules {
+            load_top: true,
+            ..Default::default()
+        },
+    );
+    rules.set(
+        "test.b",
+        LoadRules {
+            load_after: pids(&["test.c"]),
+            ..Default::default()
+        },
+    );
+    rules.apply(&mod_list);
+
+    assert_eq!(
+        sort(&mods, &mod_list.mods).unwrap(),
+        ids(&["test.d", "test.c", "test.b", "test.a"])
+    );
+}
+
+#[test]
+fn user_rules_beat_community_rules() {
+    let mut mmd = mod_meta_data("test.a");
+    mmd.community_rules = Some(LoadRules {
+        load_bottom: true,
+        ..Default::default()
+    });
+    assert!(mmd.load_bottom());
+
+    mmd.user_rules = Some(LoadRules {
+        load_top: true,
+        ..Default::default()
+    });
+    assert!(mmd.load_top());
+    assert!(!mmd.load_bottom());
+}
+
+#[test]
+fn validate_with_user_rules() {
+    let mod_list = ModList::from(["test.a", "test.b", "test.c"].map(mod_meta_data));
+    let mods = ids(&["test.a", "test.b", "test.c"]);
+    assert!(validate(&mod_list.mods, &mods).is_ok());
+
+    let mut rules = UserRules::default();
+    rules.set(
+        "test.a",
+        LoadRules {
+            load_after: pids(&["test.b"]),
+            incompatible_with: pids(&["test.c"]),
+            ..Default::default()
+        },
+    );
+    rules.apply(&mod_list);
+
+    let res = validate(&mod_list.mods, &mods);
+    assert_eq!(
+        res.diagnostics()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec![
+            String::from("test.a should be loaded after test.b"),
+            String::from("test.a is incompatible with test.c"),
+        ]
+    );
+}
+
+#[test]
+fn from_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    let mods = dir.path().join("mods");
+    let about = mods.join("A").join("About");
+    fs::create_dir_all(&about).unwrap();
+    fs::write(
+        about.join("About.xml"),
+        "<ModMetaData><packageId>test.a</packageId></ModMetaData>",
+    )
+    .unwrap();
+    let rules_path = dir.path().join("userRules.json");
+    fs::write(
+        &rules_path,
+        r#"{"rules": {"Test.A": {"loadTop": {"value": true}}}}"#,
+    )
+    .unwrap();
+
+    let mut settings = Settings::default();
+    settings.folders.local_mods = Some(mods);
+    settings.user_rules = Some(rules_path.clone());
+
+    let settings_path = dir.path().join("settings.ini");
+    settings.save_to(&settings_path).unwrap();
+    let settings = Settings::from_path(&settings_path).unwrap();
+    assert_eq!(settings.user_rules, Some(rules_path.clone()));
+    assert_eq!(UserRules::path(&settings), Some(rules_path));
+
+    let mod_list = ModList::try_from(&settings).unwrap();
+    assert!(mod_list.mods.lock().unwrap()["test.a"].load_top());
+}
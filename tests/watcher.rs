@@ -0,0 +1,114 @@
+use rimrs::watcher::{classify, WatchEvent, Watcher};
+use std::{
+    collections::HashSet,
+    fs,
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn classify_paths() {
+    let mod_dirs = [PathBuf::from("/steam/294100"), PathBuf::from("/game/Mods")];
+    let mods_config = PathBuf::from("/config/ModsConfig.xml");
+
+    assert_eq!(
+        classify(&mods_config, &mod_dirs, &mods_config),
+        Some(WatchEvent::ModsConfigChanged)
+    );
+    assert_eq!(
+        classify(
+            &PathBuf::from("/steam/294100/2009463077/About/About.xml"),
+            &mod_dirs,
+            &mods_config
+        ),
+        Some(WatchEvent::ModChanged(PathBuf::from(
+            "/steam/294100/2009463077"
+        )))
+    );
+    assert_eq!(
+        classify(&PathBuf::from("/game/Mods/Local"), &mod_dirs, &mods_config),
+        Some(WatchEvent::ModChanged(PathBuf::from("/game/Mods/Local")))
+    );
+
+    for unrelated in [
+        "/config/ModsConfig.xml.rimrs-tmp",
+        "/config/Prefs.xml",
+        "/game/Mods",
+        "/game/Data/Core",
+    ] {
+        assert_eq!(
+            classify(&PathBuf::from(unrelated), &mod_dirs, &mods_config),
+            None,
+            "{unrelated}"
+        );
+    }
+}
+
+/// Waits until the watcher has seen all of `expected`.
+fn wait_for(watcher: &Watcher, expected: &[WatchEvent]) {
+    let mut seen = HashSet::new();
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(10) {
+        seen.extend(watcher.try_iter());
+        if expected.iter().all(|event| seen.contains(event)) {
+            return;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    panic!("expected {expected:?}, saw {seen:?}");
+}
+
+fn watches_changes(make: impl FnOnce(&[PathBuf], &std::path::Path) -> Watcher) {
+    let dir = tempfile::tempdir().unwrap();
+    let mods = dir.path().join("mods");
+    let config = dir.path().join("config");
+    fs::create_dir_all(mods.join("harmony/About")).unwrap();
+    fs::create_dir_all(&config).unwrap();
+    let mods_config = config.join("ModsConfig.xml");
+    fs::write(&mods_config, "<ModsConfigData />").unwrap();
+
+    let watcher = make(std::slice::from_ref(&mods), &mods_config);
+
+    // new mod
+    fs::create_dir_all(mods.join("hugslib/About")).unwrap();
+    fs::write(mods.join("hugslib/About/About.xml"), "<ModMetaData />").unwrap();
+    // changed mod
+    fs::write(mods.join("harmony/About/About.xml"), "<ModMetaData />").unwrap();
+    // the game saving its config
+    fs::write(
+        &mods_config,
+        "<ModsConfigData><activeMods /></ModsConfigData>",
+    )
+    .unwrap();
+
+    wait_for(
+        &watcher,
+        &[
+            WatchEvent::ModChanged(mods.join("hugslib")),
+            WatchEvent::ModChanged(mods.join("harmony")),
+            WatchEvent::ModsConfigChanged,
+        ],
+    );
+
+    // removed mod
+    fs::remove_dir_all(mods.join("harmony")).unwrap();
+    wait_for(&watcher, &[WatchEvent::ModChanged(mods.join("harmony"))]);
+}
+
+#[test]
+fn watches_natively() {
+    watches_changes(|mod_dirs, mods_config| Watcher::new(mod_dirs, mods_config).unwrap());
+}
+
+#[test]
+fn watches_by_polling() {
+    watches_changes(|mod_dirs, mods_config| {
+        let watcher = Watcher::polling(mod_dirs, mods_config, Duration::from_millis(50)).unwrap();
+        assert!(watcher.is_polling());
+        // polling compares modification times to the second,
+        // so changes made within a second of the first scan would be missed
+        thread::sleep(Duration::from_millis(1100));
+        watcher
+    });
+}
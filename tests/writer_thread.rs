@@ -0,0 +1,123 @@
+use rimrs::{
+    widgets::{SaveState, SaveStatus},
+    writer_thread::{self, Message, WriteError},
+    ModsConfig, ModsConfigFile, CHANGED_ACTIVE_MODS,
+};
+use std::{
+    fs,
+    sync::{
+        mpsc::{sync_channel, SyncSender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+fn ids(mods: &[&str]) -> Vec<String> {
+    mods.iter().map(|m| String::from(*m)).collect()
+}
+
+fn spawn() -> (SyncSender<Message>, JoinHandle<()>) {
+    let (tx, rx) = sync_channel(8);
+    (tx, writer_thread::spawn(rx))
+}
+
+fn wait_for(save_status: &mut SaveStatus, active_mods: &[String]) {
+    for _ in 0..500 {
+        save_status.poll(active_mods);
+        if !save_status.is_pending() {
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("writer thread didn't reply");
+}
+
+#[test]
+fn survives_bad_requests() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("ModsConfig.xml");
+    let (tx, handle) = spawn();
+
+    let res = writer_thread::request(&tx, Message::Save).unwrap().wait();
+    assert!(matches!(res, Err(WriteError::NoDestination)));
+
+    tx.send(Message::SetDestination(file.clone())).unwrap();
+    let res = writer_thread::request(&tx, Message::Save).unwrap().wait();
+    assert!(matches!(res, Err(WriteError::NoModsConfig)));
+
+    let res = writer_thread::request(&tx, |reply_tx| {
+        Message::WriteTo(dir.path().join("export.xml"), ids(&["a"]), reply_tx)
+    })
+    .unwrap()
+    .wait();
+    assert!(matches!(res, Err(WriteError::NoModsConfig)));
+
+    let bad_path = dir.path().join("missing/export.txt");
+    let res = writer_thread::request(&tx, |reply_tx| {
+        Message::WriteFile(bad_path.clone(), String::from("a"), reply_tx)
+    })
+    .unwrap()
+    .wait();
+    assert!(matches!(res, Err(WriteError::IOError(path, _)) if path == bad_path));
+
+    // still running
+    tx.send(Message::SetModsConfig(Arc::new(ModsConfigFile::from(
+        ModsConfig::default(),
+    ))))
+    .unwrap();
+    tx.send(Message::SetActiveMods(ids(&["a", "b"]))).unwrap();
+    let res = writer_thread::request(&tx, Message::Save).unwrap().wait();
+    assert_eq!(res.unwrap(), file);
+    assert_eq!(
+        ModsConfig::try_from(file.as_path()).unwrap().activeMods,
+        ids(&["a", "b"])
+    );
+
+    // stops once nothing can send to it
+    drop(tx);
+    handle.join().unwrap();
+}
+
+#[test]
+fn save_status() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("ModsConfig.xml");
+    let (tx, handle) = spawn();
+    let mut save_status = SaveStatus::default();
+    let active = ids(&["a"]);
+
+    // no destination yet, so it fails and the mods are still unsaved
+    CHANGED_ACTIVE_MODS.set();
+    save_status.save(&tx, active.clone());
+    assert_eq!(save_status.state(), &SaveState::Pending);
+    assert_eq!(save_status.label(), "Saving...");
+    wait_for(&mut save_status, &active);
+    assert!(matches!(save_status.state(), SaveState::Failed(_)));
+    assert!(CHANGED_ACTIVE_MODS.check());
+
+    tx.send(Message::SetDestination(file.clone())).unwrap();
+    tx.send(Message::SetModsConfig(Arc::new(ModsConfigFile::from(
+        ModsConfig::default(),
+    ))))
+    .unwrap();
+
+    // the active mods changed while saving, so they're still unsaved
+    save_status.save(&tx, active.clone());
+    wait_for(&mut save_status, &ids(&["a", "b"]));
+    assert_eq!(save_status.state(), &SaveState::Saved);
+    assert!(CHANGED_ACTIVE_MODS.check());
+    assert_eq!(save_status.label(), "Save");
+
+    save_status.save(&tx, active.clone());
+    wait_for(&mut save_status, &active);
+    assert_eq!(save_status.state(), &SaveState::Saved);
+    assert!(!CHANGED_ACTIVE_MODS.check());
+    assert_eq!(save_status.label(), "Saved");
+    assert_eq!(save_status.take_saved(), Some(active.clone()));
+    assert_eq!(save_status.take_saved(), None);
+    assert!(fs::read_to_string(&file).unwrap().contains("<li>a</li>"));
+
+    tx.send(Message::Stop).unwrap();
+    handle.join().unwrap();
+}
@@ -31,6 +31,66 @@ fn test_parse_about() {
     assert!(!mmd.modDependencies.unwrap().is_empty());
 }
 
+#[test]
+fn parse_about_by_version() {
+    let bytes: &[u8] = br#"<?xml version="1.0" encoding="utf-8"?>
+<ModMetaData>
+    <packageId>Some.Mod</packageId>
+    <description>Generic</description>
+    <descriptionsByVersion>
+        <v1.3>Old</v1.3>
+    </descriptionsByVersion>
+    <modDependenciesByVersion>
+        <v1.4>
+            <li>
+                <packageId>Brrainz.Harmony</packageId>
+                <displayName>Harmony</displayName>
+            </li>
+        </v1.4>
+    </modDependenciesByVersion>
+    <loadAfterByVersion>
+        <v1.3>
+            <li>A</li>
+        </v1.3>
+        <v1.4>
+            <li>B</li>
+            <li>C</li>
+        </v1.4>
+    </loadAfterByVersion>
+    <loadBeforeByVersion>
+        <v1.4>
+            <li>D</li>
+        </v1.4>
+    </loadBeforeByVersion>
+    <incompatibleWithByVersion>
+        <v1.4>
+            <li>E</li>
+        </v1.4>
+    </incompatibleWithByVersion>
+</ModMetaData>"#;
+    let mmd = parse_about(bytes).unwrap();
+
+    assert_eq!(mmd.description.unwrap(), "Generic");
+    assert_eq!(mmd.descriptionsByVersion.unwrap()["1.3"], "Old");
+    assert!(mmd.modDependencies.is_none());
+
+    let deps = &mmd.modDependenciesByVersion.unwrap()["1.4"];
+    assert_eq!(deps.len(), 1);
+    let dep = deps.iter().next().unwrap();
+    assert_eq!(dep.packageId.as_deref(), Some("brrainz.harmony"));
+    assert_eq!(dep.displayName.as_deref(), Some("Harmony"));
+
+    let load_after = mmd.loadAfterByVersion.unwrap();
+    assert_eq!(load_after["1.3"].len(), 1);
+    assert!(load_after["1.3"].contains("a"));
+    assert_eq!(load_after["1.4"].len(), 2);
+    assert!(load_after["1.4"].contains("b"));
+    assert!(load_after["1.4"].contains("c"));
+
+    assert!(mmd.loadBeforeByVersion.unwrap()["1.4"].contains("d"));
+    assert!(mmd.incompatibleWithByVersion.unwrap()["1.4"].contains("e"));
+}
+
 #[test]
 fn parse_mods_config_from_path() {
     let appdata = env::var("APPDATA").unwrap();